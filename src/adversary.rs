@@ -0,0 +1,372 @@
+/**
+ * Cheating provers
+ * Each adversary here runs against the ordinary verifier of any sigma protocol
+ * and tries to be accepted without following the protocol with a valid
+ * witness. Measuring how often they get through and comparing that with the
+ * protocol's soundness error, 2^-soundness_bits over all rounds, checks the
+ * bound the driver reports against what an attacker actually achieves.
+ *
+ * - ChallengeGuesser runs the simulator for a challenge it guessed in advance
+ * - Replayer sends a recorded honest transcript again
+ * - MalformedCommitment flips a bit of an honest commitment and answers as if it had not
+ * - WrongWitness follows the protocol honestly with a witness that does not fit
+ */
+use rand::rngs::ThreadRng;
+use rand::{CryptoRng, Rng, RngCore};
+
+use crate::{
+    run_protocol, Challenge, Decode, Encode, HonestProver, Proof, Prover, SigmaProtocol, Simulator,
+    Verifier,
+};
+
+// A prover that bets on one challenge and is accepted only if the verifier picks it
+pub struct ChallengeGuesser<P: SigmaProtocol> {
+    statement: P::Statement,
+    response: Option<P::Response>,
+    rng: ThreadRng,
+}
+
+impl<P: Simulator> ChallengeGuesser<P> {
+    pub fn new(statement: P::Statement) -> Self {
+        ChallengeGuesser {
+            statement,
+            response: None,
+            rng: rand::thread_rng(),
+        }
+    }
+}
+
+impl<P: Simulator> Prover for ChallengeGuesser<P> {
+    type Commitment = P::Commitment;
+    type Challenge = P::Challenge;
+    type Response = P::Response;
+
+    fn commit(&mut self) -> P::Commitment {
+        let guess = P::Challenge::challenge(&mut self.rng);
+        let (commitment, response) = P::simulate(&self.statement, &guess, &mut self.rng);
+        self.response = Some(response);
+        commitment
+    }
+
+    fn respond(&mut self, _challenge: &P::Challenge) -> P::Response {
+        self.response.take().expect("respond called before commit")
+    }
+}
+
+// A prover that replays recorded transcripts in turn, whatever the challenge
+pub struct Replayer<P: SigmaProtocol> {
+    transcripts: Vec<Proof<P::Commitment, P::Challenge, P::Response>>,
+    next: usize,
+}
+
+impl<P: SigmaProtocol> Replayer<P> {
+    pub fn new(transcripts: Vec<Proof<P::Commitment, P::Challenge, P::Response>>) -> Self {
+        assert!(!transcripts.is_empty(), "nothing to replay");
+        Replayer {
+            transcripts,
+            next: 0,
+        }
+    }
+
+    // Eavesdrop on `count` sessions of an honest prover
+    pub fn record(
+        statement: &P::Statement,
+        witness: &P::Witness,
+        count: usize,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> Self {
+        Self::new(
+            (0..count)
+                .map(|_| crate::simulator::real_transcript::<P>(statement, witness, rng))
+                .collect(),
+        )
+    }
+}
+
+impl<P: SigmaProtocol> Prover for Replayer<P> {
+    type Commitment = P::Commitment;
+    type Challenge = P::Challenge;
+    type Response = P::Response;
+
+    fn commit(&mut self) -> P::Commitment {
+        self.transcripts[self.next].commitment.clone()
+    }
+
+    fn respond(&mut self, _challenge: &P::Challenge) -> P::Response {
+        let response = self.transcripts[self.next].response.clone();
+        self.next = (self.next + 1) % self.transcripts.len();
+        response
+    }
+}
+
+// A prover with the right witness whose commitment is corrupted on the way out
+pub struct MalformedCommitment<P: SigmaProtocol> {
+    prover: HonestProver<P>,
+    rng: ThreadRng,
+}
+
+impl<P: SigmaProtocol> MalformedCommitment<P> {
+    pub fn new(statement: P::Statement, witness: P::Witness) -> Self {
+        MalformedCommitment {
+            prover: HonestProver::new(statement, witness),
+            rng: rand::thread_rng(),
+        }
+    }
+}
+
+impl<P: SigmaProtocol> Prover for MalformedCommitment<P> {
+    type Commitment = P::Commitment;
+    type Challenge = P::Challenge;
+    type Response = P::Response;
+
+    fn commit(&mut self) -> P::Commitment {
+        corrupt(&self.prover.commit(), &mut self.rng)
+    }
+
+    fn respond(&mut self, challenge: &P::Challenge) -> P::Response {
+        self.prover.respond(challenge)
+    }
+}
+
+// A prover that follows the protocol with a witness that does not satisfy the statement
+pub struct WrongWitness<P: SigmaProtocol> {
+    prover: HonestProver<P>,
+}
+
+impl<P: SigmaProtocol> WrongWitness<P> {
+    pub fn new(statement: P::Statement, witness: P::Witness) -> Self {
+        WrongWitness {
+            prover: HonestProver::new(statement, witness),
+        }
+    }
+}
+
+impl<P: SigmaProtocol> Prover for WrongWitness<P> {
+    type Commitment = P::Commitment;
+    type Challenge = P::Challenge;
+    type Response = P::Response;
+
+    fn commit(&mut self) -> P::Commitment {
+        self.prover.commit()
+    }
+
+    fn respond(&mut self, challenge: &P::Challenge) -> P::Response {
+        self.prover.respond(challenge)
+    }
+}
+
+// Flip one random bit of a value's encoding, retrying until the result still decodes
+fn corrupt<T: Encode + Decode>(value: &T, rng: &mut impl Rng) -> T {
+    let bytes = value.to_bytes();
+    assert!(!bytes.is_empty(), "an empty encoding cannot be corrupted");
+    for _ in 0..10_000 {
+        let mut flipped = bytes.clone();
+        let bit = rng.gen_range(0..8 * flipped.len());
+        flipped[bit / 8] ^= 1 << (bit % 8);
+        if let Some(malformed) = T::from_bytes(&flipped) {
+            return malformed;
+        }
+    }
+    panic!("no single bit flip of this encoding decodes");
+}
+
+// How often an adversary was accepted, next to the bound soundness promises
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AttackReport {
+    // The number of complete runs of the protocol
+    pub trials: usize,
+    // The number of runs the verifier accepted
+    pub accepted: usize,
+    // The soundness error of one run as a power of two, 2^-soundness_bits
+    pub soundness_bits: u32,
+}
+
+impl AttackReport {
+    // The measured acceptance rate
+    pub fn rate(&self) -> f64 {
+        self.accepted as f64 / self.trials as f64
+    }
+
+    // The theoretical acceptance probability of one run
+    pub fn bound(&self) -> f64 {
+        2f64.powi(-(self.soundness_bits.min(i32::MAX as u32) as i32))
+    }
+
+    // Whether the measured count is no more than five standard deviations above what the
+    // bound allows; an adversary beating the bound by that much breaks soundness
+    pub fn is_within_bound(&self) -> bool {
+        let expected = self.trials as f64 * self.bound();
+        let deviation = (expected * (1.0 - self.bound())).sqrt();
+        self.accepted as f64 <= expected + 5.0 * deviation
+    }
+}
+
+// Run the protocol `trials` times at the given security level and count acceptances
+pub fn measure<A, V>(
+    adversary: &mut A,
+    verifier: &mut V,
+    security: u32,
+    trials: usize,
+) -> AttackReport
+where
+    A: Prover,
+    V: Verifier<Commitment = A::Commitment, Challenge = A::Challenge, Response = A::Response>,
+{
+    assert!(trials > 0, "cannot measure zero trials");
+    let mut accepted = 0;
+    let mut soundness_bits = 0;
+    for _ in 0..trials {
+        let result = run_protocol(adversary, verifier, security);
+        soundness_bits = result.soundness_bits;
+        if result.is_accepted() {
+            accepted += 1;
+        }
+    }
+    AttackReport {
+        trials,
+        accepted,
+        soundness_bits,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Group, HonestVerifier, PrimeField};
+    use curve25519_dalek::ristretto::RistrettoPoint;
+    use curve25519_dalek::scalar::Scalar;
+
+    type G = RistrettoPoint;
+
+    // The textbook Schnorr protocol with one-bit challenges, whose soundness error of
+    // 1/2 per round is large enough to measure
+    struct BinarySchnorr;
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Bit(bool);
+
+    impl Encode for Bit {
+        fn encode(&self, out: &mut Vec<u8>) {
+            out.push(self.0 as u8);
+        }
+    }
+
+    impl Decode for Bit {
+        fn decode(input: &mut &[u8]) -> Option<Self> {
+            match crate::encoding::take(input, 1)? {
+                [0] => Some(Bit(false)),
+                [1] => Some(Bit(true)),
+                _ => None,
+            }
+        }
+    }
+
+    impl Challenge for Bit {
+        const BITS: u32 = 1;
+
+        fn challenge<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+            Bit(rng.gen())
+        }
+
+        fn from_uniform_bytes(bytes: &[u8; 64]) -> Self {
+            Bit(bytes[0] & 1 == 1)
+        }
+    }
+
+    fn bit_scalar(bit: &Bit) -> Scalar {
+        Scalar::from_u64(bit.0 as u64)
+    }
+
+    impl SigmaProtocol for BinarySchnorr {
+        const DOMAIN: &'static [u8] = b"zero-knowledge-proof/test/binary-schnorr";
+
+        type Statement = G;
+        type Witness = Scalar;
+        type Commitment = G;
+        type State = Scalar;
+        type Challenge = Bit;
+        type Response = Scalar;
+
+        fn commit<R: RngCore + CryptoRng>(_y: &G, _x: &Scalar, rng: &mut R) -> (G, Scalar) {
+            let nonce = Scalar::random(rng);
+            (G::generator().scalar_mul(&nonce), nonce)
+        }
+
+        fn respond(_y: &G, x: &Scalar, nonce: Scalar, challenge: &Bit) -> Scalar {
+            nonce + bit_scalar(challenge) * x
+        }
+
+        fn verify(y: &G, t: &G, challenge: &Bit, s: &Scalar) -> bool {
+            G::generator().scalar_mul(s) == *t + y.scalar_mul(&bit_scalar(challenge))
+        }
+    }
+
+    impl Simulator for BinarySchnorr {
+        fn simulate<R: RngCore + CryptoRng>(y: &G, challenge: &Bit, rng: &mut R) -> (G, Scalar) {
+            let s = Scalar::random(rng);
+            (
+                G::generator().scalar_mul(&s) - y.scalar_mul(&bit_scalar(challenge)),
+                s,
+            )
+        }
+    }
+
+    const TRIALS: usize = 2000;
+
+    // The measured rate must sit within five standard deviations of the bound, from both sides
+    fn assert_matches_bound(report: &AttackReport) {
+        let expected = report.trials as f64 * report.bound();
+        let deviation = (expected * (1.0 - report.bound())).sqrt();
+        assert!(report.is_within_bound(), "{:?}", report);
+        assert!(
+            report.accepted as f64 >= expected - 5.0 * deviation,
+            "{:?}",
+            report
+        );
+    }
+
+    #[test]
+    fn guesser_succeeds_exactly_as_often_as_the_bound_allows() {
+        let (_, y) = crate::schnorr::keypair::<G, _>(&mut rand::thread_rng());
+        let mut verifier = HonestVerifier::<BinarySchnorr>::new(y);
+        for security in [1, 2, 3] {
+            let mut guesser = ChallengeGuesser::<BinarySchnorr>::new(y);
+            let report = measure(&mut guesser, &mut verifier, security, TRIALS);
+            assert_eq!(report.soundness_bits, security);
+            assert_matches_bound(&report);
+        }
+    }
+
+    #[test]
+    fn replay_succeeds_only_when_the_challenge_repeats() {
+        let mut rng = rand::thread_rng();
+        let (x, y) = crate::schnorr::keypair::<G, _>(&mut rng);
+        let mut replayer = Replayer::<BinarySchnorr>::record(&y, &x, 16, &mut rng);
+        let mut verifier = HonestVerifier::<BinarySchnorr>::new(y);
+        assert_matches_bound(&measure(&mut replayer, &mut verifier, 2, TRIALS));
+    }
+
+    #[test]
+    fn malformed_commitments_and_wrong_witnesses_never_pass() {
+        let mut rng = rand::thread_rng();
+        let (x, y) = crate::schnorr::keypair::<G, _>(&mut rng);
+        let mut verifier = HonestVerifier::<BinarySchnorr>::new(y);
+
+        let mut malformed = MalformedCommitment::<BinarySchnorr>::new(y, x);
+        let report = measure(&mut malformed, &mut verifier, 1, 200);
+        assert_eq!(report.accepted, 0);
+
+        // With the wrong x, only the challenge-0 rounds pass, and those need no witness
+        let mut wrong = WrongWitness::<BinarySchnorr>::new(y, Scalar::random(&mut rng));
+        assert_matches_bound(&measure(&mut wrong, &mut verifier, 1, TRIALS));
+    }
+
+    #[test]
+    fn corrupted_values_differ_and_decode() {
+        let mut rng = rand::thread_rng();
+        let point = G::generator();
+        for _ in 0..20 {
+            assert_ne!(corrupt(&point, &mut rng), point);
+        }
+    }
+}
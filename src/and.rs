@@ -0,0 +1,191 @@
+/**
+ * AND-composition of sigma protocols
+ * Proves knowledge of a witness for A and one for B in a single run. Both
+ * protocols commit side by side, answer the same challenge, and the proof is
+ * accepted only if both transcripts verify. Two accepting transcripts with
+ * different challenges are two for each branch, so both witnesses can be
+ * extracted, and a cheater still has to guess the one challenge.
+ *
+ * Nest the combinator, And<A, And<B, C>>, to conjoin more statements.
+ */
+use std::marker::PhantomData;
+
+use rand::{CryptoRng, RngCore};
+
+use crate::{Extractor, SigmaProtocol, Simulator, Transcript};
+
+// A proof of knowledge of witnesses for both A's statement and B's
+pub struct And<A, B>(PhantomData<(A, B)>);
+
+impl<A, B> SigmaProtocol for And<A, B>
+where
+    A: SigmaProtocol,
+    B: SigmaProtocol<Challenge = A::Challenge>,
+{
+    const DOMAIN: &'static [u8] = b"zero-knowledge-proof/and/v1";
+    // A cheater only needs to fool the weaker of the two
+    const SOUNDNESS_BITS: u32 = if A::SOUNDNESS_BITS < B::SOUNDNESS_BITS {
+        A::SOUNDNESS_BITS
+    } else {
+        B::SOUNDNESS_BITS
+    };
+
+    type Statement = (A::Statement, B::Statement);
+    type Witness = (A::Witness, B::Witness);
+    type Commitment = (A::Commitment, B::Commitment);
+    type State = (A::State, B::State);
+    type Challenge = A::Challenge;
+    type Response = (A::Response, B::Response);
+
+    fn commit<R: RngCore + CryptoRng>(
+        statement: &Self::Statement,
+        witness: &Self::Witness,
+        rng: &mut R,
+    ) -> (Self::Commitment, Self::State) {
+        let (left, left_state) = A::commit(&statement.0, &witness.0, rng);
+        let (right, right_state) = B::commit(&statement.1, &witness.1, rng);
+        ((left, right), (left_state, right_state))
+    }
+
+    fn respond(
+        statement: &Self::Statement,
+        witness: &Self::Witness,
+        state: Self::State,
+        challenge: &A::Challenge,
+    ) -> Self::Response {
+        (
+            A::respond(&statement.0, &witness.0, state.0, challenge),
+            B::respond(&statement.1, &witness.1, state.1, challenge),
+        )
+    }
+
+    fn verify(
+        statement: &Self::Statement,
+        commitment: &Self::Commitment,
+        challenge: &A::Challenge,
+        response: &Self::Response,
+    ) -> bool {
+        A::verify(&statement.0, &commitment.0, challenge, &response.0)
+            && B::verify(&statement.1, &commitment.1, challenge, &response.1)
+    }
+
+    fn append_domain(transcript: &mut Transcript) {
+        transcript.append_message(b"protocol", Self::DOMAIN);
+        A::append_domain(transcript);
+        B::append_domain(transcript);
+    }
+}
+
+// Simulate both halves for the same challenge
+impl<A, B> Simulator for And<A, B>
+where
+    A: Simulator,
+    B: Simulator<Challenge = A::Challenge>,
+{
+    fn simulate<R: RngCore + CryptoRng>(
+        statement: &Self::Statement,
+        challenge: &A::Challenge,
+        rng: &mut R,
+    ) -> (Self::Commitment, Self::Response) {
+        let (left, left_response) = A::simulate(&statement.0, challenge, rng);
+        let (right, right_response) = B::simulate(&statement.1, challenge, rng);
+        ((left, right), (left_response, right_response))
+    }
+}
+
+impl<A, B> Extractor for And<A, B>
+where
+    A: Extractor,
+    B: Extractor<Challenge = A::Challenge>,
+{
+    fn extract(
+        statement: &Self::Statement,
+        commitment: &Self::Commitment,
+        first: (&A::Challenge, &Self::Response),
+        second: (&A::Challenge, &Self::Response),
+    ) -> Option<Self::Witness> {
+        Some((
+            A::extract(
+                &statement.0,
+                &commitment.0,
+                (first.0, &first.1 .0),
+                (second.0, &second.1 .0),
+            )?,
+            B::extract(
+                &statement.1,
+                &commitment.1,
+                (first.0, &first.1 .1),
+                (second.0, &second.1 .1),
+            )?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schnorr::{self, Schnorr};
+    use crate::{
+        run_protocol, ChaumPedersen, DleqStatement, Group, HonestProver, HonestVerifier, Proof,
+        DEFAULT_SECURITY,
+    };
+    use curve25519_dalek::ristretto::RistrettoPoint;
+    use curve25519_dalek::scalar::Scalar;
+
+    type G = RistrettoPoint;
+    type P = And<Schnorr<G>, ChaumPedersen<G>>;
+
+    fn instance() -> ((G, DleqStatement<G>), (Scalar, Scalar)) {
+        let mut rng = rand::thread_rng();
+        let (secret, public_key) = schnorr::keypair::<G, _>(&mut rng);
+        let x = Scalar::random(&mut rng);
+        let statement = DleqStatement::new(G::generator(), G::hash_to_group(b"and", b"h"), &x);
+        ((public_key, statement), (secret, x))
+    }
+
+    #[test]
+    fn both_witnesses_convince_the_verifier() {
+        let (statement, witness) = instance();
+        let mut prover = HonestProver::<P>::new(statement, witness);
+        let mut verifier = HonestVerifier::<P>::new(statement);
+        let result = run_protocol(&mut prover, &mut verifier, DEFAULT_SECURITY);
+        assert!(result.is_accepted());
+        assert_eq!(result.rounds, 1);
+
+        let proof = Proof::prove::<P>(&statement, &witness, &mut rand::thread_rng());
+        assert!(proof.verify::<P>(&statement));
+    }
+
+    #[test]
+    fn one_witness_is_not_enough() {
+        let (statement, (secret, x)) = instance();
+        let wrong = (secret, x + Scalar::ONE);
+        let mut prover = HonestProver::<P>::new(statement, wrong);
+        let mut verifier = HonestVerifier::<P>::new(statement);
+        assert!(!run_protocol(&mut prover, &mut verifier, DEFAULT_SECURITY).is_accepted());
+
+        let proof = Proof::prove::<P>(&statement, &wrong, &mut rand::thread_rng());
+        assert!(!proof.verify::<P>(&statement));
+    }
+
+    #[test]
+    fn simulated_transcripts_verify() {
+        let mut rng = rand::thread_rng();
+        let (statement, _) = instance();
+        let challenge = Scalar::random(&mut rng);
+        let (commitment, response) = P::simulate(&statement, &challenge, &mut rng);
+        assert!(P::verify(&statement, &commitment, &challenge, &response));
+    }
+
+    #[test]
+    fn two_challenges_reveal_both_witnesses() {
+        let mut rng = rand::thread_rng();
+        let (statement, witness) = instance();
+        let (commitment, state) = P::commit(&statement, &witness, &mut rng);
+        let (c1, c2) = (Scalar::random(&mut rng), Scalar::random(&mut rng));
+        let first = P::respond(&statement, &witness, state, &c1);
+        let second = P::respond(&statement, &witness, state, &c2);
+        let extracted = P::extract(&statement, &commitment, (&c1, &first), (&c2, &second));
+        assert_eq!(extracted, Some(witness));
+    }
+}
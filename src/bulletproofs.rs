@@ -0,0 +1,643 @@
+/**
+ * Bulletproofs range proofs
+ * Proves that each of m Pedersen commitments V_j = g^v_j h^gamma_j holds a
+ * value in [0, 2^n), in one proof of 2 log2(n m) + 9 group elements and
+ * scalars, following Bünz et al., "Bulletproofs: Short Proofs for
+ * Confidential Transactions and More", section 4.
+ *
+ * The prover commits to the bits a_L of all values and to a_R = a_L - 1 in a
+ * single Pedersen vector commitment A, and to blinding vectors in S. The
+ * challenges y and z fold the conditions "every a_L is a bit" and "the bits
+ * add up to each v_j" into one inner product t(x) = <l(x), r(x)>, whose
+ * coefficients are committed in T1 and T2. After the challenge x the prover
+ * reveals t(x) and the blindings, and the inner-product argument shows l(x)
+ * and r(x) are the vectors committed in A and S.
+ *
+ * All challenges come from the crate's SHA-256 transcript. A verifier checks
+ * both equations of a proof, and any number of proofs, with one
+ * multiexponentiation by weighting every equation with a random scalar.
+ *
+ * The bits only pin a value down if 2^n does not wrap past the group order, so
+ * n must be below the order's bit length: over a 63-bit toy group a 64-bit
+ * proof says nothing, and both prover and verifier refuse it.
+ */
+pub mod inner_product;
+
+use rand::{CryptoRng, RngCore};
+
+use crate::fiat_shamir::TRANSCRIPT_LABEL;
+use crate::parity::power_of_two;
+use crate::{
+    Decode, Encode, Group, PedersenCommitment, PedersenGenerators, PedersenVectorGenerators,
+    PrimeField, Transcript,
+};
+use inner_product::{inner_product, InnerProductProof};
+
+// The name hashed into every range proof transcript, and the tag the generators come from
+pub const DOMAIN: &[u8] = b"zero-knowledge-proof/bulletproofs/v1";
+
+// Generators for range proofs over up to `capacity` bits in total
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BulletproofGenerators<G: Group> {
+    // g and h of the value commitments
+    pub pedersen: PedersenGenerators<G>,
+    // The bit vectors' generators, g_1..g_capacity then h_1..h_capacity, and their blinding generator
+    pub vector: PedersenVectorGenerators<G>,
+    // The generator of the inner product
+    pub u: G,
+}
+
+impl<G: Group> BulletproofGenerators<G> {
+    // Generators that work with commitments made under the default Pedersen generators
+    pub fn new(capacity: usize) -> Self {
+        BulletproofGenerators {
+            pedersen: PedersenGenerators::default(),
+            vector: PedersenVectorGenerators::new(DOMAIN, 2 * capacity),
+            u: G::hash_to_group(DOMAIN, b"u"),
+        }
+    }
+
+    // The number of bits, over all values, these generators can cover
+    pub fn capacity(&self) -> usize {
+        self.vector.g.len() / 2
+    }
+
+    fn g(&self, n: usize) -> &[G] {
+        &self.vector.g[..n]
+    }
+
+    fn h(&self, n: usize) -> &[G] {
+        let capacity = self.capacity();
+        &self.vector.g[capacity..capacity + n]
+    }
+}
+
+// An aggregated range proof
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RangeProof<G: Group> {
+    pub a: G,
+    pub s: G,
+    pub t1: G,
+    pub t2: G,
+    pub tau_x: G::Scalar,
+    pub mu: G::Scalar,
+    pub t_hat: G::Scalar,
+    pub inner_product: InnerProductProof<G>,
+}
+
+// Whether proofs about m values of n bits are possible with these generators,
+// and sound in this group
+fn supported<G: Group>(generators: &BulletproofGenerators<G>, bits: usize, m: usize) -> bool {
+    bits.is_power_of_two()
+        && bits <= 64
+        && (bits as u32) < G::Scalar::BITS
+        && m.is_power_of_two()
+        && bits
+            .checked_mul(m)
+            .is_some_and(|total| total <= generators.capacity())
+}
+
+// 1, x, x^2, ..., x^(n - 1)
+fn powers<F: PrimeField>(x: F, n: usize) -> Vec<F> {
+    let mut power = F::one();
+    (0..n)
+        .map(|_| {
+            let current = power;
+            power *= x;
+            current
+        })
+        .collect()
+}
+
+// The start of both proof and verification: the statement and the sizes
+fn begin<G: Group>(
+    transcript: &mut Transcript,
+    commitments: &[PedersenCommitment<G>],
+    bits: usize,
+) {
+    transcript.append_message(b"protocol", DOMAIN);
+    transcript.append_u64(b"bits", bits as u64);
+    transcript.append_u64(b"values", commitments.len() as u64);
+    for commitment in commitments {
+        transcript.append(b"V", commitment);
+    }
+}
+
+impl<G: Group> RangeProof<G> {
+    // Prove that every value is below 2^bits. Returns the proof and the commitments
+    // it is about, or None if a value is out of range or the sizes are unsupported:
+    // bits must be a power of two up to 64 and below the bit length of the group
+    // order, and the number of values a power of two.
+    pub fn prove(
+        generators: &BulletproofGenerators<G>,
+        values: &[u64],
+        blindings: &[G::Scalar],
+        bits: usize,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> Option<(Self, Vec<PedersenCommitment<G>>)> {
+        Self::prove_with_transcript(
+            generators,
+            &mut Transcript::new(TRANSCRIPT_LABEL),
+            values,
+            blindings,
+            bits,
+            rng,
+        )
+    }
+
+    // Prove inside a caller's transcript, binding the proof to whatever it already holds
+    pub fn prove_with_transcript(
+        generators: &BulletproofGenerators<G>,
+        transcript: &mut Transcript,
+        values: &[u64],
+        blindings: &[G::Scalar],
+        bits: usize,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> Option<(Self, Vec<PedersenCommitment<G>>)> {
+        let m = values.len();
+        if !supported(generators, bits, m)
+            || blindings.len() != m
+            || values.iter().any(|&v| bits < 64 && v >> bits != 0)
+        {
+            return None;
+        }
+        let n = bits * m;
+        let commitments: Vec<PedersenCommitment<G>> = values
+            .iter()
+            .zip(blindings)
+            .map(|(v, gamma)| generators.pedersen.commit(&G::Scalar::from_u64(*v), gamma))
+            .collect();
+        begin(transcript, &commitments, bits);
+
+        // a_L holds the bits of every value, a_R = a_L - 1
+        let one = G::Scalar::one();
+        let a_l: Vec<G::Scalar> = values
+            .iter()
+            .flat_map(|v| (0..bits).map(move |i| G::Scalar::from_u64((v >> i) & 1)))
+            .collect();
+        let a_r: Vec<G::Scalar> = a_l.iter().map(|bit| *bit - one).collect();
+        let s_l: Vec<G::Scalar> = (0..n).map(|_| G::Scalar::random(rng)).collect();
+        let s_r: Vec<G::Scalar> = (0..n).map(|_| G::Scalar::random(rng)).collect();
+
+        let vector = PedersenVectorGenerators {
+            g: [generators.g(n), generators.h(n)].concat(),
+            h: generators.vector.h,
+        };
+        let alpha = G::Scalar::random(rng);
+        let rho = G::Scalar::random(rng);
+        let a = vector
+            .commit(&[a_l.as_slice(), &a_r].concat(), &alpha)
+            .point;
+        let s = vector.commit(&[s_l.as_slice(), &s_r].concat(), &rho).point;
+        transcript.append(b"A", &a);
+        transcript.append(b"S", &s);
+        let y: G::Scalar = transcript.challenge(b"y");
+        let z: G::Scalar = transcript.challenge(b"z");
+
+        // l(X) = a_L - z + s_L X
+        // r(X) = y^i (a_R + z + s_R X) + z^(2 + j) 2^k, for bit k of value j at i = j bits + k
+        let y_powers = powers(y, n);
+        let z_powers = powers(z, m + 3);
+        let l0: Vec<G::Scalar> = a_l.iter().map(|a| *a - z).collect();
+        let r0: Vec<G::Scalar> = (0..n)
+            .map(|i| {
+                y_powers[i] * (a_r[i] + z)
+                    + z_powers[2 + i / bits] * power_of_two::<G::Scalar>(i % bits)
+            })
+            .collect();
+        let r1: Vec<G::Scalar> = (0..n).map(|i| y_powers[i] * s_r[i]).collect();
+
+        // t(X) = <l(X), r(X)> = t0 + t1 X + t2 X^2
+        let t1 = inner_product(&l0, &r1) + inner_product(&s_l, &r0);
+        let t2 = inner_product(&s_l, &r1);
+        let tau1 = G::Scalar::random(rng);
+        let tau2 = G::Scalar::random(rng);
+        let t1_point = generators.pedersen.commit(&t1, &tau1).point;
+        let t2_point = generators.pedersen.commit(&t2, &tau2).point;
+        transcript.append(b"T1", &t1_point);
+        transcript.append(b"T2", &t2_point);
+        let x: G::Scalar = transcript.challenge(b"x");
+
+        let l: Vec<G::Scalar> = l0.iter().zip(&s_l).map(|(l, s)| *l + *s * x).collect();
+        let r: Vec<G::Scalar> = r0.iter().zip(&r1).map(|(r, s)| *r + *s * x).collect();
+        let t_hat = inner_product(&l, &r);
+        let tau_x = blindings
+            .iter()
+            .enumerate()
+            .fold(tau2 * x.square() + tau1 * x, |acc, (j, gamma)| {
+                acc + z_powers[2 + j] * *gamma
+            });
+        let mu = alpha + rho * x;
+        transcript.append(b"tau_x", &tau_x);
+        transcript.append(b"mu", &mu);
+        transcript.append(b"t_hat", &t_hat);
+        let w: G::Scalar = transcript.challenge(b"w");
+
+        // The argument runs over h_i y^-i, which turns r(x) back into plain coefficients
+        let y_inverse = y.inverse().expect("a hashed challenge is nonzero");
+        let h_prime: Vec<G> = generators
+            .h(n)
+            .iter()
+            .zip(powers(y_inverse, n))
+            .map(|(h, y)| h.scalar_mul(&y))
+            .collect();
+        let inner_product = InnerProductProof::prove(
+            transcript,
+            generators.g(n).to_vec(),
+            h_prime,
+            generators.u.scalar_mul(&w),
+            l,
+            r,
+        );
+
+        Some((
+            RangeProof {
+                a,
+                s,
+                t1: t1_point,
+                t2: t2_point,
+                tau_x,
+                mu,
+                t_hat,
+                inner_product,
+            },
+            commitments,
+        ))
+    }
+
+    // Check that every commitment holds a value below 2^bits
+    pub fn verify(
+        &self,
+        generators: &BulletproofGenerators<G>,
+        commitments: &[PedersenCommitment<G>],
+        bits: usize,
+    ) -> bool {
+        Self::batch_verify(
+            generators,
+            &[(self, commitments)],
+            bits,
+            &mut rand::thread_rng(),
+        )
+    }
+
+    // Check a proof made with prove_with_transcript against a transcript in the same state
+    pub fn verify_with_transcript(
+        &self,
+        generators: &BulletproofGenerators<G>,
+        transcript: &mut Transcript,
+        commitments: &[PedersenCommitment<G>],
+        bits: usize,
+    ) -> bool {
+        let mut check = Check::new(generators);
+        self.add_to(
+            &mut check,
+            generators,
+            transcript,
+            commitments,
+            bits,
+            &mut rand::thread_rng(),
+        ) && check.holds()
+    }
+
+    // Check many proofs at once, each against its own commitments, with one
+    // multiexponentiation. A batch with any invalid proof is rejected, except
+    // with negligible probability over the verifier's random weights.
+    pub fn batch_verify(
+        generators: &BulletproofGenerators<G>,
+        proofs: &[(&Self, &[PedersenCommitment<G>])],
+        bits: usize,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> bool {
+        let mut check = Check::new(generators);
+        !proofs.is_empty()
+            && proofs.iter().all(|(proof, commitments)| {
+                proof.add_to(
+                    &mut check,
+                    generators,
+                    &mut Transcript::new(TRANSCRIPT_LABEL),
+                    commitments,
+                    bits,
+                    rng,
+                )
+            })
+            && check.holds()
+    }
+
+    // Add this proof's two verification equations, under random weights, to a batch.
+    // False if the proof is malformed and cannot hold whatever the rest of the batch is.
+    fn add_to(
+        &self,
+        check: &mut Check<G>,
+        generators: &BulletproofGenerators<G>,
+        transcript: &mut Transcript,
+        commitments: &[PedersenCommitment<G>],
+        bits: usize,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> bool {
+        let m = commitments.len();
+        if !supported(generators, bits, m) {
+            return false;
+        }
+        let n = bits * m;
+        begin(transcript, commitments, bits);
+        transcript.append(b"A", &self.a);
+        transcript.append(b"S", &self.s);
+        let y: G::Scalar = transcript.challenge(b"y");
+        let z: G::Scalar = transcript.challenge(b"z");
+        transcript.append(b"T1", &self.t1);
+        transcript.append(b"T2", &self.t2);
+        let x: G::Scalar = transcript.challenge(b"x");
+        transcript.append(b"tau_x", &self.tau_x);
+        transcript.append(b"mu", &self.mu);
+        transcript.append(b"t_hat", &self.t_hat);
+        let w: G::Scalar = transcript.challenge(b"w");
+        let (scalars, y_inverse) = match (
+            self.inner_product.verification_scalars(n, transcript),
+            y.inverse(),
+        ) {
+            (Some(scalars), Some(y_inverse)) => (scalars, y_inverse),
+            _ => return false,
+        };
+
+        // One weight for the proof, and another to keep its two equations apart
+        let weight = G::Scalar::random(rng);
+        let c = weight * G::Scalar::random(rng);
+
+        // t_hat g + tau_x h = sum z^(2 + j) V_j + delta(y, z) g + x T1 + x^2 T2, where
+        // delta(y, z) = (z - z^2) <1, y^n> - sum z^(3 + j) <1, 2^bits>
+        let y_powers = powers(y, n);
+        let z_powers = powers(z, m + 3);
+        let y_sum = y_powers.iter().fold(G::Scalar::zero(), |acc, y| acc + *y);
+        let two_sum = power_of_two::<G::Scalar>(bits) - G::Scalar::one();
+        let delta = (z - z.square()) * y_sum
+            - (0..m).fold(G::Scalar::zero(), |acc, j| acc + z_powers[3 + j]) * two_sum;
+        check.g += c * (self.t_hat - delta);
+        check.h += c * self.tau_x;
+        for (j, commitment) in commitments.iter().enumerate() {
+            check.push(commitment.point, -(c * z_powers[2 + j]));
+        }
+        check.push(self.t1, -(c * x));
+        check.push(self.t2, -(c * x.square()));
+
+        // A + x S - z <1, g> + <z + z^(2 + j) 2^k y^-i, h> - mu h_blind + t_hat w u
+        // + sum(x_j^2 L_j + x_j^-2 R_j) = <a s, g> + <b s' y^-i, h> + a b w u
+        let ipp = &self.inner_product;
+        check.push(self.a, weight);
+        check.push(self.s, weight * x);
+        check.blinding -= weight * self.mu;
+        check.u += weight * w * (self.t_hat - ipp.a * ipp.b);
+        for (l, x_squared) in ipp.left.iter().zip(&scalars.challenges_squared) {
+            check.push(*l, weight * *x_squared);
+        }
+        for (r, x_inverse_squared) in ipp.right.iter().zip(&scalars.inverses_squared) {
+            check.push(*r, weight * *x_inverse_squared);
+        }
+        let mut y_inverse_power = G::Scalar::one();
+        for i in 0..n {
+            check.g_vec[i] -= weight * (z + ipp.a * scalars.s[i]);
+            let h_scalar = z_powers[2 + i / bits] * power_of_two::<G::Scalar>(i % bits)
+                - ipp.b * scalars.s[n - 1 - i];
+            check.h_vec[i] += weight * (z + h_scalar * y_inverse_power);
+            y_inverse_power *= y_inverse;
+        }
+        true
+    }
+}
+
+// The scalars of a batch of verification equations whose sum must be the identity,
+// gathered per fixed generator and per proof-specific point
+struct Check<'a, G: Group> {
+    generators: &'a BulletproofGenerators<G>,
+    g: G::Scalar,
+    h: G::Scalar,
+    blinding: G::Scalar,
+    u: G::Scalar,
+    g_vec: Vec<G::Scalar>,
+    h_vec: Vec<G::Scalar>,
+    points: Vec<G>,
+    scalars: Vec<G::Scalar>,
+}
+
+impl<'a, G: Group> Check<'a, G> {
+    fn new(generators: &'a BulletproofGenerators<G>) -> Self {
+        let capacity = generators.capacity();
+        Check {
+            generators,
+            g: G::Scalar::zero(),
+            h: G::Scalar::zero(),
+            blinding: G::Scalar::zero(),
+            u: G::Scalar::zero(),
+            g_vec: vec![G::Scalar::zero(); capacity],
+            h_vec: vec![G::Scalar::zero(); capacity],
+            points: Vec::new(),
+            scalars: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, point: G, scalar: G::Scalar) {
+        self.points.push(point);
+        self.scalars.push(scalar);
+    }
+
+    fn holds(self) -> bool {
+        let capacity = self.generators.capacity();
+        let mut points = vec![
+            self.generators.pedersen.g,
+            self.generators.pedersen.h,
+            self.generators.vector.h,
+            self.generators.u,
+        ];
+        let mut scalars = vec![self.g, self.h, self.blinding, self.u];
+        points.extend(self.generators.g(capacity));
+        points.extend(self.generators.h(capacity));
+        points.extend(self.points);
+        scalars.extend(self.g_vec);
+        scalars.extend(self.h_vec);
+        scalars.extend(self.scalars);
+        G::multi_scalar_mul(&points, &scalars) == G::identity()
+    }
+}
+
+impl<G: Group> Encode for RangeProof<G> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.a.encode(out);
+        self.s.encode(out);
+        self.t1.encode(out);
+        self.t2.encode(out);
+        self.tau_x.encode(out);
+        self.mu.encode(out);
+        self.t_hat.encode(out);
+        self.inner_product.encode(out);
+    }
+}
+
+impl<G: Group> Decode for RangeProof<G> {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(RangeProof {
+            a: G::decode(input)?,
+            s: G::decode(input)?,
+            t1: G::decode(input)?,
+            t2: G::decode(input)?,
+            tau_x: G::Scalar::decode(input)?,
+            mu: G::Scalar::decode(input)?,
+            t_hat: G::Scalar::decode(input)?,
+            inner_product: InnerProductProof::decode(input)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use curve25519_dalek::ristretto::RistrettoPoint;
+    use curve25519_dalek::scalar::Scalar;
+    use rand::Rng;
+
+    type G = RistrettoPoint;
+
+    fn blindings(m: usize) -> Vec<Scalar> {
+        let mut rng = rand::thread_rng();
+        (0..m).map(|_| Scalar::random(&mut rng)).collect()
+    }
+
+    #[test]
+    fn single_values_at_every_width() {
+        let generators = BulletproofGenerators::<G>::new(64);
+        let mut rng = rand::thread_rng();
+        for bits in [8, 16, 32, 64] {
+            let value = if bits == 64 {
+                u64::MAX
+            } else {
+                (1 << bits) - 1
+            };
+            let (proof, commitments) =
+                RangeProof::prove(&generators, &[value], &blindings(1), bits, &mut rng).unwrap();
+            assert!(proof.verify(&generators, &commitments, bits));
+            assert_eq!(
+                proof.inner_product.left.len(),
+                bits.trailing_zeros() as usize
+            );
+        }
+    }
+
+    #[test]
+    fn aggregated_values_share_one_proof() {
+        let generators = BulletproofGenerators::<G>::new(4 * 32);
+        let mut rng = rand::thread_rng();
+        let values: Vec<u64> = (0..4).map(|_| u64::from(rng.gen::<u32>())).collect();
+        let (proof, commitments) =
+            RangeProof::prove(&generators, &values, &blindings(4), 32, &mut rng).unwrap();
+        assert!(proof.verify(&generators, &commitments, 32));
+        // log2(4 * 32) = 7 rounds, one more than for a single value
+        assert_eq!(proof.inner_product.left.len(), 7);
+
+        // The commitments cannot be reordered or swapped for others
+        let mut swapped = commitments.clone();
+        swapped.swap(0, 1);
+        assert!(!proof.verify(&generators, &swapped, 32));
+        assert!(!proof.verify(&generators, &commitments[..2], 32));
+        assert!(!proof.verify(&generators, &commitments, 16));
+    }
+
+    #[test]
+    fn values_out_of_range_are_refused_and_forgeries_rejected() {
+        let generators = BulletproofGenerators::<G>::new(64);
+        let mut rng = rand::thread_rng();
+        assert!(RangeProof::prove(&generators, &[256], &blindings(1), 8, &mut rng).is_none());
+        assert!(RangeProof::prove(&generators, &[1, 2, 3], &blindings(3), 8, &mut rng).is_none());
+        assert!(RangeProof::prove(&generators, &[1, 2], &blindings(2), 64, &mut rng).is_none());
+
+        // A proof for 255 says nothing about a commitment to 256
+        let blinding = blindings(1);
+        let (proof, _) = RangeProof::prove(&generators, &[255], &blinding, 8, &mut rng).unwrap();
+        let other = generators
+            .pedersen
+            .commit(&Scalar::from(256u64), &blinding[0]);
+        assert!(!proof.verify(&generators, &[other], 8));
+
+        let mut tampered = proof.clone();
+        tampered.t_hat += Scalar::ONE;
+        let (_, commitments) =
+            RangeProof::prove(&generators, &[255], &blinding, 8, &mut rng).unwrap();
+        assert!(proof.verify(&generators, &commitments, 8));
+        assert!(!tampered.verify(&generators, &commitments, 8));
+    }
+
+    #[test]
+    fn widths_that_can_wrap_the_group_order_are_refused() {
+        use crate::group::modp::{ModpGroup, Toy64};
+        type S = ModpGroup<Toy64>;
+        let generators = BulletproofGenerators::<S>::new(64);
+        let mut rng = rand::thread_rng();
+        let blinding = vec![PrimeField::random(&mut rng)];
+
+        // The order is just under 2^63: 32 bits are fine, 64 could wrap
+        assert!(RangeProof::prove(&generators, &[u64::MAX], &blinding, 64, &mut rng).is_none());
+        let (proof, commitments) =
+            RangeProof::prove(&generators, &[u64::from(u32::MAX)], &blinding, 32, &mut rng)
+                .unwrap();
+        assert!(proof.verify(&generators, &commitments, 32));
+        assert!(!proof.verify(&generators, &commitments, 64));
+    }
+
+    #[test]
+    fn batches_fail_if_any_proof_does() {
+        let generators = BulletproofGenerators::<G>::new(2 * 16);
+        let mut rng = rand::thread_rng();
+        let proofs: Vec<_> = (0..3)
+            .map(|_| {
+                let values = [u64::from(rng.gen::<u16>()), u64::from(rng.gen::<u16>())];
+                RangeProof::prove(&generators, &values, &blindings(2), 16, &mut rng).unwrap()
+            })
+            .collect();
+        let batch: Vec<_> = proofs
+            .iter()
+            .map(|(proof, commitments)| (proof, commitments.as_slice()))
+            .collect();
+        assert!(RangeProof::batch_verify(&generators, &batch, 16, &mut rng));
+
+        let mut bad = proofs[1].0.clone();
+        bad.mu += Scalar::ONE;
+        let mut mixed = batch.clone();
+        mixed[1].0 = &bad;
+        assert!(!RangeProof::batch_verify(&generators, &mixed, 16, &mut rng));
+        assert!(!RangeProof::batch_verify(&generators, &[], 16, &mut rng));
+    }
+
+    #[test]
+    fn proof_is_bound_to_the_surrounding_transcript() {
+        let generators = BulletproofGenerators::<G>::new(8);
+        let mut rng = rand::thread_rng();
+        let mut context = Transcript::new(b"test");
+        context.append_message(b"session", b"one");
+        let (proof, commitments) = RangeProof::prove_with_transcript(
+            &generators,
+            &mut context.clone(),
+            &[7],
+            &blindings(1),
+            8,
+            &mut rng,
+        )
+        .unwrap();
+        assert!(proof.verify_with_transcript(&generators, &mut context, &commitments, 8));
+        assert!(!proof.verify(&generators, &commitments, 8));
+    }
+
+    #[test]
+    fn size_is_logarithmic() {
+        let generators = BulletproofGenerators::<G>::new(64);
+        let (proof, commitments) = RangeProof::prove(
+            &generators,
+            &[1 << 40],
+            &blindings(1),
+            64,
+            &mut rand::thread_rng(),
+        )
+        .unwrap();
+        // Four points, three scalars, 2 * 6 round points with their length prefixes, two scalars
+        let bytes = proof.to_bytes();
+        assert_eq!(bytes.len(), 4 * 32 + 3 * 32 + 2 * (8 + 6 * 32) + 2 * 32);
+        let decoded = RangeProof::<G>::from_bytes(&bytes).unwrap();
+        assert!(decoded.verify(&generators, &commitments, 64));
+    }
+}
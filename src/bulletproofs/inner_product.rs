@@ -0,0 +1,267 @@
+/**
+ * The Bulletproofs inner-product argument
+ * For generator vectors g, h of length n (a power of two) and a point u, the
+ * prover shows it knows vectors a, b with P = <a, g> + <b, h> + <a, b> u.
+ * Each round halves the vectors: the prover sends the cross terms L and R,
+ * the transcript answers with a challenge x, and both sides fold
+ *
+ *   a' = a_lo x + a_hi / x,   g' = g_lo / x + g_hi x,
+ *   b' = b_lo / x + b_hi x,   h' = h_lo x + h_hi / x,
+ *   P' = x^2 L + P + R / x^2,
+ *
+ * until a and b are single scalars. The proof is 2 log2(n) points and two
+ * scalars. The verifier never folds the generators one round at a time: g'
+ * after the last round is <s, g> for s_i the product of x_j or 1 / x_j
+ * according to the bits of i, so the whole check is one multiexponentiation.
+ */
+use crate::{Decode, Encode, Group, PrimeField, Transcript};
+
+// The cross terms of every round and the two folded scalars
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InnerProductProof<G: Group> {
+    pub left: Vec<G>,
+    pub right: Vec<G>,
+    pub a: G::Scalar,
+    pub b: G::Scalar,
+}
+
+// The challenges of a proof, as the verifier needs them
+pub struct VerificationScalars<F> {
+    // x_j^2 and x_j^-2 for every round, the weights of L_j and R_j
+    pub challenges_squared: Vec<F>,
+    pub inverses_squared: Vec<F>,
+    // s_i, so the folded g is <s, g>; the folded h is <s reversed, h>
+    pub s: Vec<F>,
+}
+
+pub fn inner_product<F: PrimeField>(a: &[F], b: &[F]) -> F {
+    a.iter().zip(b).fold(F::zero(), |acc, (a, b)| acc + *a * *b)
+}
+
+impl<G: Group> InnerProductProof<G> {
+    // Prove knowledge of a and b for P = <a, g> + <b, h> + <a, b> u. P itself is not
+    // sent: the caller binds it, or what it is made of, into the transcript first.
+    pub fn prove(
+        transcript: &mut Transcript,
+        mut g: Vec<G>,
+        mut h: Vec<G>,
+        u: G,
+        mut a: Vec<G::Scalar>,
+        mut b: Vec<G::Scalar>,
+    ) -> Self {
+        let mut n = g.len();
+        assert!(
+            n.is_power_of_two(),
+            "the vectors must have a power-of-two length"
+        );
+        assert!(h.len() == n && a.len() == n && b.len() == n);
+        transcript.append_u64(b"n", n as u64);
+
+        let mut left = Vec::new();
+        let mut right = Vec::new();
+        while n > 1 {
+            n /= 2;
+            let (a_lo, a_hi) = a.split_at(n);
+            let (b_lo, b_hi) = b.split_at(n);
+            let (g_lo, g_hi) = g.split_at(n);
+            let (h_lo, h_hi) = h.split_at(n);
+
+            let l = G::multi_scalar_mul(g_hi, a_lo)
+                + G::multi_scalar_mul(h_lo, b_hi)
+                + u.scalar_mul(&inner_product(a_lo, b_hi));
+            let r = G::multi_scalar_mul(g_lo, a_hi)
+                + G::multi_scalar_mul(h_hi, b_lo)
+                + u.scalar_mul(&inner_product(a_hi, b_lo));
+            transcript.append(b"L", &l);
+            transcript.append(b"R", &r);
+            left.push(l);
+            right.push(r);
+
+            let x: G::Scalar = transcript.challenge(b"x");
+            let x_inv = x.inverse().expect("a hashed challenge is nonzero");
+            a = fold(a_lo, a_hi, x, x_inv);
+            b = fold(b_lo, b_hi, x_inv, x);
+            g = fold_points(g_lo, g_hi, x_inv, x);
+            h = fold_points(h_lo, h_hi, x, x_inv);
+        }
+
+        InnerProductProof {
+            left,
+            right,
+            a: a[0],
+            b: b[0],
+        }
+    }
+
+    // Replay the transcript for a proof about vectors of length n; None if the
+    // proof has the wrong number of rounds or a challenge is not invertible
+    pub fn verification_scalars(
+        &self,
+        n: usize,
+        transcript: &mut Transcript,
+    ) -> Option<VerificationScalars<G::Scalar>> {
+        // The round count comes from the proof, so compare it with log2(n) rather
+        // than shifting by it
+        let rounds = self.left.len();
+        if !n.is_power_of_two()
+            || n.trailing_zeros() as usize != rounds
+            || self.right.len() != rounds
+        {
+            return None;
+        }
+        transcript.append_u64(b"n", n as u64);
+
+        let mut challenges = Vec::with_capacity(rounds);
+        let mut inverses = Vec::with_capacity(rounds);
+        for (l, r) in self.left.iter().zip(&self.right) {
+            transcript.append(b"L", l);
+            transcript.append(b"R", r);
+            let x: G::Scalar = transcript.challenge(b"x");
+            inverses.push(x.inverse()?);
+            challenges.push(x);
+        }
+
+        // Round j splits on bit rounds - 1 - j of the index
+        let s = (0..n)
+            .map(|i| {
+                (0..rounds).fold(G::Scalar::one(), |acc, j| {
+                    if (i >> (rounds - 1 - j)) & 1 == 1 {
+                        acc * challenges[j]
+                    } else {
+                        acc * inverses[j]
+                    }
+                })
+            })
+            .collect();
+
+        Some(VerificationScalars {
+            challenges_squared: challenges.iter().map(PrimeField::square).collect(),
+            inverses_squared: inverses.iter().map(PrimeField::square).collect(),
+            s,
+        })
+    }
+
+    // Check the proof against P = <a, g> + <b, h> + <a, b> u
+    pub fn verify(&self, transcript: &mut Transcript, g: &[G], h: &[G], u: G, p: G) -> bool {
+        let n = g.len();
+        if h.len() != n {
+            return false;
+        }
+        let scalars = match self.verification_scalars(n, transcript) {
+            Some(scalars) => scalars,
+            None => return false,
+        };
+
+        // P + sum(x^2 L + x^-2 R) - <a s, g> - <b s', h> - a b u = 0
+        let mut points = vec![p, u];
+        let mut weights = vec![G::Scalar::one(), -(self.a * self.b)];
+        points.extend(self.left.iter().chain(&self.right).chain(g).chain(h));
+        weights.extend(scalars.challenges_squared);
+        weights.extend(scalars.inverses_squared);
+        weights.extend(scalars.s.iter().map(|s| -(self.a * *s)));
+        weights.extend(scalars.s.iter().rev().map(|s| -(self.b * *s)));
+        G::multi_scalar_mul(&points, &weights) == G::identity()
+    }
+}
+
+fn fold<F: PrimeField>(lo: &[F], hi: &[F], x_lo: F, x_hi: F) -> Vec<F> {
+    lo.iter()
+        .zip(hi)
+        .map(|(lo, hi)| *lo * x_lo + *hi * x_hi)
+        .collect()
+}
+
+fn fold_points<G: Group>(lo: &[G], hi: &[G], x_lo: G::Scalar, x_hi: G::Scalar) -> Vec<G> {
+    lo.iter()
+        .zip(hi)
+        .map(|(lo, hi)| G::multi_scalar_mul(&[*lo, *hi], &[x_lo, x_hi]))
+        .collect()
+}
+
+impl<G: Group> Encode for InnerProductProof<G> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.left.encode(out);
+        self.right.encode(out);
+        self.a.encode(out);
+        self.b.encode(out);
+    }
+}
+
+impl<G: Group> Decode for InnerProductProof<G> {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(InnerProductProof {
+            left: Vec::decode(input)?,
+            right: Vec::decode(input)?,
+            a: G::Scalar::decode(input)?,
+            b: G::Scalar::decode(input)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PedersenVectorGenerators;
+    use curve25519_dalek::ristretto::RistrettoPoint;
+    use curve25519_dalek::scalar::Scalar;
+
+    type G = RistrettoPoint;
+
+    fn instance(n: usize) -> (Vec<G>, Vec<G>, G, Vec<Scalar>, Vec<Scalar>, G) {
+        let mut rng = rand::thread_rng();
+        let generators = PedersenVectorGenerators::<G>::new(b"inner product test", 2 * n);
+        let (g, h) = generators.g.split_at(n);
+        let u = G::hash_to_group(b"inner product test", b"u");
+        let a: Vec<Scalar> = (0..n).map(|_| Scalar::random(&mut rng)).collect();
+        let b: Vec<Scalar> = (0..n).map(|_| Scalar::random(&mut rng)).collect();
+        let p = G::multi_scalar_mul(g, &a)
+            + G::multi_scalar_mul(h, &b)
+            + u.scalar_mul(&inner_product(&a, &b));
+        (g.to_vec(), h.to_vec(), u, a, b, p)
+    }
+
+    #[test]
+    fn proves_and_verifies_at_every_size() {
+        for n in [1, 2, 8, 64] {
+            let (g, h, u, a, b, p) = instance(n);
+            let proof = InnerProductProof::prove(
+                &mut Transcript::new(b"test"),
+                g.clone(),
+                h.clone(),
+                u,
+                a,
+                b,
+            );
+            assert_eq!(proof.left.len(), n.trailing_zeros() as usize);
+            assert!(proof.verify(&mut Transcript::new(b"test"), &g, &h, u, p));
+        }
+    }
+
+    #[test]
+    fn rejects_a_wrong_point_or_a_tampered_proof() {
+        let (g, h, u, a, b, p) = instance(16);
+        let proof =
+            InnerProductProof::prove(&mut Transcript::new(b"test"), g.clone(), h.clone(), u, a, b);
+        assert!(!proof.verify(&mut Transcript::new(b"test"), &g, &h, u, p + u));
+        assert!(!proof.verify(&mut Transcript::new(b"other"), &g, &h, u, p));
+
+        let mut tampered = proof.clone();
+        tampered.a += Scalar::ONE;
+        assert!(!tampered.verify(&mut Transcript::new(b"test"), &g, &h, u, p));
+        let mut dropped = proof;
+        dropped.left.pop();
+        dropped.right.pop();
+        assert!(!dropped.verify(&mut Transcript::new(b"test"), &g, &h, u, p));
+    }
+
+    #[test]
+    fn round_trips_through_bytes() {
+        let (g, h, u, a, b, p) = instance(8);
+        let proof =
+            InnerProductProof::prove(&mut Transcript::new(b"test"), g.clone(), h.clone(), u, a, b);
+        let bytes = proof.to_bytes();
+        assert_eq!(bytes.len(), 2 * (8 + 3 * 32) + 2 * 32);
+        let decoded = InnerProductProof::<G>::from_bytes(&bytes).unwrap();
+        assert!(decoded.verify(&mut Transcript::new(b"test"), &g, &h, u, p));
+    }
+}
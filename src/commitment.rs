@@ -0,0 +1,131 @@
+/**
+ * A hiding hash commitment
+ * The committed value is hashed together with a fresh random nonce and a
+ * domain tag, and the full SHA-256 digest is kept as the commitment
+ */
+use rand::{CryptoRng, RngCore};
+
+use crate::{Commitment, Transcript};
+
+// The domain tag used when no other tag is given
+pub const DEFAULT_DOMAIN: &[u8] = b"zero-knowledge-proof/hash-commitment/v1";
+
+// The number of random bytes mixed into every commitment
+pub const NONCE_LEN: usize = 32;
+
+// A commitment to a value: a SHA-256 transcript over the domain, nonce and value
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct HashCommitment {
+    digest: [u8; 32],
+}
+
+// Everything needed to open a hash commitment
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HashOpening {
+    pub value: u64,
+    pub nonce: [u8; NONCE_LEN],
+}
+
+impl HashCommitment {
+    // Commit to a value under a caller-chosen domain tag
+    pub fn commit_with_domain<R: RngCore + CryptoRng>(
+        domain: &[u8],
+        value: u64,
+        rng: &mut R,
+    ) -> (Self, HashOpening) {
+        let mut nonce = [0u8; NONCE_LEN];
+        rng.fill_bytes(&mut nonce);
+        (
+            Self::commit_with_nonce(domain, value, &nonce),
+            HashOpening { value, nonce },
+        )
+    }
+
+    // Commit to a value with a given nonce; the nonce must never be reused
+    pub fn commit_with_nonce(domain: &[u8], value: u64, nonce: &[u8; NONCE_LEN]) -> Self {
+        HashCommitment {
+            digest: digest(domain, value, nonce),
+        }
+    }
+
+    // Check an opening against this commitment under the given domain tag
+    pub fn open_with_domain(&self, domain: &[u8], opening: &HashOpening) -> Option<u64> {
+        if digest(domain, opening.value, &opening.nonce) == self.digest {
+            Some(opening.value)
+        } else {
+            None
+        }
+    }
+
+    // The raw 32-byte digest
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.digest
+    }
+}
+
+impl Commitment for HashCommitment {
+    type Value = u64;
+    type Randomness = [u8; NONCE_LEN];
+    type Opening = HashOpening;
+
+    fn commit<R: RngCore + CryptoRng>(value: u64, rng: &mut R) -> (Self, HashOpening) {
+        Self::commit_with_domain(DEFAULT_DOMAIN, value, rng)
+    }
+
+    fn commit_with(value: &u64, nonce: &[u8; NONCE_LEN]) -> Self {
+        Self::commit_with_nonce(DEFAULT_DOMAIN, *value, nonce)
+    }
+
+    fn open(&self, opening: &HashOpening) -> Option<u64> {
+        self.open_with_domain(DEFAULT_DOMAIN, opening)
+    }
+}
+
+fn digest(domain: &[u8], value: u64, nonce: &[u8; NONCE_LEN]) -> [u8; 32] {
+    // The transcript frames every field, so no tag is a prefix of another
+    let mut transcript = Transcript::new(b"zero-knowledge-proof/hash-commitment");
+    transcript.append_message(b"domain", domain);
+    transcript.append_message(b"nonce", nonce);
+    transcript.append_u64(b"value", value);
+    let mut digest = [0u8; 32];
+    transcript.challenge_bytes(b"digest", &mut digest);
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opens_to_committed_value() {
+        let mut rng = rand::thread_rng();
+        let (commitment, opening) = HashCommitment::commit(42, &mut rng);
+        assert_eq!(commitment.open(&opening), Some(42));
+    }
+
+    #[test]
+    fn rejects_wrong_value_nonce_or_domain() {
+        let mut rng = rand::thread_rng();
+        let (commitment, opening) = HashCommitment::commit(42, &mut rng);
+
+        let wrong_value = HashOpening {
+            value: 43,
+            ..opening
+        };
+        assert_eq!(commitment.open(&wrong_value), None);
+
+        let mut wrong_nonce = opening;
+        wrong_nonce.nonce[0] ^= 1;
+        assert_eq!(commitment.open(&wrong_nonce), None);
+
+        assert_eq!(commitment.open_with_domain(b"other", &opening), None);
+    }
+
+    #[test]
+    fn same_value_gives_different_commitments() {
+        let mut rng = rand::thread_rng();
+        let (first, _) = HashCommitment::commit(7, &mut rng);
+        let (second, _) = HashCommitment::commit(7, &mut rng);
+        assert_ne!(first, second);
+    }
+}
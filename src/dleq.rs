@@ -0,0 +1,227 @@
+/**
+ * Chaum-Pedersen proof of discrete-log equality
+ * The prover knows x with A = g^x and B = h^x. It commits to (g^r, h^r),
+ * receives a challenge c and answers s = r + c x; the verifier checks both
+ * g^s = g^r A^c and h^s = h^r B^c. With g the generator this proves
+ * (g, h, A, B) is a Diffie-Hellman tuple, and with h an ElGamal ephemeral key
+ * it proves a decryption share was computed with the right secret key.
+ */
+use std::marker::PhantomData;
+
+use rand::{CryptoRng, RngCore};
+
+use crate::{Decode, Encode, Extractor, Group, PrimeField, SigmaProtocol, Simulator};
+
+// The claim log_g(a) = log_h(b)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DleqStatement<G> {
+    pub g: G,
+    pub h: G,
+    pub a: G,
+    pub b: G,
+}
+
+impl<G: Group> DleqStatement<G> {
+    // The statement for exponent x over bases g and h
+    pub fn new(g: G, h: G, x: &G::Scalar) -> Self {
+        DleqStatement {
+            g,
+            h,
+            a: g.scalar_mul(x),
+            b: h.scalar_mul(x),
+        }
+    }
+}
+
+// The bases are part of the statement, so a proof for one pair of bases says nothing about another
+impl<G: Group> Encode for DleqStatement<G> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.g.encode(out);
+        self.h.encode(out);
+        self.a.encode(out);
+        self.b.encode(out);
+    }
+}
+
+impl<G: Group> Decode for DleqStatement<G> {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(DleqStatement {
+            g: G::decode(input)?,
+            h: G::decode(input)?,
+            a: G::decode(input)?,
+            b: G::decode(input)?,
+        })
+    }
+}
+
+// The prover's first message, the same nonce under both bases
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DleqCommitment<G> {
+    pub g_nonce: G,
+    pub h_nonce: G,
+}
+
+impl<G: Group> Encode for DleqCommitment<G> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.g_nonce.encode(out);
+        self.h_nonce.encode(out);
+    }
+}
+
+impl<G: Group> Decode for DleqCommitment<G> {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(DleqCommitment {
+            g_nonce: G::decode(input)?,
+            h_nonce: G::decode(input)?,
+        })
+    }
+}
+
+// The Chaum-Pedersen protocol; the witness is the shared exponent
+pub struct ChaumPedersen<G>(PhantomData<G>);
+
+impl<G: Group> SigmaProtocol for ChaumPedersen<G> {
+    const DOMAIN: &'static [u8] = b"zero-knowledge-proof/chaum-pedersen/v1";
+
+    type Statement = DleqStatement<G>;
+    type Witness = G::Scalar;
+    type Commitment = DleqCommitment<G>;
+    type State = G::Scalar;
+    type Challenge = G::Scalar;
+    type Response = G::Scalar;
+
+    fn commit<R: RngCore + CryptoRng>(
+        statement: &DleqStatement<G>,
+        _x: &G::Scalar,
+        rng: &mut R,
+    ) -> (DleqCommitment<G>, G::Scalar) {
+        let nonce = G::Scalar::random(rng);
+        let commitment = DleqCommitment {
+            g_nonce: statement.g.scalar_mul(&nonce),
+            h_nonce: statement.h.scalar_mul(&nonce),
+        };
+        (commitment, nonce)
+    }
+
+    fn respond(
+        _statement: &DleqStatement<G>,
+        x: &G::Scalar,
+        nonce: G::Scalar,
+        challenge: &G::Scalar,
+    ) -> G::Scalar {
+        nonce + *challenge * *x
+    }
+
+    fn verify(
+        statement: &DleqStatement<G>,
+        commitment: &DleqCommitment<G>,
+        challenge: &G::Scalar,
+        response: &G::Scalar,
+    ) -> bool {
+        statement.g.scalar_mul(response) == commitment.g_nonce + statement.a.scalar_mul(challenge)
+            && statement.h.scalar_mul(response)
+                == commitment.h_nonce + statement.b.scalar_mul(challenge)
+    }
+}
+
+// Pick the response first and solve for both nonces
+impl<G: Group> Simulator for ChaumPedersen<G> {
+    fn simulate<R: RngCore + CryptoRng>(
+        statement: &DleqStatement<G>,
+        challenge: &G::Scalar,
+        rng: &mut R,
+    ) -> (DleqCommitment<G>, G::Scalar) {
+        let response = G::Scalar::random(rng);
+        let commitment = DleqCommitment {
+            g_nonce: statement.g.scalar_mul(&response) - statement.a.scalar_mul(challenge),
+            h_nonce: statement.h.scalar_mul(&response) - statement.b.scalar_mul(challenge),
+        };
+        (commitment, response)
+    }
+}
+
+// The same extraction as Schnorr: x = (s - s') / (c - c')
+impl<G: Group> Extractor for ChaumPedersen<G> {
+    fn extract(
+        _statement: &DleqStatement<G>,
+        _commitment: &DleqCommitment<G>,
+        first: (&G::Scalar, &G::Scalar),
+        second: (&G::Scalar, &G::Scalar),
+    ) -> Option<G::Scalar> {
+        Some((*first.1 - *second.1) * (*first.0 - *second.0).inverse()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{run_protocol, HonestProver, HonestVerifier, Proof, DEFAULT_SECURITY};
+    use curve25519_dalek::ristretto::RistrettoPoint;
+    use curve25519_dalek::scalar::Scalar;
+
+    type G = RistrettoPoint;
+    type P = ChaumPedersen<G>;
+
+    // A Diffie-Hellman tuple (g, g^y, g^x, g^xy) and its witness x
+    fn dh_tuple() -> (DleqStatement<G>, Scalar) {
+        let mut rng = rand::thread_rng();
+        let (x, y) = (Scalar::random(&mut rng), Scalar::random(&mut rng));
+        let g = G::generator();
+        (DleqStatement::new(g, g.scalar_mul(&y), &x), x)
+    }
+
+    #[test]
+    fn interactive_proof_of_a_dh_tuple() {
+        let (statement, x) = dh_tuple();
+        let mut prover = HonestProver::<P>::new(statement, x);
+        let mut verifier = HonestVerifier::<P>::new(statement);
+        assert!(run_protocol(&mut prover, &mut verifier, DEFAULT_SECURITY).is_accepted());
+    }
+
+    #[test]
+    fn non_interactive_proof_of_a_dh_tuple() {
+        let mut rng = rand::thread_rng();
+        let (statement, x) = dh_tuple();
+        let proof = Proof::prove::<P>(&statement, &x, &mut rng);
+        assert!(proof.verify::<P>(&statement));
+        assert_eq!(Proof::from_bytes(&proof.to_bytes()), Some(proof.clone()));
+
+        let swapped = DleqStatement {
+            g: statement.h,
+            h: statement.g,
+            a: statement.b,
+            b: statement.a,
+        };
+        assert!(!proof.verify::<P>(&swapped));
+    }
+
+    #[test]
+    fn unequal_logs_are_rejected() {
+        let mut rng = rand::thread_rng();
+        let (mut statement, x) = dh_tuple();
+        // B = h^(x + 1) while A = g^x
+        statement.b += statement.h;
+        let mut prover = HonestProver::<P>::new(statement, x);
+        let mut verifier = HonestVerifier::<P>::new(statement);
+        assert!(!run_protocol(&mut prover, &mut verifier, DEFAULT_SECURITY).is_accepted());
+        assert!(!Proof::prove::<P>(&statement, &x, &mut rng).verify::<P>(&statement));
+    }
+
+    #[test]
+    fn simulated_transcripts_verify_and_two_challenges_reveal_x() {
+        let mut rng = rand::thread_rng();
+        let (statement, x) = dh_tuple();
+        let challenge = Scalar::random(&mut rng);
+        let (commitment, response) = P::simulate(&statement, &challenge, &mut rng);
+        assert!(P::verify(&statement, &commitment, &challenge, &response));
+
+        let (commitment, nonce) = P::commit(&statement, &x, &mut rng);
+        let (c1, c2) = (Scalar::random(&mut rng), Scalar::random(&mut rng));
+        let s1 = P::respond(&statement, &x, nonce, &c1);
+        let s2 = P::respond(&statement, &x, nonce, &c2);
+        assert_eq!(
+            P::extract(&statement, &commitment, (&c1, &s1), (&c2, &s2)),
+            Some(x)
+        );
+    }
+}
@@ -0,0 +1,202 @@
+/**
+ * Canonical byte encodings
+ * Everything that goes into a transcript or a serialized proof is written in
+ * one fixed format: integers little-endian, field and group elements in their
+ * backend's fixed-length canonical form, pairs as one part after the other,
+ * fixed-size arrays item by item, vectors prefixed with their length as a
+ * u64, and optional values behind a one-byte tag
+ */
+use std::convert::TryFrom;
+
+// A trait for types with a canonical byte encoding, so they can be hashed into challenges
+pub trait Encode {
+    // Append the encoding to a buffer
+    fn encode(&self, out: &mut Vec<u8>);
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.encode(&mut out);
+        out
+    }
+}
+
+// A trait for types that can be read back from their canonical encoding
+pub trait Decode: Sized {
+    // Read a value from the front of the input and advance past it
+    fn decode(input: &mut &[u8]) -> Option<Self>;
+
+    // Decode a value that must take up the whole input
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut input = bytes;
+        let value = Self::decode(&mut input)?;
+        if input.is_empty() {
+            Some(value)
+        } else {
+            None
+        }
+    }
+}
+
+// Split `length` bytes off the front of the input
+pub fn take<'a>(input: &mut &'a [u8], length: usize) -> Option<&'a [u8]> {
+    if input.len() < length {
+        return None;
+    }
+    let (head, tail) = input.split_at(length);
+    *input = tail;
+    Some(head)
+}
+
+impl Encode for u8 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self);
+    }
+}
+
+impl Decode for u8 {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(take(input, 1)?[0])
+    }
+}
+
+impl Encode for u64 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_le_bytes());
+    }
+}
+
+impl Decode for u64 {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        let mut array = [0u8; 8];
+        array.copy_from_slice(take(input, 8)?);
+        Some(u64::from_le_bytes(array))
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (self.len() as u64).encode(out);
+        for item in self {
+            item.encode(out);
+        }
+    }
+}
+
+impl<T: Decode> Decode for Vec<T> {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        let length = u64::decode(input)?;
+        // Every item takes at least one byte, which bounds the allocation
+        if length > input.len() as u64 {
+            return None;
+        }
+        (0..length).map(|_| T::decode(input)).collect()
+    }
+}
+
+// The two halves of a composed protocol's messages, one after the other
+impl<A: Encode, B: Encode> Encode for (A, B) {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.0.encode(out);
+        self.1.encode(out);
+    }
+}
+
+impl<A: Decode, B: Decode> Decode for (A, B) {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some((A::decode(input)?, B::decode(input)?))
+    }
+}
+
+// 0 for None, 1 followed by the value for Some
+impl<T: Encode> Encode for Option<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            None => out.push(0),
+            Some(value) => {
+                out.push(1);
+                value.encode(out);
+            }
+        }
+    }
+}
+
+impl<T: Decode> Decode for Option<T> {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        match u8::decode(input)? {
+            0 => Some(None),
+            1 => Some(Some(T::decode(input)?)),
+            _ => None,
+        }
+    }
+}
+
+// The length is part of the type, so it is not written out
+impl<T: Encode, const N: usize> Encode for [T; N] {
+    fn encode(&self, out: &mut Vec<u8>) {
+        for item in self {
+            item.encode(out);
+        }
+    }
+}
+
+impl<T: Decode, const N: usize> Decode for [T; N] {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        let items = (0..N)
+            .map(|_| T::decode(input))
+            .collect::<Option<Vec<T>>>()?;
+        <[T; N]>::try_from(items).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vectors_round_trip() {
+        let values: Vec<u64> = vec![1, 2, u64::MAX];
+        let bytes = values.to_bytes();
+        assert_eq!(bytes.len(), 8 + 3 * 8);
+        assert_eq!(Vec::<u64>::from_bytes(&bytes), Some(values));
+    }
+
+    #[test]
+    fn pairs_round_trip() {
+        let pair = (7u64, vec![8u64, 9]);
+        let bytes = pair.to_bytes();
+        assert_eq!(bytes.len(), 8 + 8 + 2 * 8);
+        assert_eq!(<(u64, Vec<u64>)>::from_bytes(&bytes), Some(pair));
+        assert_eq!(<(u64, u64)>::from_bytes(&bytes[..12]), None);
+    }
+
+    #[test]
+    fn arrays_have_no_length_prefix() {
+        let array = [1u64, 2, 3];
+        let bytes = array.to_bytes();
+        assert_eq!(bytes.len(), 3 * 8);
+        assert_eq!(<[u64; 3]>::from_bytes(&bytes), Some(array));
+        assert_eq!(<[u64; 2]>::from_bytes(&bytes), None);
+        assert_eq!(<[u64; 4]>::from_bytes(&bytes), None);
+    }
+
+    #[test]
+    fn options_carry_a_tag() {
+        assert_eq!(None::<u64>.to_bytes(), vec![0]);
+        let bytes = Some(7u64).to_bytes();
+        assert_eq!(bytes.len(), 1 + 8);
+        assert_eq!(Option::<u64>::from_bytes(&bytes), Some(Some(7)));
+        assert_eq!(Option::<u64>::from_bytes(&[2]), None);
+    }
+
+    #[test]
+    fn rejects_truncated_trailing_and_oversized_input() {
+        let bytes = vec![5u64, 6].to_bytes();
+        assert_eq!(Vec::<u64>::from_bytes(&bytes[..bytes.len() - 1]), None);
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert_eq!(Vec::<u64>::from_bytes(&trailing), None);
+
+        assert_eq!(Vec::<u64>::from_bytes(&u64::MAX.to_bytes()), None);
+    }
+}
@@ -0,0 +1,65 @@
+/**
+ * Special soundness
+ * A sigma protocol is a proof of knowledge if two accepting transcripts with
+ * the same commitment and different challenges are enough to compute a
+ * witness. A prover that can answer more than one challenge for a commitment
+ * therefore knows the witness, which bounds a cheater's success by one over
+ * the number of challenges.
+ *
+ * The harness makes this executable: it runs a prover up to its commitment,
+ * clones it, answers two different challenges from the two copies, and hands
+ * both transcripts to the protocol's extractor.
+ */
+use rand::{CryptoRng, RngCore};
+
+use crate::{Challenge, Prover, SigmaProtocol};
+
+// A sigma protocol whose witness can be computed from two related transcripts
+pub trait Extractor: SigmaProtocol {
+    // Recover a witness from two accepting transcripts that share a commitment; None when
+    // the challenges are equal and the transcripts say nothing more than one of them
+    fn extract(
+        statement: &Self::Statement,
+        commitment: &Self::Commitment,
+        first: (&Self::Challenge, &Self::Response),
+        second: (&Self::Challenge, &Self::Response),
+    ) -> Option<Self::Witness>;
+}
+
+// Rewind a prover past its commitment and extract a witness from its two answers.
+// None means the prover failed to answer both challenges, as any prover without
+// the witness does except with probability 2^-SOUNDNESS_BITS.
+pub fn rewind<P, V>(
+    statement: &P::Statement,
+    prover: &mut V,
+    rng: &mut (impl RngCore + CryptoRng),
+) -> Option<P::Witness>
+where
+    P: Extractor,
+    V: Prover<Commitment = P::Commitment, Challenge = P::Challenge, Response = P::Response> + Clone,
+{
+    let commitment = prover.commit();
+    let mut rewound = prover.clone();
+
+    let first = P::Challenge::challenge(rng);
+    let second = loop {
+        let challenge = P::Challenge::challenge(rng);
+        if challenge != first {
+            break challenge;
+        }
+    };
+
+    let first_response = prover.respond(&first);
+    let second_response = rewound.respond(&second);
+    if !P::verify(statement, &commitment, &first, &first_response)
+        || !P::verify(statement, &commitment, &second, &second_response)
+    {
+        return None;
+    }
+    P::extract(
+        statement,
+        &commitment,
+        (&first, &first_response),
+        (&second, &second_response),
+    )
+}
@@ -0,0 +1,134 @@
+/**
+ * The Fiat-Shamir transform
+ * A sigma protocol becomes non-interactive by replacing the verifier's random
+ * challenge with one squeezed from a transcript holding the protocol's domain
+ * separator, the statement and the prover's commitment. The resulting proof can be checked offline by
+ * anyone holding the statement.
+ */
+use rand::{CryptoRng, RngCore};
+
+use crate::{Proof, SigmaProtocol, Transcript};
+
+// The label every standalone proof transcript starts from
+pub const TRANSCRIPT_LABEL: &[u8] = b"zero-knowledge-proof/fiat-shamir/v1";
+
+// Prove a statement without a live verifier
+pub fn prove<P: SigmaProtocol>(
+    statement: &P::Statement,
+    witness: &P::Witness,
+    rng: &mut (impl RngCore + CryptoRng),
+) -> Proof<P::Commitment, P::Challenge, P::Response> {
+    prove_with_transcript::<P>(
+        &mut Transcript::new(TRANSCRIPT_LABEL),
+        statement,
+        witness,
+        rng,
+    )
+}
+
+// Check a non-interactive proof against a statement
+pub fn verify<P: SigmaProtocol>(
+    statement: &P::Statement,
+    proof: &Proof<P::Commitment, P::Challenge, P::Response>,
+) -> bool {
+    verify_with_transcript::<P>(&mut Transcript::new(TRANSCRIPT_LABEL), statement, proof)
+}
+
+// Prove inside a caller's transcript, binding the proof to whatever it already holds
+pub fn prove_with_transcript<P: SigmaProtocol>(
+    transcript: &mut Transcript,
+    statement: &P::Statement,
+    witness: &P::Witness,
+    rng: &mut (impl RngCore + CryptoRng),
+) -> Proof<P::Commitment, P::Challenge, P::Response> {
+    let (commitment, state) = P::commit(statement, witness, rng);
+    let challenge = challenge::<P>(transcript, statement, &commitment);
+    let response = P::respond(statement, witness, state, &challenge);
+    // Anything squeezed from the transcript later depends on the whole proof
+    transcript.append(b"response", &response);
+    Proof {
+        commitment,
+        challenge,
+        response,
+    }
+}
+
+// Check a proof made with prove_with_transcript against a transcript in the same state
+pub fn verify_with_transcript<P: SigmaProtocol>(
+    transcript: &mut Transcript,
+    statement: &P::Statement,
+    proof: &Proof<P::Commitment, P::Challenge, P::Response>,
+) -> bool {
+    let challenge = challenge::<P>(transcript, statement, &proof.commitment);
+    transcript.append(b"response", &proof.response);
+    challenge == proof.challenge
+        && P::verify(statement, &proof.commitment, &challenge, &proof.response)
+}
+
+// The challenge for a commitment, squeezed after the domain, statement and commitment
+pub fn challenge<P: SigmaProtocol>(
+    transcript: &mut Transcript,
+    statement: &P::Statement,
+    commitment: &P::Commitment,
+) -> P::Challenge {
+    P::append_domain(transcript);
+    transcript.append(b"statement", statement);
+    transcript.append(b"commitment", commitment);
+    transcript.challenge(b"challenge")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parity::{self, Parity};
+    use curve25519_dalek::ristretto::RistrettoPoint;
+
+    type P = Parity<RistrettoPoint>;
+
+    #[test]
+    fn parity_proof_verifies_offline() {
+        let mut rng = rand::thread_rng();
+        let (statement, witness) = parity::commit_value(1_000_000, &mut rng);
+        let proof = prove::<P>(&statement, &witness, &mut rng);
+        assert!(verify::<P>(&statement, &proof));
+
+        let (other, _) = parity::commit_value(1_000_000, &mut rng);
+        assert!(!verify::<P>(&other, &proof));
+    }
+
+    #[test]
+    fn challenge_depends_on_statement_and_commitment() {
+        let mut rng = rand::thread_rng();
+        let (statement, witness) = parity::commit_value(8, &mut rng);
+        let (other, _) = parity::commit_value(8, &mut rng);
+        let (commitment, _) = P::commit(&statement, &witness, &mut rng);
+        let (second, _) = P::commit(&statement, &witness, &mut rng);
+
+        let derive = |statement, commitment| {
+            challenge::<P>(&mut Transcript::new(b"test"), statement, commitment)
+        };
+        let base = derive(&statement, &commitment);
+        assert_eq!(base, derive(&statement, &commitment));
+        assert_ne!(base, derive(&other, &commitment));
+        assert_ne!(base, derive(&statement, &second));
+    }
+
+    #[test]
+    fn proof_is_bound_to_the_surrounding_transcript() {
+        let mut rng = rand::thread_rng();
+        let (statement, witness) = parity::commit_value(12, &mut rng);
+        let mut context = Transcript::new(b"test");
+        context.append_message(b"session", b"one");
+        let proof =
+            prove_with_transcript::<P>(&mut context.clone(), &statement, &witness, &mut rng);
+        assert!(verify_with_transcript::<P>(
+            &mut context.clone(),
+            &statement,
+            &proof
+        ));
+
+        let mut other = Transcript::new(b"test");
+        other.append_message(b"session", b"two");
+        assert!(!verify_with_transcript::<P>(&mut other, &statement, &proof));
+    }
+}
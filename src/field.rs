@@ -0,0 +1,325 @@
+/**
+ * Prime-field arithmetic
+ * The protocols in this crate compute over the scalar field of a prime-order
+ * group. The PrimeField trait is what they rely on, and the generic methods
+ * (pow, inverse, square root, sampling) are written once against it.
+ */
+use std::fmt::Debug;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use curve25519_dalek::scalar::Scalar;
+use k256::elliptic_curve::bigint::U512;
+use k256::elliptic_curve::ops::Reduce;
+use k256::elliptic_curve::PrimeField as _;
+use rand::{CryptoRng, RngCore};
+
+use crate::encoding::take;
+use crate::{Decode, Encode};
+
+mod fr;
+
+pub use fr::Fr;
+
+// A trait for elements of a prime field
+pub trait PrimeField:
+    Sized
+    + Copy
+    + Debug
+    + Eq
+    + Encode
+    + Decode
+    + Send
+    + Sync
+    + 'static
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+{
+    // The modulus as little-endian 64-bit limbs
+    const MODULUS: &'static [u64];
+    // The bit length of the modulus
+    const BITS: u32;
+    // The length of the canonical encoding; decoding rejects anything at or
+    // above the modulus
+    const BYTES: usize;
+    // The largest s such that 2^s divides the modulus minus one
+    const TWO_ADICITY: u32;
+    // A small quadratic non-residue, used to find square roots
+    const NONRESIDUE: u64;
+
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn from_u64(value: u64) -> Self;
+
+    // Reduce 64 uniformly random bytes to an element with negligible bias
+    fn from_bytes_wide(bytes: &[u8; 64]) -> Self;
+
+    fn is_zero(&self) -> bool {
+        *self == Self::zero()
+    }
+
+    fn double(&self) -> Self {
+        *self + *self
+    }
+
+    fn square(&self) -> Self {
+        *self * *self
+    }
+
+    // Raise to a power given as little-endian 64-bit limbs
+    fn pow(&self, exponent: &[u64]) -> Self {
+        let mut result = Self::one();
+        for limb in exponent.iter().rev() {
+            for i in (0..64).rev() {
+                result = result.square();
+                if (limb >> i) & 1 == 1 {
+                    result *= *self;
+                }
+            }
+        }
+        result
+    }
+
+    // The multiplicative inverse, by Fermat's little theorem
+    fn inverse(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+        Some(self.pow(&sub_small(Self::MODULUS, 2)))
+    }
+
+    // A square root by Tonelli-Shanks, if one exists
+    fn sqrt(&self) -> Option<Self> {
+        if self.is_zero() {
+            return Some(*self);
+        }
+        let s = Self::TWO_ADICITY;
+        // modulus - 1 = 2^s * t with t odd
+        let t = shift_right(&sub_small(Self::MODULUS, 1), s);
+
+        let mut z = Self::from_u64(Self::NONRESIDUE).pow(&t);
+        let mut x = self.pow(&shift_right(&t, 1));
+        let mut b = *self * x.square();
+        x *= *self;
+        let mut m = s;
+
+        while b != Self::one() {
+            // Find the least i with b^(2^i) = 1
+            let mut i = 0;
+            let mut b2i = b;
+            while b2i != Self::one() {
+                b2i = b2i.square();
+                i += 1;
+                if i == m {
+                    return None;
+                }
+            }
+            for _ in 0..(m - i - 1) {
+                z = z.square();
+            }
+            x *= z;
+            z = z.square();
+            b *= z;
+            m = i;
+        }
+        Some(x)
+    }
+
+    // A uniformly random element
+    fn random<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let mut bytes = [0u8; 64];
+        rng.fill_bytes(&mut bytes);
+        Self::from_bytes_wide(&bytes)
+    }
+}
+
+// limbs - small, for a multi-limb value known to be larger than small
+fn sub_small(limbs: &[u64], small: u64) -> Vec<u64> {
+    let mut result = limbs.to_vec();
+    let mut borrow = small;
+    for limb in result.iter_mut() {
+        let (value, underflow) = limb.overflowing_sub(borrow);
+        *limb = value;
+        borrow = underflow as u64;
+    }
+    result
+}
+
+// limbs >> shift, for shift below 64 * limbs.len()
+fn shift_right(limbs: &[u64], shift: u32) -> Vec<u64> {
+    let words = (shift / 64) as usize;
+    let bits = shift % 64;
+    (0..limbs.len())
+        .map(|i| {
+            let low = limbs.get(i + words).copied().unwrap_or(0);
+            let high = limbs.get(i + words + 1).copied().unwrap_or(0);
+            if bits == 0 {
+                low
+            } else {
+                (low >> bits) | (high << (64 - bits))
+            }
+        })
+        .collect()
+}
+
+impl Encode for Scalar {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self.as_bytes());
+    }
+}
+
+// Only canonical encodings, below the group order, are accepted
+impl Decode for Scalar {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        let mut array = [0u8; 32];
+        array.copy_from_slice(take(input, 32)?);
+        Scalar::from_canonical_bytes(array).into()
+    }
+}
+
+// The scalar field of Ristretto255, order 2^252 + 27742317777372353535851937790883648493
+impl PrimeField for Scalar {
+    const MODULUS: &'static [u64] = &[
+        0x5812631a5cf5d3ed,
+        0x14def9dea2f79cd6,
+        0x0000000000000000,
+        0x1000000000000000,
+    ];
+    const BITS: u32 = 253;
+    const BYTES: usize = 32;
+    const TWO_ADICITY: u32 = 2;
+    const NONRESIDUE: u64 = 2;
+
+    fn zero() -> Self {
+        Scalar::ZERO
+    }
+
+    fn one() -> Self {
+        Scalar::ONE
+    }
+
+    fn from_u64(value: u64) -> Self {
+        Scalar::from(value)
+    }
+
+    fn from_bytes_wide(bytes: &[u8; 64]) -> Self {
+        Scalar::from_bytes_mod_order_wide(bytes)
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        if *self == Scalar::ZERO {
+            None
+        } else {
+            Some(self.invert())
+        }
+    }
+
+    fn random<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        Scalar::random(rng)
+    }
+}
+
+impl Encode for k256::Scalar {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_bytes());
+    }
+}
+
+// Big-endian like the rest of SEC1; only canonical encodings are accepted
+impl Decode for k256::Scalar {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        let bytes = k256::FieldBytes::clone_from_slice(take(input, 32)?);
+        k256::Scalar::from_repr(bytes).into()
+    }
+}
+
+// The scalar field of secp256k1, order
+// 0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141
+impl PrimeField for k256::Scalar {
+    const MODULUS: &'static [u64] = &[
+        0xbfd25e8cd0364141,
+        0xbaaedce6af48a03b,
+        0xfffffffffffffffe,
+        0xffffffffffffffff,
+    ];
+    const BITS: u32 = 256;
+    const BYTES: usize = 32;
+    const TWO_ADICITY: u32 = 6;
+    const NONRESIDUE: u64 = 5;
+
+    fn zero() -> Self {
+        k256::Scalar::ZERO
+    }
+
+    fn one() -> Self {
+        k256::Scalar::ONE
+    }
+
+    fn from_u64(value: u64) -> Self {
+        k256::Scalar::from(value)
+    }
+
+    fn from_bytes_wide(bytes: &[u8; 64]) -> Self {
+        <k256::Scalar as Reduce<U512>>::reduce_bytes(bytes.into())
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        self.invert().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Field laws every implementation must satisfy
+    fn check_field<F: PrimeField>() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..20 {
+            let a = F::random(&mut rng);
+            let b = F::random(&mut rng);
+            assert_eq!(a + b - b, a);
+            assert_eq!(a + (-a), F::zero());
+            assert_eq!(a * (b + F::one()), a * b + a);
+            if let Some(inverse) = a.inverse() {
+                assert_eq!(a * inverse, F::one());
+            }
+            assert_eq!(F::from_bytes(&a.to_bytes()), Some(a));
+
+            let square = a.square();
+            let root = square.sqrt().expect("squares have roots");
+            assert!(root == a || root == -a);
+        }
+        assert_eq!(F::zero().inverse(), None);
+        assert_eq!(F::from_u64(3).pow(&[4]), F::from_u64(81));
+        assert_eq!(F::from_u64(F::NONRESIDUE).sqrt(), None);
+        assert_eq!(F::from_bytes(&vec![0xff; F::BYTES]), None);
+    }
+
+    #[test]
+    fn fr_satisfies_field_laws() {
+        check_field::<Fr>();
+    }
+
+    #[test]
+    fn ristretto_scalar_satisfies_field_laws() {
+        check_field::<Scalar>();
+    }
+
+    #[test]
+    fn secp256k1_scalar_satisfies_field_laws() {
+        check_field::<k256::Scalar>();
+    }
+
+    #[test]
+    fn modp_scalars_satisfy_field_laws() {
+        use crate::group::modp::{Rfc3526Group14, Toy23, Toy64};
+        check_field::<crate::ModpScalar<Toy23>>();
+        check_field::<crate::ModpScalar<Toy64>>();
+        check_field::<crate::ModpScalar<Rfc3526Group14>>();
+    }
+}
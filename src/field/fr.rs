@@ -0,0 +1,303 @@
+/**
+ * The 255-bit scalar field of BLS12-381
+ * r = 0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001
+ * Elements are four 64-bit limbs kept in Montgomery form, a * 2^256 mod r
+ */
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use super::PrimeField;
+use crate::encoding::take;
+use crate::{Decode, Encode};
+
+const MODULUS: [u64; 4] = [
+    0xffffffff00000001,
+    0x53bda402fffe5bfe,
+    0x3339d80809a1d805,
+    0x73eda753299d7d48,
+];
+
+// -r^-1 mod 2^64
+const INV: u64 = 0xfffffffeffffffff;
+
+// 2^256 mod r, the Montgomery form of one
+const R: [u64; 4] = [
+    0x00000001fffffffe,
+    0x5884b7fa00034802,
+    0x998c4fefecbc4ff5,
+    0x1824b159acc5056f,
+];
+
+// 2^512 mod r
+const R2: [u64; 4] = [
+    0xc999e990f3f29c6d,
+    0x2b6cedcb87925c23,
+    0x05d314967254398f,
+    0x0748d9d99f59ff11,
+];
+
+// 2^768 mod r
+const R3: [u64; 4] = [
+    0xc62c1807439b73af,
+    0x1b3e0d188cf06990,
+    0x73d13c71c7b5f418,
+    0x6e2a5bb9c8db33e9,
+];
+
+// An element of the BLS12-381 scalar field
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fr([u64; 4]);
+
+impl Fr {
+    // Convert canonical limbs into Montgomery form
+    const fn from_canonical(limbs: [u64; 4]) -> Self {
+        Fr(montgomery_mul(&limbs, &R2))
+    }
+
+    // Convert out of Montgomery form
+    fn to_canonical(self) -> [u64; 4] {
+        montgomery_mul(&self.0, &[1, 0, 0, 0])
+    }
+}
+
+impl fmt::Debug for Fr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Fr(0x")?;
+        for limb in self.to_canonical().iter().rev() {
+            write!(f, "{:016x}", limb)?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl PrimeField for Fr {
+    const MODULUS: &'static [u64] = &MODULUS;
+    const BITS: u32 = 255;
+    const BYTES: usize = 32;
+    const TWO_ADICITY: u32 = 32;
+    const NONRESIDUE: u64 = 7;
+
+    fn zero() -> Self {
+        Fr([0; 4])
+    }
+
+    fn one() -> Self {
+        Fr(R)
+    }
+
+    fn from_u64(value: u64) -> Self {
+        Fr::from_canonical([value, 0, 0, 0])
+    }
+
+    fn from_bytes_wide(bytes: &[u8; 64]) -> Self {
+        // low + high * 2^256, each half brought into Montgomery form separately
+        let low = read_limbs(&bytes[..32]);
+        let high = read_limbs(&bytes[32..]);
+        Fr(montgomery_mul(&low, &R2)) + Fr(montgomery_mul(&high, &R3))
+    }
+}
+
+// The canonical little-endian encoding of the value
+impl Encode for Fr {
+    fn encode(&self, out: &mut Vec<u8>) {
+        for limb in self.to_canonical().iter() {
+            out.extend_from_slice(&limb.to_le_bytes());
+        }
+    }
+}
+
+impl Decode for Fr {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        let limbs = read_limbs(take(input, 32)?);
+        if !less_than(&limbs, &MODULUS) {
+            return None;
+        }
+        Some(Fr::from_canonical(limbs))
+    }
+}
+
+impl Add for Fr {
+    type Output = Fr;
+
+    fn add(self, other: Fr) -> Fr {
+        // Both inputs are below r < 2^255, so the sum cannot overflow 256 bits
+        let (sum, _) = add_limbs(&self.0, &other.0);
+        Fr(reduce_once(sum))
+    }
+}
+
+impl Sub for Fr {
+    type Output = Fr;
+
+    fn sub(self, other: Fr) -> Fr {
+        let (difference, borrow) = sub_limbs(&self.0, &other.0);
+        if borrow {
+            Fr(add_limbs(&difference, &MODULUS).0)
+        } else {
+            Fr(difference)
+        }
+    }
+}
+
+impl Mul for Fr {
+    type Output = Fr;
+
+    fn mul(self, other: Fr) -> Fr {
+        Fr(montgomery_mul(&self.0, &other.0))
+    }
+}
+
+impl Neg for Fr {
+    type Output = Fr;
+
+    fn neg(self) -> Fr {
+        Fr::zero() - self
+    }
+}
+
+impl AddAssign for Fr {
+    fn add_assign(&mut self, other: Fr) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Fr {
+    fn sub_assign(&mut self, other: Fr) {
+        *self = *self - other;
+    }
+}
+
+impl MulAssign for Fr {
+    fn mul_assign(&mut self, other: Fr) {
+        *self = *self * other;
+    }
+}
+
+fn read_limbs(bytes: &[u8]) -> [u64; 4] {
+    let mut limbs = [0u64; 4];
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks(8)) {
+        let mut array = [0u8; 8];
+        array.copy_from_slice(chunk);
+        *limb = u64::from_le_bytes(array);
+    }
+    limbs
+}
+
+const fn add_limbs(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], bool) {
+    let mut result = [0u64; 4];
+    let mut carry = 0u128;
+    let mut i = 0;
+    while i < 4 {
+        let sum = a[i] as u128 + b[i] as u128 + carry;
+        result[i] = sum as u64;
+        carry = sum >> 64;
+        i += 1;
+    }
+    (result, carry != 0)
+}
+
+const fn sub_limbs(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], bool) {
+    let mut result = [0u64; 4];
+    let mut borrow = 0u64;
+    let mut i = 0;
+    while i < 4 {
+        let (partial, first) = a[i].overflowing_sub(b[i]);
+        let (difference, second) = partial.overflowing_sub(borrow);
+        result[i] = difference;
+        borrow = (first | second) as u64;
+        i += 1;
+    }
+    (result, borrow != 0)
+}
+
+const fn less_than(a: &[u64; 4], b: &[u64; 4]) -> bool {
+    sub_limbs(a, b).1
+}
+
+// Subtract the modulus once if the value is at or above it
+const fn reduce_once(limbs: [u64; 4]) -> [u64; 4] {
+    let (reduced, borrow) = sub_limbs(&limbs, &MODULUS);
+    if borrow {
+        limbs
+    } else {
+        reduced
+    }
+}
+
+// a * b * 2^-256 mod r, by coarsely integrated operand scanning
+const fn montgomery_mul(a: &[u64; 4], b: &[u64; 4]) -> [u64; 4] {
+    let mut t = [0u64; 6];
+    let mut i = 0;
+    while i < 4 {
+        let mut carry = 0u128;
+        let mut j = 0;
+        while j < 4 {
+            let product = t[j] as u128 + a[j] as u128 * b[i] as u128 + carry;
+            t[j] = product as u64;
+            carry = product >> 64;
+            j += 1;
+        }
+        let top = t[4] as u128 + carry;
+        t[4] = top as u64;
+        t[5] = (top >> 64) as u64;
+
+        let m = t[0].wrapping_mul(INV);
+        let mut carry = (t[0] as u128 + m as u128 * MODULUS[0] as u128) >> 64;
+        let mut j = 1;
+        while j < 4 {
+            let product = t[j] as u128 + m as u128 * MODULUS[j] as u128 + carry;
+            t[j - 1] = product as u64;
+            carry = product >> 64;
+            j += 1;
+        }
+        let top = t[4] as u128 + carry;
+        t[3] = top as u64;
+        t[4] = t[5] + (top >> 64) as u64;
+        i += 1;
+    }
+    reduce_once([t[0], t[1], t[2], t[3]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn modulus_minus_one_squares_to_one() {
+        let minus_one = -Fr::one();
+        assert_eq!(minus_one.square(), Fr::one());
+        assert_eq!(Fr::from_bytes(&Fr::zero().to_bytes()), Some(Fr::zero()));
+    }
+
+    #[test]
+    fn known_answers() {
+        // Values computed independently with Python's arbitrary-precision integers
+        let inverse_of_three =
+            from_hex("01000000aaaaaaaa543d5455576d7ee258e56b06b03ad1ccdaa81371371a494d");
+        assert_eq!(
+            Fr::from_u64(3).inverse().unwrap().to_bytes(),
+            inverse_of_three
+        );
+
+        let mut wide = [0u8; 64];
+        for (i, byte) in wide.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+        let reduced = from_hex("a6ed0de6a3c0dc72cdac8704ad0bb870bbc61ae72cb344c5bd1fcfea4367186c");
+        assert_eq!(Fr::from_bytes_wide(&wide).to_bytes(), reduced);
+    }
+
+    #[test]
+    fn rejects_non_canonical_encodings() {
+        let modulus: Vec<u8> = MODULUS.iter().flat_map(|limb| limb.to_le_bytes()).collect();
+        assert_eq!(Fr::from_bytes(&modulus), None);
+        assert_eq!(Fr::from_bytes(&[0u8; 31]), None);
+    }
+}
@@ -0,0 +1,239 @@
+/**
+ * Prime-order groups
+ * Commitments and sigma protocols are written against the Group trait, with
+ * exponents taken from the group's scalar field. Two elliptic-curve backends
+ * implement it: Ristretto255 and secp256k1, both at about 128-bit security.
+ * The modp module adds the textbook setting, a prime-order subgroup of Z_p^*.
+ */
+use std::fmt::Debug;
+use std::ops::{Add, Neg, Sub};
+
+use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+use curve25519_dalek::ristretto::{CompressedRistretto, RistrettoPoint};
+use curve25519_dalek::scalar::Scalar;
+use curve25519_dalek::traits::{Identity, MultiscalarMul};
+use k256::elliptic_curve::group::Group as _;
+use k256::elliptic_curve::hash2curve::{ExpandMsgXmd, GroupDigest};
+use k256::elliptic_curve::sec1::{FromEncodedPoint, ToEncodedPoint};
+use k256::{EncodedPoint, ProjectivePoint, Secp256k1};
+use sha2::{Sha256, Sha512};
+use subtle::ConstantTimeEq;
+
+use crate::encoding::take;
+use crate::{Decode, Encode, PrimeField};
+
+pub mod modp;
+
+pub use modp::{ModpGroup, ModpParams, ModpScalar};
+
+// A trait for cyclic groups of prime order, written additively. Elements encode
+// to a fixed-length compressed form and decode only from canonical encodings;
+// ct_eq compares elements without branching on them.
+pub trait Group:
+    Sized
+    + Copy
+    + Debug
+    + Eq
+    + ConstantTimeEq
+    + Encode
+    + Decode
+    + Send
+    + Sync
+    + 'static
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Neg<Output = Self>
+{
+    // The field of exponents, whose order is the group order
+    type Scalar: PrimeField;
+
+    fn identity() -> Self;
+    // The standard generator of the group
+    fn generator() -> Self;
+    fn scalar_mul(&self, scalar: &Self::Scalar) -> Self;
+    // Map a message to a group element whose discrete log nobody knows
+    fn hash_to_group(domain: &[u8], message: &[u8]) -> Self;
+
+    // The sum of points[i] * scalars[i]
+    fn multi_scalar_mul(points: &[Self], scalars: &[Self::Scalar]) -> Self {
+        points
+            .iter()
+            .zip(scalars)
+            .fold(Self::identity(), |acc, (point, scalar)| {
+                acc + point.scalar_mul(scalar)
+            })
+    }
+}
+
+// The Ristretto255 group, a prime-order group built on Curve25519
+impl Group for RistrettoPoint {
+    type Scalar = Scalar;
+
+    fn identity() -> Self {
+        <RistrettoPoint as Identity>::identity()
+    }
+
+    fn generator() -> Self {
+        RISTRETTO_BASEPOINT_POINT
+    }
+
+    fn scalar_mul(&self, scalar: &Scalar) -> Self {
+        self * scalar
+    }
+
+    fn hash_to_group(domain: &[u8], message: &[u8]) -> Self {
+        RistrettoPoint::hash_from_bytes::<Sha512>(&domain_separated(domain, message))
+    }
+
+    // Straus' method, in constant time since the scalars may be secret
+    fn multi_scalar_mul(points: &[Self], scalars: &[Scalar]) -> Self {
+        let length = points.len().min(scalars.len());
+        <RistrettoPoint as MultiscalarMul>::multiscalar_mul(&scalars[..length], &points[..length])
+    }
+}
+
+// The 32-byte compressed encoding
+impl Encode for RistrettoPoint {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self.compress().as_bytes());
+    }
+}
+
+// domain length || domain || message, so that distinct pairs never collide
+fn domain_separated(domain: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut input = Vec::with_capacity(8 + domain.len() + message.len());
+    input.extend_from_slice(&(domain.len() as u64).to_le_bytes());
+    input.extend_from_slice(domain);
+    input.extend_from_slice(message);
+    input
+}
+
+// Rejects anything that is not the canonical encoding of a group element
+impl Decode for RistrettoPoint {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        CompressedRistretto::from_slice(take(input, 32)?)
+            .ok()?
+            .decompress()
+    }
+}
+
+// secp256k1, the curve of Bitcoin and Ethereum signatures
+impl Group for ProjectivePoint {
+    type Scalar = k256::Scalar;
+
+    fn identity() -> Self {
+        ProjectivePoint::IDENTITY
+    }
+
+    fn generator() -> Self {
+        ProjectivePoint::GENERATOR
+    }
+
+    fn scalar_mul(&self, scalar: &k256::Scalar) -> Self {
+        self * scalar
+    }
+
+    // RFC 9380 hash-to-curve, suite secp256k1_XMD:SHA-256_SSWU_RO_
+    fn hash_to_group(domain: &[u8], message: &[u8]) -> Self {
+        Secp256k1::hash_from_bytes::<ExpandMsgXmd<Sha256>>(&[message], &[domain])
+            .expect("the domain tag is not empty")
+    }
+}
+
+// The 33-byte SEC1 compressed encoding; the identity, which SEC1 writes as a single
+// zero byte, is 33 zero bytes so that every element has the same length
+impl Encode for ProjectivePoint {
+    fn encode(&self, out: &mut Vec<u8>) {
+        if bool::from(self.is_identity()) {
+            out.extend_from_slice(&[0; 33]);
+        } else {
+            out.extend_from_slice(self.to_affine().to_encoded_point(true).as_bytes());
+        }
+    }
+}
+
+// Rejects points off the curve and anything that is not a compressed encoding
+impl Decode for ProjectivePoint {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        let bytes = take(input, 33)?;
+        if bytes.iter().all(|&byte| byte == 0) {
+            return Some(ProjectivePoint::IDENTITY);
+        }
+        if bytes[0] != 2 && bytes[0] != 3 {
+            return None;
+        }
+        let point = EncodedPoint::from_bytes(bytes).ok()?;
+        Option::from(ProjectivePoint::from_encoded_point(&point))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PrimeField;
+
+    // Group laws and encoding rules every backend must satisfy
+    fn check_group<G: Group>() {
+        let mut rng = rand::thread_rng();
+        let g = G::generator();
+        for _ in 0..10 {
+            let a = G::Scalar::random(&mut rng);
+            let b = G::Scalar::random(&mut rng);
+            assert_eq!(g.scalar_mul(&a) + g.scalar_mul(&b), g.scalar_mul(&(a + b)));
+            assert_eq!(g.scalar_mul(&a) - g.scalar_mul(&a), G::identity());
+            assert_eq!(-g.scalar_mul(&a), g.scalar_mul(&(-a)));
+
+            let point = g.scalar_mul(&a);
+            assert!(bool::from(point.ct_eq(&point)));
+            assert!(!bool::from(point.ct_eq(&g.scalar_mul(&b))));
+            assert_eq!(G::from_bytes(&point.to_bytes()), Some(point));
+            assert_eq!(point.to_bytes().len(), g.to_bytes().len());
+        }
+
+        let identity = G::identity();
+        assert_eq!(G::from_bytes(&identity.to_bytes()), Some(identity));
+        assert_eq!(g.scalar_mul(&G::Scalar::zero()), identity);
+        assert_eq!(
+            G::multi_scalar_mul(&[g, g], &[G::Scalar::one(), G::Scalar::from_u64(2)]),
+            g.scalar_mul(&G::Scalar::from_u64(3))
+        );
+
+        let hashed = G::hash_to_group(b"domain", b"message");
+        assert_eq!(hashed, G::hash_to_group(b"domain", b"message"));
+        assert_ne!(hashed, G::hash_to_group(b"domain", b"other message"));
+        assert_ne!(hashed, G::hash_to_group(b"other domain", b"message"));
+        assert_ne!(hashed, identity);
+
+        let len = g.to_bytes().len();
+        assert_eq!(G::from_bytes(&vec![0xff; len]), None);
+        assert_eq!(G::from_bytes(&g.to_bytes()[..len - 1]), None);
+    }
+
+    #[test]
+    fn ristretto_satisfies_group_laws() {
+        check_group::<RistrettoPoint>();
+    }
+
+    #[test]
+    fn secp256k1_satisfies_group_laws() {
+        check_group::<ProjectivePoint>();
+    }
+
+    #[test]
+    fn modp_groups_satisfy_group_laws() {
+        check_group::<ModpGroup<modp::Toy64>>();
+        check_group::<ModpGroup<modp::Rfc3526Group14>>();
+    }
+
+    // The generator's compressed encoding from SEC 2
+    #[test]
+    fn secp256k1_generator_encoding() {
+        let mut expected = vec![0x02];
+        expected.extend_from_slice(&[
+            0x79, 0xbe, 0x66, 0x7e, 0xf9, 0xdc, 0xbb, 0xac, 0x55, 0xa0, 0x62, 0x95, 0xce, 0x87,
+            0x0b, 0x07, 0x02, 0x9b, 0xfc, 0xdb, 0x2d, 0xce, 0x28, 0xd9, 0x59, 0xf2, 0x81, 0x5b,
+            0x16, 0xf8, 0x17, 0x98,
+        ]);
+        assert_eq!(ProjectivePoint::GENERATOR.to_bytes(), expected);
+    }
+}
@@ -0,0 +1,558 @@
+/**
+ * The textbook discrete-log setting: the subgroup of squares in Z_p^* for a
+ * safe prime p = 2q + 1. That subgroup has prime order q, and exponents live
+ * in Z_q. Elements are plain integers below p, so every step of a protocol
+ * can be followed with a calculator or a big-integer library.
+ *
+ * Arithmetic goes through num-bigint, whose modular exponentiation is not
+ * constant time. This backend is for teaching and auditing; use an
+ * elliptic-curve group where secrets have to stay secret.
+ */
+use std::fmt::{self, Debug};
+use std::marker::PhantomData;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use num_bigint::BigUint;
+use rand::{CryptoRng, RngCore};
+use sha2::{Digest, Sha512};
+use subtle::{Choice, ConstantTimeEq};
+
+use crate::encoding::take;
+use crate::{Decode, Encode, Group, PrimeField};
+
+// Room for the largest supported modulus, 2048 bits
+const LIMBS: usize = 32;
+
+// The parameters of a safe-prime group
+pub trait ModpParams: Copy + Debug + Eq + Send + Sync + 'static {
+    // The safe prime p = 2q + 1, as little-endian 64-bit limbs
+    const P: &'static [u64];
+    // The prime order q of the subgroup
+    const Q: &'static [u64];
+    // The bit length of q
+    const Q_BITS: u32;
+    // The lengths of the big-endian encodings of group elements and exponents
+    const P_BYTES: usize;
+    const Q_BYTES: usize;
+    // The largest s such that 2^s divides q - 1, and a non-residue mod q
+    const Q_TWO_ADICITY: u32;
+    const Q_NONRESIDUE: u64;
+    // A square, and so a generator of the order-q subgroup
+    const GENERATOR: u64;
+}
+
+// p = 23, q = 11: small enough to work through by hand, and no security at all
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Toy23;
+
+impl ModpParams for Toy23 {
+    const P: &'static [u64] = &[23];
+    const Q: &'static [u64] = &[11];
+    const Q_BITS: u32 = 4;
+    const P_BYTES: usize = 1;
+    const Q_BYTES: usize = 1;
+    const Q_TWO_ADICITY: u32 = 1;
+    const Q_NONRESIDUE: u64 = 2;
+    const GENERATOR: u64 = 2;
+}
+
+// p = 2^64 − 8489, a 64-bit safe prime; realistic shapes with numbers that still fit a u64
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Toy64;
+
+impl ModpParams for Toy64 {
+    const P: &'static [u64] = &[0xffffffffffffded7];
+    const Q: &'static [u64] = &[0x7fffffffffffef6b];
+    const Q_BITS: u32 = 63;
+    const P_BYTES: usize = 8;
+    const Q_BYTES: usize = 8;
+    const Q_TWO_ADICITY: u32 = 1;
+    const Q_NONRESIDUE: u64 = 2;
+    const GENERATOR: u64 = 2;
+}
+
+// The 2048-bit MODP group 14 of RFC 3526, about 112-bit security
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rfc3526Group14;
+
+impl ModpParams for Rfc3526Group14 {
+    const P: &'static [u64] = GROUP14_P;
+    const Q: &'static [u64] = GROUP14_Q;
+    const Q_BITS: u32 = 2047;
+    const P_BYTES: usize = 256;
+    const Q_BYTES: usize = 256;
+    const Q_TWO_ADICITY: u32 = 1;
+    const Q_NONRESIDUE: u64 = 7;
+    const GENERATOR: u64 = 2;
+}
+
+const GROUP14_P: &[u64] = &[
+    0xffffffffffffffff,
+    0x15728e5a8aacaa68,
+    0x15d2261898fa0510,
+    0x3995497cea956ae5,
+    0xde2bcbf695581718,
+    0xb5c55df06f4c52c9,
+    0x9b2783a2ec07a28f,
+    0xe39e772c180e8603,
+    0x32905e462e36ce3b,
+    0xf1746c08ca18217c,
+    0x670c354e4abc9804,
+    0x9ed529077096966d,
+    0x1c62f356208552bb,
+    0x83655d23dca3ad96,
+    0x69163fa8fd24cf5f,
+    0x98da48361c55d39a,
+    0xc2007cb8a163bf05,
+    0x49286651ece45b3d,
+    0xae9f24117c4b1fe6,
+    0xee386bfb5a899fa5,
+    0x0bff5cb6f406b7ed,
+    0xf44c42e9a637ed6b,
+    0xe485b576625e7ec6,
+    0x4fe1356d6d51c245,
+    0x302b0a6df25f1437,
+    0xef9519b3cd3a431b,
+    0x514a08798e3404dd,
+    0x020bbea63b139b22,
+    0x29024e088a67cc74,
+    0xc4c6628b80dc1cd1,
+    0xc90fdaa22168c234,
+    0xffffffffffffffff,
+];
+
+const GROUP14_Q: &[u64] = &[
+    0x7fffffffffffffff,
+    0x0ab9472d45565534,
+    0x8ae9130c4c7d0288,
+    0x1ccaa4be754ab572,
+    0xef15e5fb4aac0b8c,
+    0xdae2aef837a62964,
+    0xcd93c1d17603d147,
+    0xf1cf3b960c074301,
+    0x19482f23171b671d,
+    0x78ba3604650c10be,
+    0xb3861aa7255e4c02,
+    0xcf6a9483b84b4b36,
+    0x0e3179ab1042a95d,
+    0xc1b2ae91ee51d6cb,
+    0x348b1fd47e9267af,
+    0xcc6d241b0e2ae9cd,
+    0xe1003e5c50b1df82,
+    0x24943328f6722d9e,
+    0xd74f9208be258ff3,
+    0xf71c35fdad44cfd2,
+    0x85ffae5b7a035bf6,
+    0x7a262174d31bf6b5,
+    0xf242dabb312f3f63,
+    0xa7f09ab6b6a8e122,
+    0x98158536f92f8a1b,
+    0xf7ca8cd9e69d218d,
+    0x28a5043cc71a026e,
+    0x0105df531d89cd91,
+    0x948127044533e63a,
+    0x62633145c06e0e68,
+    0xe487ed5110b4611a,
+    0x7fffffffffffffff,
+];
+
+fn big(limbs: &[u64]) -> BigUint {
+    let bytes: Vec<u8> = limbs.iter().flat_map(|limb| limb.to_le_bytes()).collect();
+    BigUint::from_bytes_le(&bytes)
+}
+
+fn limbs(value: &BigUint) -> [u64; LIMBS] {
+    let mut out = [0; LIMBS];
+    for (limb, digit) in out.iter_mut().zip(value.to_u64_digits()) {
+        *limb = digit;
+    }
+    out
+}
+
+// value as exactly `len` big-endian bytes; value must fit
+fn to_be_bytes(value: &BigUint, len: usize, out: &mut Vec<u8>) {
+    let bytes = value.to_bytes_be();
+    out.resize(out.len() + len - bytes.len(), 0);
+    out.extend_from_slice(&bytes);
+}
+
+// An exponent: an element of Z_q
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct ModpScalar<P: ModpParams> {
+    limbs: [u64; LIMBS],
+    params: PhantomData<P>,
+}
+
+impl<P: ModpParams> ModpScalar<P> {
+    // Reduce any integer mod q
+    pub fn from_biguint(value: &BigUint) -> Self {
+        ModpScalar {
+            limbs: limbs(&(value % big(P::Q))),
+            params: PhantomData,
+        }
+    }
+
+    // The canonical representative in [0, q)
+    pub fn to_biguint(&self) -> BigUint {
+        big(&self.limbs)
+    }
+}
+
+impl<P: ModpParams> Debug for ModpScalar<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ModpScalar({:#x})", self.to_biguint())
+    }
+}
+
+impl<P: ModpParams> Add for ModpScalar<P> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::from_biguint(&(self.to_biguint() + other.to_biguint()))
+    }
+}
+
+impl<P: ModpParams> Sub for ModpScalar<P> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::from_biguint(&(self.to_biguint() + big(P::Q) - other.to_biguint()))
+    }
+}
+
+impl<P: ModpParams> Mul for ModpScalar<P> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self::from_biguint(&(self.to_biguint() * other.to_biguint()))
+    }
+}
+
+impl<P: ModpParams> Neg for ModpScalar<P> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::from_biguint(&(big(P::Q) - self.to_biguint()))
+    }
+}
+
+impl<P: ModpParams> AddAssign for ModpScalar<P> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<P: ModpParams> SubAssign for ModpScalar<P> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<P: ModpParams> MulAssign for ModpScalar<P> {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+impl<P: ModpParams> Encode for ModpScalar<P> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        to_be_bytes(&self.to_biguint(), P::Q_BYTES, out);
+    }
+}
+
+// Big-endian, and only below q
+impl<P: ModpParams> Decode for ModpScalar<P> {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        let value = BigUint::from_bytes_be(take(input, P::Q_BYTES)?);
+        if value >= big(P::Q) {
+            return None;
+        }
+        Some(Self::from_biguint(&value))
+    }
+}
+
+impl<P: ModpParams> PrimeField for ModpScalar<P> {
+    const MODULUS: &'static [u64] = P::Q;
+    const BITS: u32 = P::Q_BITS;
+    const BYTES: usize = P::Q_BYTES;
+    const TWO_ADICITY: u32 = P::Q_TWO_ADICITY;
+    const NONRESIDUE: u64 = P::Q_NONRESIDUE;
+
+    fn zero() -> Self {
+        Self::from_biguint(&BigUint::from(0u32))
+    }
+
+    fn one() -> Self {
+        Self::from_biguint(&BigUint::from(1u32))
+    }
+
+    fn from_u64(value: u64) -> Self {
+        Self::from_biguint(&BigUint::from(value))
+    }
+
+    fn from_bytes_wide(bytes: &[u8; 64]) -> Self {
+        Self::from_biguint(&BigUint::from_bytes_be(bytes))
+    }
+
+    fn pow(&self, exponent: &[u64]) -> Self {
+        Self::from_biguint(&self.to_biguint().modpow(&big(exponent), &big(P::Q)))
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+        let q = big(P::Q);
+        Some(Self::from_biguint(
+            &self.to_biguint().modpow(&(&q - 2u32), &q),
+        ))
+    }
+
+    // 64 bytes are not enough to be uniform mod a 2047-bit q; draw 16 bytes more than q has
+    fn random<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let mut bytes = vec![0u8; P::Q_BYTES + 16];
+        rng.fill_bytes(&mut bytes);
+        Self::from_biguint(&BigUint::from_bytes_be(&bytes))
+    }
+}
+
+// An element of the order-q subgroup of Z_p^*, written additively like every Group:
+// + is multiplication mod p and scalar_mul is exponentiation
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct ModpGroup<P: ModpParams> {
+    limbs: [u64; LIMBS],
+    params: PhantomData<P>,
+}
+
+impl<P: ModpParams> ModpGroup<P> {
+    // The element as an integer in [1, p)
+    pub fn to_biguint(&self) -> BigUint {
+        big(&self.limbs)
+    }
+
+    // Accept an integer only if it lies in the order-q subgroup
+    pub fn from_biguint(value: &BigUint) -> Option<Self> {
+        let p = big(P::P);
+        if value == &BigUint::from(0u32)
+            || value >= &p
+            || value.modpow(&big(P::Q), &p) != BigUint::from(1u32)
+        {
+            return None;
+        }
+        Some(Self::reduced(value))
+    }
+
+    fn reduced(value: &BigUint) -> Self {
+        ModpGroup {
+            limbs: limbs(&(value % big(P::P))),
+            params: PhantomData,
+        }
+    }
+}
+
+impl<P: ModpParams> Debug for ModpGroup<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ModpGroup({:#x})", self.to_biguint())
+    }
+}
+
+impl<P: ModpParams> ConstantTimeEq for ModpGroup<P> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.limbs[..].ct_eq(&other.limbs[..])
+    }
+}
+
+impl<P: ModpParams> Add for ModpGroup<P> {
+    type Output = Self;
+
+    // The group operation is multiplication mod p
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, other: Self) -> Self {
+        Self::reduced(&(self.to_biguint() * other.to_biguint()))
+    }
+}
+
+// The inverse mod p, computed as x^(q - 1) since x^q = 1
+impl<P: ModpParams> Neg for ModpGroup<P> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        let exponent = big(P::Q) - 1u32;
+        Self::reduced(&self.to_biguint().modpow(&exponent, &big(P::P)))
+    }
+}
+
+impl<P: ModpParams> Sub for ModpGroup<P> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self + (-other)
+    }
+}
+
+impl<P: ModpParams> Group for ModpGroup<P> {
+    type Scalar = ModpScalar<P>;
+
+    fn identity() -> Self {
+        Self::reduced(&BigUint::from(1u32))
+    }
+
+    fn generator() -> Self {
+        Self::reduced(&BigUint::from(P::GENERATOR))
+    }
+
+    fn scalar_mul(&self, scalar: &ModpScalar<P>) -> Self {
+        Self::reduced(&self.to_biguint().modpow(&scalar.to_biguint(), &big(P::P)))
+    }
+
+    // Hash to an integer mod p with 128 spare bits and square it; the squares are
+    // exactly the subgroup. A result of 1 is skipped by hashing again.
+    fn hash_to_group(domain: &[u8], message: &[u8]) -> Self {
+        let p = big(P::P);
+        for attempt in 0u32.. {
+            let mut wide = Vec::with_capacity(P::P_BYTES + 16 + 64);
+            for block in 0u32.. {
+                if wide.len() >= P::P_BYTES + 16 {
+                    break;
+                }
+                let mut hasher = Sha512::new();
+                hasher.update((domain.len() as u64).to_le_bytes());
+                hasher.update(domain);
+                hasher.update(message);
+                hasher.update(attempt.to_le_bytes());
+                hasher.update(block.to_le_bytes());
+                wide.extend_from_slice(&hasher.finalize());
+            }
+            let root = BigUint::from_bytes_be(&wide) % &p;
+            let element = Self::reduced(&(&root * &root));
+            if element != Self::identity() && root != BigUint::from(0u32) {
+                return element;
+            }
+        }
+        unreachable!("some attempt hashes to a non-trivial square")
+    }
+}
+
+impl<P: ModpParams> Encode for ModpGroup<P> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        to_be_bytes(&self.to_biguint(), P::P_BYTES, out);
+    }
+}
+
+// Big-endian, below p, and in the subgroup; the last check costs an exponentiation
+impl<P: ModpParams> Decode for ModpGroup<P> {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Self::from_biguint(&BigUint::from_bytes_be(take(input, P::P_BYTES)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type G23 = ModpGroup<Toy23>;
+
+    // Miller–Rabin to the first twelve prime bases, which is exact below 3.3 * 10^24
+    // and leaves at most a 4^-12 chance of error above it
+    fn is_probable_prime(n: &BigUint) -> bool {
+        const BASES: [u32; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+        // Small numbers, and multiples of a base, are settled by trial division
+        if *n < BigUint::from(2u32) {
+            return false;
+        }
+        if let Some(&a) = BASES.iter().find(|&&a| (n % a) == BigUint::from(0u32)) {
+            return *n == BigUint::from(a);
+        }
+        let one = BigUint::from(1u32);
+        let minus_one = n - &one;
+        let shift = minus_one.trailing_zeros().unwrap_or(0);
+        let odd = &minus_one >> shift;
+        BASES.iter().map(|&a| BigUint::from(a)).all(|a| {
+            let mut x = a.modpow(&odd, n);
+            if x == one || x == minus_one {
+                return true;
+            }
+            for _ in 1..shift {
+                x = x.modpow(&BigUint::from(2u32), n);
+                if x == minus_one {
+                    return true;
+                }
+            }
+            false
+        })
+    }
+
+    #[test]
+    fn primality_test_separates_primes_from_composites() {
+        let primes = [2u64, 3, 11, 23, 7919, 0xffffffffffffffc5];
+        // 561 and 3215031751 fool Fermat tests; 3215031751 is a strong pseudoprime to bases 2-7
+        let composites = [1u64, 4, 561, 3215031751, 0xffffffffffffffff];
+        assert!(primes.iter().all(|&n| is_probable_prime(&BigUint::from(n))));
+        assert!(!composites
+            .iter()
+            .any(|&n| is_probable_prime(&BigUint::from(n))));
+    }
+
+    #[test]
+    fn parameters_are_safe_primes() {
+        fn check<P: ModpParams>() {
+            let (p, q) = (big(P::P), big(P::Q));
+            assert_eq!(p, &q * 2u32 + 1u32);
+            assert!(is_probable_prime(&q), "q is not prime");
+            assert!(is_probable_prime(&p), "p is not prime");
+            assert_eq!(q.bits(), P::Q_BITS as u64);
+            assert_eq!((p.bits() as usize).div_ceil(8), P::P_BYTES);
+            assert_eq!((q.bits() as usize).div_ceil(8), P::Q_BYTES);
+            // The generator is a square other than 1, so it has order q
+            let generator = BigUint::from(P::GENERATOR);
+            assert_eq!(
+                ModpGroup::<P>::from_biguint(&generator),
+                Some(ModpGroup::generator())
+            );
+            assert_ne!(ModpGroup::<P>::generator(), ModpGroup::identity());
+        }
+        check::<Toy23>();
+        check::<Toy64>();
+        check::<Rfc3526Group14>();
+    }
+
+    #[test]
+    fn toy_group_by_hand() {
+        // The powers of 2 mod 23 are the eleven squares
+        let g = G23::generator();
+        let powers: Vec<BigUint> = (0..11)
+            .map(|i| g.scalar_mul(&ModpScalar::from_u64(i)).to_biguint())
+            .collect();
+        let squares: Vec<BigUint> = [1u32, 2, 4, 8, 16, 9, 18, 13, 3, 6, 12]
+            .iter()
+            .map(|&x| BigUint::from(x))
+            .collect();
+        assert_eq!(powers, squares);
+        assert_eq!(g.scalar_mul(&ModpScalar::from_u64(11)), G23::identity());
+        assert_eq!((g + g).to_biguint(), BigUint::from(4u32));
+        assert_eq!((-g).to_biguint(), BigUint::from(12u32));
+    }
+
+    #[test]
+    fn only_subgroup_members_decode() {
+        // 5 is a non-square mod 23, 0 and 23 are not in Z_23^*
+        assert_eq!(G23::from_bytes(&[5]), None);
+        assert_eq!(G23::from_bytes(&[0]), None);
+        assert_eq!(G23::from_bytes(&[23]), None);
+        assert_eq!(
+            G23::from_bytes(&[13]),
+            G23::from_biguint(&BigUint::from(13u32))
+        );
+        assert_eq!(ModpScalar::<Toy23>::from_bytes(&[11]), None);
+    }
+
+    #[test]
+    fn hashed_elements_lie_in_the_subgroup() {
+        let element = ModpGroup::<Rfc3526Group14>::hash_to_group(b"domain", b"message");
+        assert_eq!(
+            ModpGroup::from_biguint(&element.to_biguint()),
+            Some(element)
+        );
+        assert_eq!(element.to_bytes().len(), 256);
+    }
+}
@@ -0,0 +1,631 @@
+/**
+ * KZG polynomial commitments over BLS12-381
+ * A commitment to p is the single G1 point [p(τ)] for a secret τ fixed by a
+ * setup, and an opening at points z_1..z_k is one more point, [q(τ)] where
+ * q = (p − I) / Z, I interpolates the claimed values and Z vanishes on the
+ * points. The verifier checks e(C − [I(τ)], [1]) = e(π, [Z(τ)]) with the
+ * powers of τ the setup publishes. Nothing is hidden: a commitment to a
+ * low-degree polynomial can be opened by anyone who guesses it.
+ *
+ * The setup is only as trustworthy as the party who forgot τ. The generator
+ * here derives τ from a seed so tests run offline and reproducibly, which
+ * makes every commitment under it forgeable by anyone who knows the seed. It
+ * is only compiled for the crate's own tests and under the insecure-setup
+ * feature, so a normal build cannot reach it.
+ */
+use std::convert::TryInto;
+
+use bls12_381::{
+    multi_miller_loop, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt, Scalar,
+};
+use rand::{CryptoRng, RngCore};
+#[cfg(any(test, feature = "insecure-setup"))]
+use sha2::{Digest, Sha512};
+
+use crate::encoding::take;
+use crate::{Decode, Encode, Fr, PrimeField, VectorCommitment};
+
+// Separates the seed hash from any other use of the same bytes
+#[cfg(any(test, feature = "insecure-setup"))]
+const SETUP_DOMAIN: &[u8] = b"zero-knowledge-proof/kzg/insecure-setup";
+
+// The crate's Fr and the curve library's scalar share a canonical encoding
+fn to_scalar(value: &Fr) -> Scalar {
+    let bytes: [u8; 32] = value.to_bytes().try_into().unwrap();
+    Scalar::from_bytes(&bytes).unwrap()
+}
+
+// A polynomial over the BLS12-381 scalar field, lowest coefficient first.
+// Trailing zero coefficients are trimmed, so the zero polynomial is empty.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Polynomial {
+    pub coefficients: Vec<Fr>,
+}
+
+impl Polynomial {
+    pub fn new(mut coefficients: Vec<Fr>) -> Self {
+        while coefficients.last() == Some(&Fr::zero()) {
+            coefficients.pop();
+        }
+        Polynomial { coefficients }
+    }
+
+    pub fn zero() -> Self {
+        Polynomial::new(Vec::new())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.is_empty()
+    }
+
+    // The degree, counting the zero polynomial as degree 0
+    pub fn degree(&self) -> usize {
+        self.coefficients.len().saturating_sub(1)
+    }
+
+    // Horner's rule
+    pub fn evaluate(&self, x: &Fr) -> Fr {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(Fr::zero(), |acc, c| acc * *x + *c)
+    }
+
+    // (X − z_1) ... (X − z_k)
+    pub fn vanishing(points: &[Fr]) -> Self {
+        let mut coefficients = vec![Fr::one()];
+        for z in points {
+            // Multiply by X, then subtract z times the old polynomial
+            coefficients.insert(0, Fr::zero());
+            for i in 0..coefficients.len() - 1 {
+                let shifted = coefficients[i + 1] * *z;
+                coefficients[i] -= shifted;
+            }
+        }
+        Polynomial::new(coefficients)
+    }
+
+    // The lowest-degree polynomial through the given (x, y) pairs; None if an
+    // x repeats
+    pub fn interpolate(points: &[Fr], values: &[Fr]) -> Option<Self> {
+        assert_eq!(points.len(), values.len(), "one value per point");
+        let vanishing = Polynomial::vanishing(points);
+        let mut result = Polynomial::zero();
+        for (x, y) in points.iter().zip(values) {
+            // The Lagrange basis polynomial for x, before normalising
+            let (basis, _) = vanishing.div_rem(&Polynomial::new(vec![-*x, Fr::one()]));
+            let scale = *y * basis.evaluate(x).inverse()?;
+            result = &result + &basis.scale(&scale);
+        }
+        Some(result)
+    }
+
+    pub fn scale(&self, factor: &Fr) -> Self {
+        Polynomial::new(self.coefficients.iter().map(|c| *c * *factor).collect())
+    }
+
+    // Long division, returning the quotient and remainder
+    pub fn div_rem(&self, divisor: &Polynomial) -> (Polynomial, Polynomial) {
+        let lead = divisor
+            .coefficients
+            .last()
+            .expect("division by the zero polynomial")
+            .inverse()
+            .unwrap();
+        if self.coefficients.len() < divisor.coefficients.len() {
+            return (Polynomial::zero(), self.clone());
+        }
+        let mut remainder = self.coefficients.clone();
+        let shift = divisor.degree();
+        let mut quotient = vec![Fr::zero(); remainder.len() - shift];
+        for i in (0..quotient.len()).rev() {
+            let factor = remainder[i + shift] * lead;
+            quotient[i] = factor;
+            for (j, d) in divisor.coefficients.iter().enumerate() {
+                remainder[i + j] -= factor * *d;
+            }
+        }
+        remainder.truncate(shift);
+        (Polynomial::new(quotient), Polynomial::new(remainder))
+    }
+}
+
+impl<'a> std::ops::Add for &'a Polynomial {
+    type Output = Polynomial;
+
+    fn add(self, other: &'a Polynomial) -> Polynomial {
+        let length = self.coefficients.len().max(other.coefficients.len());
+        let coefficient = |p: &Polynomial, i| p.coefficients.get(i).copied().unwrap_or(Fr::zero());
+        Polynomial::new(
+            (0..length)
+                .map(|i| coefficient(self, i) + coefficient(other, i))
+                .collect(),
+        )
+    }
+}
+
+impl<'a> std::ops::Sub for &'a Polynomial {
+    type Output = Polynomial;
+
+    fn sub(self, other: &'a Polynomial) -> Polynomial {
+        self + &other.scale(&-Fr::one())
+    }
+}
+
+impl Encode for Polynomial {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.coefficients.encode(out);
+    }
+}
+
+// Only the trimmed form is canonical
+impl Decode for Polynomial {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        let coefficients = Vec::<Fr>::decode(input)?;
+        if coefficients.last() == Some(&Fr::zero()) {
+            return None;
+        }
+        Some(Polynomial { coefficients })
+    }
+}
+
+// The public powers of τ: enough in G1 to commit up to a maximum degree, and
+// enough in G2 to open at up to a maximum number of points at once
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KzgSetup {
+    pub g1: Vec<G1Affine>,
+    pub g2: Vec<G2Affine>,
+}
+
+impl KzgSetup {
+    // A setup with τ derived from the seed. Anyone who knows the seed knows τ
+    // and can open any commitment to anything, so this is for tests and
+    // benchmarks only; real deployments load the output of a ceremony.
+    #[cfg(any(test, feature = "insecure-setup"))]
+    pub fn insecure_from_seed(seed: &[u8], max_degree: usize, max_points: usize) -> Self {
+        let mut hasher = Sha512::new();
+        hasher.update(SETUP_DOMAIN);
+        hasher.update(seed);
+        let tau = Fr::from_bytes_wide(&hasher.finalize().into());
+
+        let powers = |count: usize| {
+            let mut power = Fr::one();
+            (0..count)
+                .map(|_| {
+                    let current = to_scalar(&power);
+                    power *= tau;
+                    current
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let g1: Vec<G1Projective> = powers(max_degree + 1)
+            .iter()
+            .map(|p| G1Projective::generator() * p)
+            .collect();
+        let g2: Vec<G2Projective> = powers(max_points.max(1) + 1)
+            .iter()
+            .map(|p| G2Projective::generator() * p)
+            .collect();
+
+        let mut setup = KzgSetup {
+            g1: vec![G1Affine::identity(); g1.len()],
+            g2: vec![G2Affine::identity(); g2.len()],
+        };
+        G1Projective::batch_normalize(&g1, &mut setup.g1);
+        G2Projective::batch_normalize(&g2, &mut setup.g2);
+        setup
+    }
+
+    // Both saturate at 0, so a hand-built setup with an empty list supports nothing
+    pub fn max_degree(&self) -> usize {
+        self.g1.len().saturating_sub(1)
+    }
+
+    pub fn max_points(&self) -> usize {
+        self.g2.len().saturating_sub(1)
+    }
+
+    // Check that both lists start at the generators and step by the same τ,
+    // as a loaded setup must before it is used
+    pub fn is_well_formed(&self) -> bool {
+        if self.g1.len() < 2 || self.g2.len() < 2 {
+            return false;
+        }
+        if self.g1[0] != G1Affine::generator() || self.g2[0] != G2Affine::generator() {
+            return false;
+        }
+        // e(g1[i + 1], [1]) = e(g1[i], [τ]) and e([1], g2[i + 1]) = e([τ], g2[i])
+        let g2_one = G2Prepared::from(self.g2[0]);
+        let g2_tau = G2Prepared::from(self.g2[1]);
+        let g1_steps = self
+            .g1
+            .windows(2)
+            .all(|pair| pairing_product_is_one(&[(pair[1], &g2_one), (-pair[0], &g2_tau)]));
+        let g2_steps = self.g2.windows(2).all(|pair| {
+            pairing_product_is_one(&[
+                (self.g1[0], &G2Prepared::from(pair[1])),
+                (-self.g1[1], &G2Prepared::from(pair[0])),
+            ])
+        });
+        g1_steps && g2_steps
+    }
+
+    // [p(τ)] in G1; None if the degree is above the setup's maximum
+    pub fn commit(&self, polynomial: &Polynomial) -> Option<KzgCommitment> {
+        Some(KzgCommitment {
+            point: G1Affine::from(self.evaluate_g1(polynomial)?),
+        })
+    }
+
+    // The value at z and a proof of it; None under the same conditions as open_many
+    pub fn open(&self, polynomial: &Polynomial, point: &Fr) -> Option<(Fr, KzgProof)> {
+        let (mut values, proof) = self.open_many(polynomial, std::slice::from_ref(point))?;
+        Some((values.remove(0), proof))
+    }
+
+    // The values at several points and a single proof for all of them; None
+    // if a point repeats, there are more points than the setup supports, or
+    // the polynomial could not have been committed under it
+    pub fn open_many(&self, polynomial: &Polynomial, points: &[Fr]) -> Option<(Vec<Fr>, KzgProof)> {
+        if points.is_empty()
+            || points.len() > self.max_points()
+            || polynomial.coefficients.len() > self.g1.len()
+        {
+            return None;
+        }
+        let values: Vec<Fr> = points.iter().map(|z| polynomial.evaluate(z)).collect();
+        let interpolation = Polynomial::interpolate(points, &values)?;
+        let (quotient, _) = (polynomial - &interpolation).div_rem(&Polynomial::vanishing(points));
+        let proof = KzgProof {
+            point: G1Affine::from(self.evaluate_g1(&quotient)?),
+        };
+        Some((values, proof))
+    }
+
+    pub fn verify(
+        &self,
+        commitment: &KzgCommitment,
+        point: &Fr,
+        value: &Fr,
+        proof: &KzgProof,
+    ) -> bool {
+        self.verify_many(
+            commitment,
+            std::slice::from_ref(point),
+            std::slice::from_ref(value),
+            proof,
+        )
+    }
+
+    // e(C − [I(τ)], [1]) = e(π, [Z(τ)])
+    pub fn verify_many(
+        &self,
+        commitment: &KzgCommitment,
+        points: &[Fr],
+        values: &[Fr],
+        proof: &KzgProof,
+    ) -> bool {
+        if points.is_empty() || points.len() != values.len() || points.len() > self.max_points() {
+            return false;
+        }
+        let interpolation = match Polynomial::interpolate(points, values)
+            .and_then(|interpolation| self.evaluate_g1(&interpolation))
+        {
+            Some(interpolation) => interpolation,
+            None => return false,
+        };
+        let vanishing = Polynomial::vanishing(points);
+        let vanishing_g2 = vanishing
+            .coefficients
+            .iter()
+            .zip(&self.g2)
+            .fold(G2Projective::identity(), |acc, (c, g)| {
+                acc + g * to_scalar(c)
+            });
+
+        let lhs = G1Affine::from(G1Projective::from(commitment.point) - interpolation);
+        pairing_product_is_one(&[
+            (lhs, &G2Prepared::from(self.g2[0])),
+            (
+                -proof.point,
+                &G2Prepared::from(G2Affine::from(vanishing_g2)),
+            ),
+        ])
+    }
+
+    // [p(τ)] in G1, if the setup has a power for every coefficient
+    fn evaluate_g1(&self, polynomial: &Polynomial) -> Option<G1Projective> {
+        if polynomial.coefficients.len() > self.g1.len() {
+            return None;
+        }
+        Some(
+            polynomial
+                .coefficients
+                .iter()
+                .zip(&self.g1)
+                .fold(G1Projective::identity(), |acc, (c, g)| {
+                    acc + g * to_scalar(c)
+                }),
+        )
+    }
+}
+
+fn pairing_product_is_one(terms: &[(G1Affine, &G2Prepared)]) -> bool {
+    let terms: Vec<(&G1Affine, &G2Prepared)> = terms.iter().map(|(p, q)| (p, *q)).collect();
+    multi_miller_loop(&terms).final_exponentiation() == Gt::identity()
+}
+
+// A commitment to a polynomial, [p(τ)] in G1
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KzgCommitment {
+    pub point: G1Affine,
+}
+
+// A proof of the values at one or more points, [q(τ)] in G1
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KzgProof {
+    pub point: G1Affine,
+}
+
+// Both are a single point in 48-byte compressed form
+fn decode_g1(input: &mut &[u8]) -> Option<G1Affine> {
+    let bytes: [u8; 48] = take(input, 48)?.try_into().ok()?;
+    Option::from(G1Affine::from_compressed(&bytes))
+}
+
+impl Encode for KzgCommitment {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.point.to_compressed());
+    }
+}
+
+impl Decode for KzgCommitment {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(KzgCommitment {
+            point: decode_g1(input)?,
+        })
+    }
+}
+
+impl Encode for KzgProof {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.point.to_compressed());
+    }
+}
+
+impl Decode for KzgProof {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(KzgProof {
+            point: decode_g1(input)?,
+        })
+    }
+}
+
+// A commitment to a vector: the polynomial through (i, values_i), and the
+// vector's length, so positions past the end are rejected rather than read off
+// the polynomial
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KzgVectorCommitment {
+    pub commitment: KzgCommitment,
+    pub len: u64,
+}
+
+impl Encode for KzgVectorCommitment {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.commitment.encode(out);
+        self.len.encode(out);
+    }
+}
+
+impl Decode for KzgVectorCommitment {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(KzgVectorCommitment {
+            commitment: KzgCommitment::decode(input)?,
+            len: u64::decode(input)?,
+        })
+    }
+}
+
+// What the committer keeps: the vector and the polynomial through it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KzgVectorOpening {
+    pub values: Vec<Fr>,
+    pub polynomial: Polynomial,
+}
+
+// Position i is the polynomial's value at i, so any subset opens with one
+// constant-size proof. Unlike the Pedersen version the commitment is not
+// hiding and the randomness goes unused.
+impl VectorCommitment for KzgVectorCommitment {
+    type Key = KzgSetup;
+    type Value = Fr;
+    type Opening = KzgVectorOpening;
+    type Proof = KzgProof;
+
+    fn commit_vector<R: RngCore + CryptoRng>(
+        setup: &KzgSetup,
+        values: &[Fr],
+        _rng: &mut R,
+    ) -> Option<(Self, KzgVectorOpening)> {
+        if values.len() > setup.g1.len() {
+            return None;
+        }
+        let points: Vec<Fr> = (0..values.len() as u64).map(Fr::from_u64).collect();
+        let polynomial = Polynomial::interpolate(&points, values)?;
+        Some((
+            KzgVectorCommitment {
+                commitment: setup.commit(&polynomial)?,
+                len: values.len() as u64,
+            },
+            KzgVectorOpening {
+                values: values.to_vec(),
+                polynomial,
+            },
+        ))
+    }
+
+    fn open_at<R: RngCore + CryptoRng>(
+        &self,
+        setup: &KzgSetup,
+        opening: &KzgVectorOpening,
+        indices: &[usize],
+        _rng: &mut R,
+    ) -> Option<KzgProof> {
+        if indices.iter().any(|i| *i as u64 >= self.len) || opening.values.len() as u64 != self.len
+        {
+            return None;
+        }
+        let points: Vec<Fr> = indices.iter().map(|i| Fr::from_u64(*i as u64)).collect();
+        let (_, proof) = setup.open_many(&opening.polynomial, &points)?;
+        Some(proof)
+    }
+
+    fn verify_at(
+        &self,
+        setup: &KzgSetup,
+        indices: &[usize],
+        values: &[Fr],
+        proof: &KzgProof,
+    ) -> bool {
+        if indices.iter().any(|i| *i as u64 >= self.len) {
+            return false;
+        }
+        let points: Vec<Fr> = indices.iter().map(|i| Fr::from_u64(*i as u64)).collect();
+        setup.verify_many(&self.commitment, &points, values, proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> KzgSetup {
+        KzgSetup::insecure_from_seed(b"kzg tests", 8, 3)
+    }
+
+    fn random_polynomial(degree: usize) -> Polynomial {
+        let mut rng = rand::thread_rng();
+        Polynomial::new((0..=degree).map(|_| Fr::random(&mut rng)).collect())
+    }
+
+    #[test]
+    fn division_and_interpolation_agree_with_evaluation() {
+        let p = random_polynomial(6);
+        let divisor = random_polynomial(2);
+        let (quotient, remainder) = p.div_rem(&divisor);
+        assert!(remainder.degree() < divisor.degree());
+        let x = Fr::from_u64(12345);
+        assert_eq!(
+            quotient.evaluate(&x) * divisor.evaluate(&x) + remainder.evaluate(&x),
+            p.evaluate(&x)
+        );
+
+        let points: Vec<Fr> = (1..=7).map(Fr::from_u64).collect();
+        let values: Vec<Fr> = points.iter().map(|z| p.evaluate(z)).collect();
+        assert_eq!(Polynomial::interpolate(&points, &values), Some(p));
+
+        let repeated = [Fr::one(), Fr::one()];
+        assert_eq!(Polynomial::interpolate(&repeated, &values[..2]), None);
+    }
+
+    #[test]
+    fn the_seeded_setup_is_deterministic_and_well_formed() {
+        let setup = setup();
+        assert_eq!(setup, KzgSetup::insecure_from_seed(b"kzg tests", 8, 3));
+        assert_ne!(setup, KzgSetup::insecure_from_seed(b"other", 8, 3));
+        assert_eq!((setup.max_degree(), setup.max_points()), (8, 3));
+        assert!(setup.is_well_formed());
+
+        let mut tampered = setup.clone();
+        tampered.g1.swap(2, 3);
+        assert!(!tampered.is_well_formed());
+    }
+
+    #[test]
+    fn openings_verify_only_for_the_true_values() {
+        let setup = setup();
+        let p = random_polynomial(8);
+        let commitment = setup.commit(&p).unwrap();
+
+        let z = Fr::from_u64(77);
+        let (value, proof) = setup.open(&p, &z).unwrap();
+        assert!(setup.verify(&commitment, &z, &value, &proof));
+        assert!(!setup.verify(&commitment, &z, &(value + Fr::one()), &proof));
+        assert!(!setup.verify(&commitment, &(z + Fr::one()), &value, &proof));
+
+        let points = [Fr::from_u64(1), Fr::from_u64(2), Fr::from_u64(3)];
+        let (values, proof) = setup.open_many(&p, &points).unwrap();
+        assert!(setup.verify_many(&commitment, &points, &values, &proof));
+        let mut wrong = values.clone();
+        wrong[1] += Fr::one();
+        assert!(!setup.verify_many(&commitment, &points, &wrong, &proof));
+        assert!(!setup.verify_many(&commitment, &points[..2], &values[..2], &proof));
+    }
+
+    #[test]
+    fn openings_respect_the_setup_limits() {
+        let setup = setup();
+        let p = random_polynomial(4);
+        let points: Vec<Fr> = (0..4).map(Fr::from_u64).collect();
+        assert!(setup.open_many(&p, &points).is_none());
+        assert!(setup.open_many(&p, &[]).is_none());
+        assert!(setup.open_many(&p, &[Fr::one(), Fr::one()]).is_none());
+
+        let mut rng = rand::thread_rng();
+        let values = vec![Fr::one(); 10];
+        assert!(KzgVectorCommitment::commit_vector(&setup, &values[..9], &mut rng).is_some());
+        assert!(KzgVectorCommitment::commit_vector(&setup, &values, &mut rng).is_none());
+    }
+
+    #[test]
+    fn vector_positions_past_the_end_are_rejected() {
+        let setup = setup();
+        let mut rng = rand::thread_rng();
+        let values: Vec<Fr> = (1..=5).map(|i| Fr::from_u64(i * i)).collect();
+        let (commitment, opening) =
+            KzgVectorCommitment::commit_vector(&setup, &values, &mut rng).unwrap();
+        assert!(commitment
+            .open_at(&setup, &opening, &[5], &mut rng)
+            .is_none());
+
+        // The polynomial has a value at 10, and a valid KZG proof of it, but the
+        // vector has no position 10
+        let ten = Fr::from_u64(10);
+        let (value, proof) = setup.open(&opening.polynomial, &ten).unwrap();
+        assert!(setup.verify(&commitment.commitment, &ten, &value, &proof));
+        assert!(!commitment.verify_at(&setup, &[10], &[value], &proof));
+    }
+
+    #[test]
+    fn polynomials_above_the_maximum_degree_are_refused() {
+        let setup = setup();
+        let p = random_polynomial(9);
+        assert_eq!(setup.commit(&p), None);
+        assert!(setup.open(&p, &Fr::one()).is_none());
+    }
+
+    #[test]
+    fn hand_built_setups_without_powers_refuse_everything() {
+        let p = random_polynomial(0);
+        let empty = KzgSetup {
+            g1: Vec::new(),
+            g2: Vec::new(),
+        };
+        assert_eq!((empty.max_degree(), empty.max_points()), (0, 0));
+        assert!(!empty.is_well_formed());
+        assert_eq!(empty.commit(&p), None);
+
+        // Enough to commit, but no power of τ in G2 to open with
+        let setup = setup();
+        let commitment = setup.commit(&p).unwrap();
+        let (value, proof) = setup.open(&p, &Fr::one()).unwrap();
+        let no_openings = KzgSetup {
+            g2: setup.g2[..1].to_vec(),
+            ..setup
+        };
+        assert_eq!(no_openings.max_points(), 0);
+        assert!(no_openings.open(&p, &Fr::one()).is_none());
+        assert!(!no_openings.verify(&commitment, &Fr::one(), &value, &proof));
+    }
+}
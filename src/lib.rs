@@ -0,0 +1,178 @@
+/**
+ * A zero knowledge proof library
+ * Provides the commitment, challenge and response traits, sigma protocols
+ * built on them, and a driver that runs an interactive protocol between a
+ * prover and a verifier
+ */
+use rand::{CryptoRng, RngCore};
+use sha2::{Digest, Sha512};
+
+pub mod adversary;
+pub mod and;
+pub mod bulletproofs;
+pub mod commitment;
+pub mod dleq;
+pub mod encoding;
+pub mod extractor;
+pub mod fiat_shamir;
+pub mod field;
+pub mod group;
+pub mod kzg;
+pub mod membership;
+pub mod merkle;
+pub mod opening;
+pub mod or;
+pub mod parity;
+pub mod pedersen;
+pub mod proof;
+pub mod protocol;
+pub mod range;
+pub mod repeated;
+pub mod schnorr;
+pub mod sigma;
+pub mod simulator;
+pub mod transcript;
+pub mod vector;
+
+pub use and::And;
+pub use bulletproofs::{BulletproofGenerators, RangeProof};
+pub use commitment::{HashCommitment, HashOpening};
+pub use dleq::{ChaumPedersen, DleqStatement};
+pub use encoding::{Decode, Encode};
+pub use extractor::Extractor;
+pub use field::{Fr, PrimeField};
+pub use group::{Group, ModpGroup, ModpScalar};
+pub use kzg::{KzgCommitment, KzgProof, KzgSetup, KzgVectorCommitment, Polynomial};
+pub use membership::{Membership, MembershipStatement, MerkleMembership, MerkleSet};
+pub use merkle::{MerkleTree, SortedMerkleTree};
+pub use opening::Opening;
+pub use or::{Or, OrResponse, OrWitness};
+pub use pedersen::{
+    PedersenCommitment, PedersenGenerators, PedersenOpening, PedersenVectorGenerators,
+};
+pub use proof::Proof;
+pub use protocol::{
+    rounds_for, run_protocol, HonestProver, HonestVerifier, ProtocolResult, Prover, Verifier,
+    DEFAULT_SECURITY,
+};
+pub use range::{Range, RangeWitness};
+pub use repeated::Repeated;
+pub use schnorr::{Dlog, DlogStatement, Schnorr, SchnorrProof};
+pub use sigma::SigmaProtocol;
+pub use simulator::Simulator;
+pub use transcript::Transcript;
+pub use vector::{
+    PedersenVectorOpening, VectorOpening, VectorOpeningProof, VectorOpeningStatement,
+};
+
+// A trait for types that can be used as commitments in a zero-knowledge proof
+pub trait Commitment: Sized {
+    // The type of value being committed to
+    type Value;
+    // The blinding randomness mixed into a commitment
+    type Randomness;
+    // What the committer reveals to open the commitment (the value and its blinding)
+    type Opening;
+
+    // A method for creating a commitment from a value using fresh randomness
+    fn commit<R: RngCore + CryptoRng>(value: Self::Value, rng: &mut R) -> (Self, Self::Opening);
+    // A method for creating a commitment with caller-chosen randomness, for proofs about it
+    fn commit_with(value: &Self::Value, randomness: &Self::Randomness) -> Self;
+    // A method for checking an opening and revealing the value
+    fn open(&self, opening: &Self::Opening) -> Option<Self::Value>;
+}
+
+// A trait for commitments to a whole vector, any subset of whose positions can
+// be opened together with one proof while the rest stay hidden; the indexed
+// counterpart of Commitment's commit and open
+pub trait VectorCommitment: Sized {
+    // The public parameters committer and verifier share, such as generators
+    type Key;
+    // The type of each position's value
+    type Value;
+    // What the committer keeps to open positions later
+    type Opening;
+    // The proof that some positions hold the claimed values
+    type Proof;
+
+    // A method for committing to a vector using fresh randomness; None if the vector
+    // is longer than the key supports
+    fn commit_vector<R: RngCore + CryptoRng>(
+        key: &Self::Key,
+        values: &[Self::Value],
+        rng: &mut R,
+    ) -> Option<(Self, Self::Opening)>;
+    // A method for proving the values at some positions; None if a position is not in the vector
+    fn open_at<R: RngCore + CryptoRng>(
+        &self,
+        key: &Self::Key,
+        opening: &Self::Opening,
+        indices: &[usize],
+        rng: &mut R,
+    ) -> Option<Self::Proof>;
+    // A method for checking claimed values, given in the order of their positions
+    fn verify_at(
+        &self,
+        key: &Self::Key,
+        indices: &[usize],
+        values: &[Self::Value],
+        proof: &Self::Proof,
+    ) -> bool;
+}
+
+// A trait for types that can be used as challenges in a zero-knowledge proof
+pub trait Challenge: Sized + Clone + PartialEq + Encode + Decode {
+    // The size of the challenge space in bits, rounded down, so a prover who has to
+    // guess a challenge succeeds with probability at most 2^-BITS
+    const BITS: u32;
+
+    // A method for drawing a fresh challenge, independent of anything the prover sent
+    fn challenge<R: RngCore + CryptoRng>(rng: &mut R) -> Self;
+    // A method for deriving a challenge from 64 uniform bytes, such as a hash output
+    fn from_uniform_bytes(bytes: &[u8; 64]) -> Self;
+}
+
+// A trait for types that can be used as responses in a zero-knowledge proof
+pub trait Response: Sized + Clone + Encode + Decode {}
+
+impl<F: PrimeField> Challenge for F {
+    // The field has at least 2^(BITS - 1) elements
+    const BITS: u32 = F::BITS - 1;
+
+    fn challenge<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        // A uniformly random field element, so a cheating prover guesses it with probability 1/p
+        F::random(rng)
+    }
+
+    fn from_uniform_bytes(bytes: &[u8; 64]) -> Self {
+        F::from_bytes_wide(bytes)
+    }
+}
+
+impl<F: PrimeField> Response for F {}
+
+// Independent challenges side by side, one for each repetition of a protocol
+impl<C: Challenge, const N: usize> Challenge for [C; N] {
+    const BITS: u32 = C::BITS.saturating_mul(N as u32);
+
+    fn challenge<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        std::array::from_fn(|_| C::challenge(rng))
+    }
+
+    fn from_uniform_bytes(bytes: &[u8; 64]) -> Self {
+        // Stretch the input into 64 fresh bytes per challenge
+        std::array::from_fn(|i| {
+            let wide = Sha512::new()
+                .chain_update(bytes)
+                .chain_update((i as u64).to_le_bytes())
+                .finalize();
+            C::from_uniform_bytes(&wide.into())
+        })
+    }
+}
+
+impl<T: Response, const N: usize> Response for [T; N] {}
+
+impl<A: Response, B: Response> Response for (A, B) {}
+
+impl<T: Response> Response for Vec<T> {}
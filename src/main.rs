@@ -3,126 +3,106 @@
  * As generated by ChatGPT and then modified
  * is an "interactive" ZKP program
  */
-use std::marker::Sized;
+use curve25519_dalek::ristretto::RistrettoPoint;
 use rand::Rng;
-use sha2::{Digest, Sha256};
-
-// A trait for types that can be used as commitments in a zero-knowledge proof
-trait Commitment: Sized {
-    // A method for creating a commitment from a value
-    fn commit(value: Self) -> Self;
-    // A method for opening a commitment and revealing the value
-    fn open(self) -> Option<Self>;
-}
-
-// A trait for types that can be used as challenges in a zero-knowledge proof
-trait Challenge: Sized {
-    // A method for creating a challenge from a commitment
-    fn challenge(_commitment: &Self) -> Self;
-}
-
-// A trait for types that can be used as responses in a zero-knowledge proof
-trait Response: Sized {
-    // A method for creating a response from a value and a challenge
-    fn respond(value: Self, _challenge: &Self) -> Self;
-    // A method for verifying a response given a commitment and challenge
-    fn verify(commitment: &Self, challenge: &Self, response: &Self) -> bool;
-}
-
-// A struct for holding the commitments, challenges, and responses in a zero-knowledge proof
-// struct Proof<C: Commitment, Ch: Challenge, R: Response> {
-//     commitment: C,
-//     challenge: Ch,
-//     response: R,
-// }
-
-impl Commitment for u64 {
-    fn commit(value: Self) -> Self {
-        // Hash the value to create a commitment
-        let mut hasher = Sha256::new();
-        hasher.update(value.to_le_bytes());
-        let hash = hasher.finalize();
-        // Return the commitment as a u64
-        let mut array = [0u8; 8];
-        array.copy_from_slice(&hash[..8]);
-        u64::from_le_bytes(array)
-    }
-
-    fn open(self) -> Option<Self> {
-        // In this implementation, the commitment can always be opened to reveal the value
-        Some(self)
-    }
-}
-
-impl Challenge for u64 {
-    fn challenge(_commitment: &Self) -> Self {
-        // Generate a random challenge
-        rand::thread_rng().gen()
-    }
-}
-
-impl Response for u64 {
-    fn respond(value: Self, _challenge: &Self) -> Self {
-        // Calculate the response as a function of the value and challenge
-        (value & 1) ^ 1
-    }
-
-    fn verify(commitment: &Self, challenge: &Self, response: &Self) -> bool {
-        // Calculate the expected response based on the commitment and challenge
-        let expected_response = Self::respond(*commitment, challenge);
-        // Check if the calculated response matches the given response
-        // println!("response: {}, expected_response: {}", response, expected_response);
-
-        response == &expected_response
-    }
-}
+use zero_knowledge_proof::adversary::{
+    self, AttackReport, ChallengeGuesser, MalformedCommitment, Replayer, WrongWitness,
+};
+use zero_knowledge_proof::parity::{self, Parity, ParityWitness};
+use zero_knowledge_proof::{
+    rounds_for, run_protocol, Encode, HonestProver, HonestVerifier, Proof, Repeated, SigmaProtocol,
+    DEFAULT_SECURITY,
+};
+
+// The security parameter: a cheating prover succeeds with probability at most 2^-LAMBDA
+const LAMBDA: u32 = DEFAULT_SECURITY;
+// Enough parallel copies of the parity proof to reach it in a single round
+const COPIES: usize = rounds_for(LAMBDA, Parity::<RistrettoPoint>::SOUNDNESS_BITS);
+type EvenProof = Repeated<Parity<RistrettoPoint>, COPIES>;
 
 fn main() {
-    // The number of iterations to perform
     const USE_RANDOM: bool = true;
-    let iterations: i32 = 10;
 
     // The prover knows an even number, but doesn't want to reveal what it is
-    let mut value: u64;
-
-    if !USE_RANDOM {
-        value = 437567812942;
+    let value: u64 = if USE_RANDOM {
+        rand::thread_rng().gen::<u64>() & !1
     } else {
-        value = 0;
-    }
-
-    // A counter for the number of successful proofs
-    let mut successful_proofs = 0;
-
-    for _ in 0..iterations {
-        // generate a random number
-        if USE_RANDOM {
-            value = rand::thread_rng().gen();
-        }
-
-        // The prover creates a commitment to the value
-        let commitment = u64::commit(value);
-
-        // The verifier creates a challenge based on the commitment
-        let challenge = u64::challenge(&commitment);
-
-        // The prover creates a response to the challenge
-        let response = u64::respond(value, &challenge);
-
-        // The verifier verifies the response using the commitment and challenge
-        if !u64::verify(&commitment, &challenge, &response) {
-            successful_proofs += 1;
-        }
-    }
-
-    // Calculate the probability of a successful proof
-    let probability = successful_proofs as f64 / iterations as f64;
-    println!("The probability of a successful proof is {:.2}.", probability);
-
-    // if probability is above 0.95 then the challenge is passed
-    if probability > 0.95 {
+        437567812942
+    };
+
+    // The verifier only ever sees a Pedersen commitment to the value
+    let (statement, witness) =
+        parity::commit_value::<RistrettoPoint, _>(value, &mut rand::thread_rng());
+
+    let mut prover = HonestProver::<EvenProof>::new(statement, witness);
+    let mut verifier = HonestVerifier::<EvenProof>::new(statement);
+    let result = run_protocol(&mut prover, &mut verifier, LAMBDA);
+
+    // Report the soundness the copies achieved, not a ratio of accepted rounds
+    println!(
+        "Passed {} of {} rounds of {} parallel copies; a cheating prover would pass with probability at most 2^-{} ({:e}).",
+        result.accepted,
+        result.rounds,
+        COPIES,
+        result.soundness_bits,
+        result.cheating_probability()
+    );
+
+    // Every round has to pass for the proof to be accepted
+    if result.is_accepted() {
         println!("The proof is valid, the prover knows an even number.");
     } else {
         println!("The proof is invalid, the prover does not know an even number.");
     }
+
+    // The same copies as one non-interactive proof, checked with one call
+    let proof = Proof::prove::<EvenProof>(&statement, &witness, &mut rand::thread_rng());
+    println!(
+        "Non-interactive proof of {} bytes, valid: {}.",
+        proof.to_bytes().len(),
+        proof.verify::<EvenProof>(&statement)
+    );
+
+    // Cheating provers against the same verifier, each measured over a few attempts
+    const TRIALS: usize = 10;
+    let mut rng = rand::thread_rng();
+    let report = |name: &str, report: AttackReport| {
+        println!(
+            "{}: accepted {} of {} times ({:.2}), bound {:e}.",
+            name,
+            report.accepted,
+            report.trials,
+            report.rate(),
+            report.bound()
+        );
+    };
+
+    let mut guesser = ChallengeGuesser::<EvenProof>::new(statement);
+    report(
+        "Challenge guesser",
+        adversary::measure(&mut guesser, &mut verifier, LAMBDA, TRIALS),
+    );
+
+    let mut replayer = Replayer::<EvenProof>::record(&statement, &witness, 4, &mut rng);
+    report(
+        "Replayed transcripts",
+        adversary::measure(&mut replayer, &mut verifier, LAMBDA, TRIALS),
+    );
+
+    let mut malformed = MalformedCommitment::<EvenProof>::new(statement, witness);
+    report(
+        "Malformed commitments",
+        adversary::measure(&mut malformed, &mut verifier, LAMBDA, TRIALS),
+    );
+
+    // Another even value does not open the commitment
+    let other = ParityWitness {
+        value: witness.value.wrapping_add(2),
+        ..witness
+    };
+    let mut wrong = WrongWitness::<EvenProof>::new(statement, other);
+    report(
+        "Wrong witness",
+        adversary::measure(&mut wrong, &mut verifier, LAMBDA, TRIALS),
+    );
 }
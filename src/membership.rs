@@ -0,0 +1,620 @@
+/**
+ * Proofs that a committed value belongs to a public set
+ * For a Pedersen commitment C = g^x h^r and a set {v_1..v_n}, x = v_i exactly
+ * when C / g^v_i = h^r. The small-set proof is the OR of those n discrete-log
+ * statements: the prover simulates every branch but its own under challenge
+ * shares it picks in advance, and the verifier's challenge fixes the share of
+ * the real branch as c minus the rest. Nothing about i is revealed, but the
+ * proof and the verifier's work grow linearly in n.
+ *
+ * The large-set proof splits the set into blocks, commits to the blocks with a
+ * Merkle tree, and proves membership in one block: the prover reveals the
+ * block and its Merkle path, then runs the small-set proof over the block. The
+ * verifier only needs the root, and the cost is one block plus a logarithmic
+ * path. The price is that the block is public, so the value is hidden only
+ * among the block's elements. The block size trades proof size against that
+ * anonymity set; a block as large as the set is the small-set proof again.
+ */
+use std::marker::PhantomData;
+
+use rand::{CryptoRng, RngCore};
+
+use crate::merkle::{self, MerklePath, MerkleTree};
+use crate::schnorr::{Dlog, DlogStatement};
+use crate::{
+    Decode, Encode, Extractor, Group, PedersenCommitment, PedersenGenerators, PedersenOpening,
+    PrimeField, Response, SigmaProtocol, Simulator, Transcript,
+};
+
+// The proof that a committed value is one of a list, hiding which
+pub struct Membership<G>(PhantomData<G>);
+
+// The claim that the commitment opens to an element of the set
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MembershipStatement<G: Group> {
+    pub commitment: PedersenCommitment<G>,
+    pub set: Vec<G::Scalar>,
+}
+
+// The real branch's nonce, and the challenge shares and responses simulated for the others
+#[derive(Clone)]
+pub struct MembershipState<F> {
+    index: usize,
+    nonce: F,
+    challenges: Vec<F>,
+    responses: Vec<F>,
+}
+
+// Every branch's response, and the challenge shares of all branches but the
+// last, whose share is whatever is left of the verifier's challenge
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MembershipResponse<F> {
+    pub challenges: Vec<F>,
+    pub responses: Vec<F>,
+}
+
+// Branch i's statement: C / g^v_i = h^r
+fn branch<G: Group>(
+    generators: &PedersenGenerators<G>,
+    commitment: &PedersenCommitment<G>,
+    value: &G::Scalar,
+) -> DlogStatement<G> {
+    DlogStatement {
+        base: generators.h,
+        point: commitment.point - generators.g.scalar_mul(value),
+    }
+}
+
+// The share of the last branch
+fn last_share<F: PrimeField>(challenge: &F, shares: &[F]) -> F {
+    shares.iter().fold(*challenge, |acc, share| acc - *share)
+}
+
+impl<G: Group> Encode for MembershipStatement<G> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.commitment.encode(out);
+        self.set.encode(out);
+    }
+}
+
+impl<G: Group> Decode for MembershipStatement<G> {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(MembershipStatement {
+            commitment: PedersenCommitment::decode(input)?,
+            set: Vec::decode(input)?,
+        })
+    }
+}
+
+impl<F: PrimeField> Encode for MembershipResponse<F> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.challenges.encode(out);
+        self.responses.encode(out);
+    }
+}
+
+impl<F: PrimeField> Decode for MembershipResponse<F> {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(MembershipResponse {
+            challenges: Vec::decode(input)?,
+            responses: Vec::decode(input)?,
+        })
+    }
+}
+
+impl<F: PrimeField> Response for MembershipResponse<F> {}
+
+impl<G: Group> SigmaProtocol for Membership<G> {
+    const DOMAIN: &'static [u8] = b"zero-knowledge-proof/membership/v1";
+
+    type Statement = MembershipStatement<G>;
+    type Witness = PedersenOpening<G::Scalar>;
+    type Commitment = Vec<G>;
+    type State = MembershipState<G::Scalar>;
+    type Challenge = G::Scalar;
+    type Response = MembershipResponse<G::Scalar>;
+
+    // A value outside the set is given the first branch, and its proof fails
+    fn commit<R: RngCore + CryptoRng>(
+        statement: &MembershipStatement<G>,
+        witness: &PedersenOpening<G::Scalar>,
+        rng: &mut R,
+    ) -> (Vec<G>, MembershipState<G::Scalar>) {
+        let generators = PedersenGenerators::<G>::default();
+        let index = statement
+            .set
+            .iter()
+            .position(|v| *v == witness.value)
+            .unwrap_or(0);
+
+        let mut commitments = Vec::with_capacity(statement.set.len());
+        let mut challenges = Vec::with_capacity(statement.set.len());
+        let mut responses = Vec::with_capacity(statement.set.len());
+        let mut nonce = G::Scalar::zero();
+        for (i, value) in statement.set.iter().enumerate() {
+            let branch = branch(&generators, &statement.commitment, value);
+            if i == index {
+                let (commitment, state) = Dlog::commit(&branch, &witness.blinding, rng);
+                commitments.push(commitment);
+                nonce = state;
+                challenges.push(G::Scalar::zero());
+                responses.push(G::Scalar::zero());
+            } else {
+                let challenge = G::Scalar::random(rng);
+                let (commitment, response) = Dlog::simulate(&branch, &challenge, rng);
+                commitments.push(commitment);
+                challenges.push(challenge);
+                responses.push(response);
+            }
+        }
+
+        (
+            commitments,
+            MembershipState {
+                index,
+                nonce,
+                challenges,
+                responses,
+            },
+        )
+    }
+
+    fn respond(
+        statement: &MembershipStatement<G>,
+        witness: &PedersenOpening<G::Scalar>,
+        state: MembershipState<G::Scalar>,
+        challenge: &G::Scalar,
+    ) -> MembershipResponse<G::Scalar> {
+        let generators = PedersenGenerators::<G>::default();
+        let MembershipState {
+            index,
+            nonce,
+            mut challenges,
+            mut responses,
+        } = state;
+        if challenges.is_empty() {
+            return MembershipResponse {
+                challenges,
+                responses,
+            };
+        }
+        // The real branch's slot still holds zero, so it drops out of the sum
+        challenges[index] = last_share(challenge, &challenges);
+        let branch = branch(&generators, &statement.commitment, &statement.set[index]);
+        responses[index] = Dlog::respond(&branch, &witness.blinding, nonce, &challenges[index]);
+        challenges.pop();
+        MembershipResponse {
+            challenges,
+            responses,
+        }
+    }
+
+    fn verify(
+        statement: &MembershipStatement<G>,
+        commitment: &Vec<G>,
+        challenge: &G::Scalar,
+        response: &MembershipResponse<G::Scalar>,
+    ) -> bool {
+        let n = statement.set.len();
+        if n == 0
+            || commitment.len() != n
+            || response.responses.len() != n
+            || response.challenges.len() != n - 1
+        {
+            return false;
+        }
+        let generators = PedersenGenerators::<G>::default();
+        let last = last_share(challenge, &response.challenges);
+
+        statement
+            .set
+            .iter()
+            .zip(commitment)
+            .zip(response.challenges.iter().copied().chain([last]))
+            .zip(&response.responses)
+            .all(|(((value, commitment), share), answer)| {
+                let branch = branch(&generators, &statement.commitment, value);
+                Dlog::verify(&branch, commitment, &share, answer)
+            })
+    }
+}
+
+// Split the challenge at random and simulate every branch
+impl<G: Group> Simulator for Membership<G> {
+    fn simulate<R: RngCore + CryptoRng>(
+        statement: &MembershipStatement<G>,
+        challenge: &G::Scalar,
+        rng: &mut R,
+    ) -> (Vec<G>, MembershipResponse<G::Scalar>) {
+        let generators = PedersenGenerators::<G>::default();
+        let n = statement.set.len();
+        let mut challenges: Vec<G::Scalar> = (1..n).map(|_| G::Scalar::random(rng)).collect();
+        let last = last_share(challenge, &challenges);
+        challenges.push(last);
+
+        let (commitments, responses) = statement
+            .set
+            .iter()
+            .zip(&challenges)
+            .map(|(value, share)| {
+                Dlog::simulate(
+                    &branch(&generators, &statement.commitment, value),
+                    share,
+                    rng,
+                )
+            })
+            .unzip();
+        challenges.pop();
+        (
+            commitments,
+            MembershipResponse {
+                challenges,
+                responses,
+            },
+        )
+    }
+}
+
+// Two different challenges differ in at least one share, and that branch's
+// transcripts give up the blinding of C / g^v_i
+impl<G: Group> Extractor for Membership<G> {
+    fn extract(
+        statement: &MembershipStatement<G>,
+        commitment: &Vec<G>,
+        first: (&G::Scalar, &MembershipResponse<G::Scalar>),
+        second: (&G::Scalar, &MembershipResponse<G::Scalar>),
+    ) -> Option<PedersenOpening<G::Scalar>> {
+        let n = statement.set.len();
+        if n == 0 || first.1.challenges.len() != n - 1 || second.1.challenges.len() != n - 1 {
+            return None;
+        }
+        let generators = PedersenGenerators::<G>::default();
+        let shares = |(challenge, response): (&G::Scalar, &MembershipResponse<G::Scalar>)| {
+            let mut shares = response.challenges.clone();
+            shares.push(last_share(challenge, &response.challenges));
+            shares
+        };
+        let (first_shares, second_shares) = (shares(first), shares(second));
+        let i = (0..n).find(|&i| first_shares[i] != second_shares[i])?;
+        let value = statement.set[i];
+        let blinding = Dlog::extract(
+            &branch(&generators, &statement.commitment, &value),
+            commitment.get(i)?,
+            (&first_shares[i], first.1.responses.get(i)?),
+            (&second_shares[i], second.1.responses.get(i)?),
+        )?;
+        Some(PedersenOpening { value, blinding })
+    }
+}
+
+// The domain the blocks of a large set are hashed under
+const BLOCK_DOMAIN: &[u8] = b"zero-knowledge-proof/membership/block/v1";
+
+// A block's leaf: the domain and its elements
+fn block_leaf<F: PrimeField>(block: &[F]) -> Vec<u8> {
+    let mut leaf = BLOCK_DOMAIN.to_vec();
+    block.to_vec().encode(&mut leaf);
+    leaf
+}
+
+// A large set split into blocks under a Merkle tree; the prover keeps this, the
+// verifier only needs the root
+#[derive(Clone, Debug)]
+pub struct MerkleSet<F> {
+    blocks: Vec<Vec<F>>,
+    tree: MerkleTree,
+}
+
+impl<F: PrimeField> MerkleSet<F> {
+    // Split the set into blocks of block_size elements, in order; the last may be shorter
+    pub fn new(set: &[F], block_size: usize) -> Self {
+        assert!(block_size > 0, "blocks must hold at least one element");
+        let blocks: Vec<Vec<F>> = set.chunks(block_size).map(<[F]>::to_vec).collect();
+        let leaves: Vec<Vec<u8>> = blocks.iter().map(|block| block_leaf(block)).collect();
+        MerkleSet {
+            tree: MerkleTree::new(&leaves),
+            blocks,
+        }
+    }
+
+    pub fn root(&self) -> merkle::Digest {
+        self.tree.root()
+    }
+
+    // The witness for a commitment to a member of the set, or None if the value is not in it
+    pub fn witness(&self, opening: &PedersenOpening<F>) -> Option<MerkleMembershipWitness<F>> {
+        let index = self
+            .blocks
+            .iter()
+            .position(|block| block.contains(&opening.value))?;
+        Some(MerkleMembershipWitness {
+            opening: *opening,
+            block: self.blocks[index].clone(),
+            path: self.tree.path(index)?,
+        })
+    }
+}
+
+// Membership in a large set, proven in the one block of the set that holds the value
+pub struct MerkleMembership<G>(PhantomData<G>);
+
+// The claim that the commitment opens to an element of the set under the root
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MerkleMembershipStatement<G: Group> {
+    pub commitment: PedersenCommitment<G>,
+    pub root: merkle::Digest,
+}
+
+// The opening, and the block holding the value with its path to the root
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleMembershipWitness<F> {
+    pub opening: PedersenOpening<F>,
+    pub block: Vec<F>,
+    pub path: MerklePath,
+}
+
+// The prover's first message: the revealed block, its path, and the small-set
+// proof's commitments over the block
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleMembershipCommitment<G: Group> {
+    pub block: Vec<G::Scalar>,
+    pub path: MerklePath,
+    pub proof: Vec<G>,
+}
+
+impl<G: Group> MerkleMembershipCommitment<G> {
+    // The small-set statement the block reduces the claim to
+    fn statement(&self, commitment: &PedersenCommitment<G>) -> MembershipStatement<G> {
+        MembershipStatement {
+            commitment: *commitment,
+            set: self.block.clone(),
+        }
+    }
+}
+
+impl<G: Group> Encode for MerkleMembershipStatement<G> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.commitment.encode(out);
+        self.root.encode(out);
+    }
+}
+
+impl<G: Group> Decode for MerkleMembershipStatement<G> {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(MerkleMembershipStatement {
+            commitment: PedersenCommitment::decode(input)?,
+            root: <[u8; 32]>::decode(input)?,
+        })
+    }
+}
+
+impl<G: Group> Encode for MerkleMembershipCommitment<G> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.block.encode(out);
+        self.path.encode(out);
+        self.proof.encode(out);
+    }
+}
+
+impl<G: Group> Decode for MerkleMembershipCommitment<G> {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(MerkleMembershipCommitment {
+            block: Vec::decode(input)?,
+            path: MerklePath::decode(input)?,
+            proof: Vec::decode(input)?,
+        })
+    }
+}
+
+impl<G: Group> SigmaProtocol for MerkleMembership<G> {
+    const DOMAIN: &'static [u8] = b"zero-knowledge-proof/merkle-membership/v1";
+
+    type Statement = MerkleMembershipStatement<G>;
+    type Witness = MerkleMembershipWitness<G::Scalar>;
+    type Commitment = MerkleMembershipCommitment<G>;
+    type State = MembershipState<G::Scalar>;
+    type Challenge = G::Scalar;
+    type Response = MembershipResponse<G::Scalar>;
+
+    fn commit<R: RngCore + CryptoRng>(
+        statement: &MerkleMembershipStatement<G>,
+        witness: &MerkleMembershipWitness<G::Scalar>,
+        rng: &mut R,
+    ) -> (MerkleMembershipCommitment<G>, MembershipState<G::Scalar>) {
+        let mut commitment = MerkleMembershipCommitment {
+            block: witness.block.clone(),
+            path: witness.path.clone(),
+            proof: Vec::new(),
+        };
+        let (proof, state) = Membership::commit(
+            &commitment.statement(&statement.commitment),
+            &witness.opening,
+            rng,
+        );
+        commitment.proof = proof;
+        (commitment, state)
+    }
+
+    fn respond(
+        statement: &MerkleMembershipStatement<G>,
+        witness: &MerkleMembershipWitness<G::Scalar>,
+        state: MembershipState<G::Scalar>,
+        challenge: &G::Scalar,
+    ) -> MembershipResponse<G::Scalar> {
+        let block = MembershipStatement {
+            commitment: statement.commitment,
+            set: witness.block.clone(),
+        };
+        Membership::respond(&block, &witness.opening, state, challenge)
+    }
+
+    fn verify(
+        statement: &MerkleMembershipStatement<G>,
+        commitment: &MerkleMembershipCommitment<G>,
+        challenge: &G::Scalar,
+        response: &MembershipResponse<G::Scalar>,
+    ) -> bool {
+        commitment
+            .path
+            .verify(&statement.root, &block_leaf(&commitment.block))
+            && Membership::verify(
+                &commitment.statement(&statement.commitment),
+                &commitment.proof,
+                challenge,
+                response,
+            )
+    }
+
+    fn append_domain(transcript: &mut Transcript) {
+        transcript.append_message(b"protocol", Self::DOMAIN);
+        Membership::<G>::append_domain(transcript);
+    }
+}
+
+// The revealed block is a small-set statement, whose extractor applies unchanged
+impl<G: Group> Extractor for MerkleMembership<G> {
+    fn extract(
+        statement: &MerkleMembershipStatement<G>,
+        commitment: &MerkleMembershipCommitment<G>,
+        first: (&G::Scalar, &MembershipResponse<G::Scalar>),
+        second: (&G::Scalar, &MembershipResponse<G::Scalar>),
+    ) -> Option<MerkleMembershipWitness<G::Scalar>> {
+        let opening = Membership::extract(
+            &commitment.statement(&statement.commitment),
+            &commitment.proof,
+            first,
+            second,
+        )?;
+        Some(MerkleMembershipWitness {
+            opening,
+            block: commitment.block.clone(),
+            path: commitment.path.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{run_protocol, Commitment, HonestProver, HonestVerifier, Proof, DEFAULT_SECURITY};
+    use curve25519_dalek::ristretto::RistrettoPoint;
+    use curve25519_dalek::scalar::Scalar;
+
+    type G = RistrettoPoint;
+
+    fn set(n: u64) -> Vec<Scalar> {
+        (0..n).map(|i| Scalar::from(1000 + 7 * i)).collect()
+    }
+
+    fn instance(
+        value: Scalar,
+        set: Vec<Scalar>,
+    ) -> (MembershipStatement<G>, PedersenOpening<Scalar>) {
+        let (commitment, opening) = PedersenCommitment::<G>::commit(value, &mut rand::thread_rng());
+        (MembershipStatement { commitment, set }, opening)
+    }
+
+    #[test]
+    fn every_member_convinces_the_verifier() {
+        let set = set(5);
+        for value in &set {
+            let (statement, opening) = instance(*value, set.clone());
+            let mut prover = HonestProver::<Membership<G>>::new(statement.clone(), opening);
+            let mut verifier = HonestVerifier::<Membership<G>>::new(statement.clone());
+            assert!(run_protocol(&mut prover, &mut verifier, DEFAULT_SECURITY).is_accepted());
+
+            let proof =
+                Proof::prove::<Membership<G>>(&statement, &opening, &mut rand::thread_rng());
+            assert!(proof.verify::<Membership<G>>(&statement));
+        }
+    }
+
+    #[test]
+    fn non_members_and_other_sets_are_rejected() {
+        let mut rng = rand::thread_rng();
+        let (statement, opening) = instance(Scalar::from(3u64), set(4));
+        let proof = Proof::prove::<Membership<G>>(&statement, &opening, &mut rng);
+        assert!(!proof.verify::<Membership<G>>(&statement));
+
+        let (statement, opening) = instance(Scalar::from(1007u64), set(4));
+        let proof = Proof::prove::<Membership<G>>(&statement, &opening, &mut rng);
+        assert!(proof.verify::<Membership<G>>(&statement));
+        let other = MembershipStatement {
+            set: set(5)[1..].to_vec(),
+            ..statement.clone()
+        };
+        assert!(!proof.verify::<Membership<G>>(&other));
+        let empty = MembershipStatement {
+            set: Vec::new(),
+            ..statement
+        };
+        assert!(!Membership::<G>::verify(
+            &empty,
+            &Vec::new(),
+            &Scalar::ONE,
+            &MembershipResponse {
+                challenges: Vec::new(),
+                responses: Vec::new(),
+            }
+        ));
+    }
+
+    #[test]
+    fn simulation_and_extraction() {
+        let mut rng = rand::thread_rng();
+        let (statement, opening) = instance(Scalar::from(1014u64), set(6));
+        let challenge = Scalar::random(&mut rng);
+        let (commitment, response) = Membership::simulate(&statement, &challenge, &mut rng);
+        assert!(Membership::verify(
+            &statement,
+            &commitment,
+            &challenge,
+            &response
+        ));
+
+        let (commitment, state) = Membership::commit(&statement, &opening, &mut rng);
+        let (c1, c2) = (Scalar::random(&mut rng), Scalar::random(&mut rng));
+        let r1 = Membership::respond(&statement, &opening, state.clone(), &c1);
+        let r2 = Membership::respond(&statement, &opening, state, &c2);
+        let extracted = Membership::extract(&statement, &commitment, (&c1, &r1), (&c2, &r2));
+        assert_eq!(extracted, Some(opening));
+    }
+
+    #[test]
+    fn large_sets_reveal_only_the_block() {
+        let mut rng = rand::thread_rng();
+        let set = set(1000);
+        let tree = MerkleSet::new(&set, 16);
+        let (commitment, opening) = PedersenCommitment::<G>::commit(set[537], &mut rng);
+        let statement = MerkleMembershipStatement {
+            commitment,
+            root: tree.root(),
+        };
+        let witness = tree.witness(&opening).unwrap();
+        let proof = Proof::prove::<MerkleMembership<G>>(&statement, &witness, &mut rng);
+        assert!(proof.verify::<MerkleMembership<G>>(&statement));
+        // Block 33 of 63, six levels deep
+        assert_eq!(proof.commitment.block, set[528..544].to_vec());
+        assert_eq!(proof.commitment.path.siblings.len(), 6);
+
+        // The block must be one of the set's, and the value one of the block's
+        let mut forged = proof.clone();
+        forged.commitment.block[0] = Scalar::from(5u64);
+        assert!(!forged.verify::<MerkleMembership<G>>(&statement));
+        let other = MerkleSet::new(&set[..512], 16);
+        assert!(
+            !proof.verify::<MerkleMembership<G>>(&MerkleMembershipStatement {
+                commitment,
+                root: other.root(),
+            })
+        );
+        let (outsider, outside) = PedersenCommitment::<G>::commit(Scalar::from(5u64), &mut rng);
+        assert!(tree.witness(&outside).is_none());
+        assert!(
+            !proof.verify::<MerkleMembership<G>>(&MerkleMembershipStatement {
+                commitment: outsider,
+                root: tree.root(),
+            })
+        );
+    }
+}
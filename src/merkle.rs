@@ -0,0 +1,606 @@
+/**
+ * SHA-256 Merkle trees
+ * Commits to a list of byte strings with one 32-byte root. Leaves and inner
+ * nodes are hashed with different prefixes, so an inner node can never be
+ * passed off as a leaf. The leaf layer is padded with zero digests up to a
+ * power of two, and an inclusion proof is the leaf's index and the sibling of
+ * every node on its path to the root.
+ *
+ * Opening several leaves at once, a multiproof sends each needed sibling once
+ * and leaves out every node the verifier can compute from the opened leaves,
+ * so neighbouring leaves share most of their paths.
+ *
+ * A sorted tree holds its leaves in increasing order without duplicates, and
+ * its root also commits to the number of leaves. Then the absence of a key is
+ * shown by the two adjacent leaves around it, or by the first or last leaf
+ * alone when the key falls outside them.
+ */
+use sha2::{Digest as _, Sha256};
+
+use crate::{Decode, Encode};
+
+// A node of the tree
+pub type Digest = [u8; 32];
+
+// The padding past the last leaf, and the root of an empty tree
+pub const EMPTY: Digest = [0; 32];
+
+pub fn hash_leaf(leaf: &[u8]) -> Digest {
+    Sha256::new()
+        .chain_update([0u8])
+        .chain_update(leaf)
+        .finalize()
+        .into()
+}
+
+pub fn hash_node(left: &Digest, right: &Digest) -> Digest {
+    Sha256::new()
+        .chain_update([1u8])
+        .chain_update(left)
+        .chain_update(right)
+        .finalize()
+        .into()
+}
+
+// Every layer of the tree, from the padded leaf hashes up to the root
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleTree {
+    layers: Vec<Vec<Digest>>,
+    len: usize,
+}
+
+// The path from one leaf to the root
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerklePath {
+    pub index: u64,
+    pub siblings: Vec<Digest>,
+}
+
+// The opening of several leaves: their indices in increasing order, the depth
+// of the tree, and the siblings the verifier cannot compute, level by level
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultiProof {
+    pub depth: u64,
+    pub indices: Vec<u64>,
+    pub nodes: Vec<Digest>,
+}
+
+impl MerkleTree {
+    pub fn new<T: AsRef<[u8]>>(leaves: &[T]) -> Self {
+        let mut layer: Vec<Digest> = leaves.iter().map(|leaf| hash_leaf(leaf.as_ref())).collect();
+        layer.resize(leaves.len().next_power_of_two(), EMPTY);
+        let mut layers = vec![layer];
+        while layers[layers.len() - 1].len() > 1 {
+            let next = layers[layers.len() - 1]
+                .chunks(2)
+                .map(|pair| hash_node(&pair[0], &pair[1]))
+                .collect();
+            layers.push(next);
+        }
+        MerkleTree {
+            layers,
+            len: leaves.len(),
+        }
+    }
+
+    pub fn root(&self) -> Digest {
+        match self.layers.last() {
+            Some(top) if self.len > 0 => top[0],
+            _ => EMPTY,
+        }
+    }
+
+    // The number of leaves, not counting padding
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // The number of levels above the leaves
+    pub fn depth(&self) -> usize {
+        self.layers.len() - 1
+    }
+
+    // The inclusion proof for leaf `index`, if there is such a leaf
+    pub fn path(&self, index: usize) -> Option<MerklePath> {
+        if index >= self.len {
+            return None;
+        }
+        let siblings = self.layers[..self.depth()]
+            .iter()
+            .enumerate()
+            .map(|(level, layer)| layer[(index >> level) ^ 1])
+            .collect();
+        Some(MerklePath {
+            index: index as u64,
+            siblings,
+        })
+    }
+
+    // One proof for all the given leaves, in any order and with repeats, if they all exist
+    pub fn prove_many(&self, indices: &[usize]) -> Option<MultiProof> {
+        let mut known = indices.to_vec();
+        known.sort_unstable();
+        known.dedup();
+        if known.is_empty() || known[known.len() - 1] >= self.len {
+            return None;
+        }
+        let leaves = known.iter().map(|&i| i as u64).collect();
+
+        let mut nodes = Vec::new();
+        for layer in &self.layers[..self.depth()] {
+            let mut parents = Vec::with_capacity(known.len());
+            let mut i = 0;
+            while i < known.len() {
+                // A node whose sibling is also known needs nothing from the proof
+                if known.get(i + 1) == Some(&(known[i] ^ 1)) {
+                    i += 1;
+                } else {
+                    nodes.push(layer[known[i] ^ 1]);
+                }
+                parents.push(known[i] >> 1);
+                i += 1;
+            }
+            known = parents;
+        }
+
+        Some(MultiProof {
+            depth: self.depth() as u64,
+            indices: leaves,
+            nodes,
+        })
+    }
+}
+
+impl MerklePath {
+    // The root a tree holding `leaf` at this path's index would have
+    pub fn root(&self, leaf: &[u8]) -> Digest {
+        self.siblings
+            .iter()
+            .enumerate()
+            .fold(hash_leaf(leaf), |node, (level, sibling)| {
+                if (self.index >> level) & 1 == 0 {
+                    hash_node(&node, sibling)
+                } else {
+                    hash_node(sibling, &node)
+                }
+            })
+    }
+
+    pub fn verify(&self, root: &Digest, leaf: &[u8]) -> bool {
+        // The index must fit the path, or two indices would share one proof
+        self.siblings.len() < 64
+            && self.index >> self.siblings.len() == 0
+            && self.root(leaf) == *root
+    }
+}
+
+impl MultiProof {
+    // Check the leaves, given in the order of the proof's indices, against the root
+    pub fn verify<T: AsRef<[u8]>>(&self, root: &Digest, leaves: &[T]) -> bool {
+        let depth = self.depth;
+        if depth >= 64
+            || self.indices.is_empty()
+            || leaves.len() != self.indices.len()
+            || self.indices.windows(2).any(|pair| pair[0] >= pair[1])
+            || self.indices[self.indices.len() - 1] >> depth != 0
+        {
+            return false;
+        }
+
+        let mut known: Vec<(u64, Digest)> = self
+            .indices
+            .iter()
+            .zip(leaves)
+            .map(|(i, leaf)| (*i, hash_leaf(leaf.as_ref())))
+            .collect();
+        let mut nodes = self.nodes.iter();
+        for _ in 0..depth {
+            let mut parents = Vec::with_capacity(known.len());
+            let mut i = 0;
+            while i < known.len() {
+                let (index, node) = known[i];
+                let sibling = match known.get(i + 1) {
+                    Some((next, sibling)) if *next == index ^ 1 => {
+                        i += 1;
+                        *sibling
+                    }
+                    _ => match nodes.next() {
+                        Some(sibling) => *sibling,
+                        None => return false,
+                    },
+                };
+                let parent = if index & 1 == 0 {
+                    hash_node(&node, &sibling)
+                } else {
+                    hash_node(&sibling, &node)
+                };
+                parents.push((index >> 1, parent));
+                i += 1;
+            }
+            known = parents;
+        }
+        nodes.next().is_none() && known == [(0, *root)]
+    }
+}
+
+// A Merkle tree over sorted, distinct leaves, whose root binds the leaf count
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SortedMerkleTree {
+    leaves: Vec<Vec<u8>>,
+    tree: MerkleTree,
+}
+
+// The proof that a key is a leaf of a sorted tree
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SortedInclusionProof {
+    pub len: u64,
+    pub path: MerklePath,
+}
+
+// A leaf next to an absent key, with its path
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Neighbour {
+    pub leaf: Vec<u8>,
+    pub path: MerklePath,
+}
+
+// The proof that a key is not a leaf: the largest leaf below it and the
+// smallest above it, either missing if the key is past that end of the tree
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NonInclusionProof {
+    pub len: u64,
+    pub lower: Option<Neighbour>,
+    pub upper: Option<Neighbour>,
+}
+
+// The root of a sorted tree: the leaf count and the plain tree's root
+fn sorted_root(len: u64, root: &Digest) -> Digest {
+    let mut count = Vec::new();
+    len.encode(&mut count);
+    Sha256::new()
+        .chain_update([2u8])
+        .chain_update(count)
+        .chain_update(root)
+        .finalize()
+        .into()
+}
+
+// The depth of a tree with len leaves
+fn depth_for(len: u64) -> Option<usize> {
+    Some(len.checked_next_power_of_two()?.trailing_zeros() as usize)
+}
+
+// The plain tree's root a path leads to, if it is a well-formed path for a tree of len leaves
+fn path_root(len: u64, path: &MerklePath, leaf: &[u8]) -> Option<Digest> {
+    if path.index >= len || Some(path.siblings.len()) != depth_for(len) {
+        return None;
+    }
+    Some(path.root(leaf))
+}
+
+impl SortedMerkleTree {
+    // Sort the leaves and drop duplicates
+    pub fn new<T: AsRef<[u8]>>(leaves: &[T]) -> Self {
+        let mut leaves: Vec<Vec<u8>> = leaves.iter().map(|leaf| leaf.as_ref().to_vec()).collect();
+        leaves.sort_unstable();
+        leaves.dedup();
+        SortedMerkleTree {
+            tree: MerkleTree::new(&leaves),
+            leaves,
+        }
+    }
+
+    pub fn root(&self) -> Digest {
+        sorted_root(self.leaves.len() as u64, &self.tree.root())
+    }
+
+    // The number of distinct leaves
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    pub fn leaves(&self) -> &[Vec<u8>] {
+        &self.leaves
+    }
+
+    // The proof that the key is a leaf, or None if it is not
+    pub fn prove_inclusion(&self, key: &[u8]) -> Option<SortedInclusionProof> {
+        let index = self
+            .leaves
+            .binary_search_by(|leaf| leaf.as_slice().cmp(key))
+            .ok()?;
+        Some(SortedInclusionProof {
+            len: self.leaves.len() as u64,
+            path: self.tree.path(index)?,
+        })
+    }
+
+    // The proof that the key is not a leaf, or None if it is one
+    pub fn prove_non_inclusion(&self, key: &[u8]) -> Option<NonInclusionProof> {
+        // The index the key would be inserted at: every leaf before it is smaller
+        let index = self
+            .leaves
+            .binary_search_by(|leaf| leaf.as_slice().cmp(key))
+            .err()?;
+        let neighbour = |i: usize| {
+            Some(Neighbour {
+                leaf: self.leaves.get(i)?.clone(),
+                path: self.tree.path(i)?,
+            })
+        };
+        Some(NonInclusionProof {
+            len: self.leaves.len() as u64,
+            lower: index.checked_sub(1).and_then(neighbour),
+            upper: neighbour(index),
+        })
+    }
+}
+
+impl SortedInclusionProof {
+    pub fn verify(&self, root: &Digest, key: &[u8]) -> bool {
+        path_root(self.len, &self.path, key).map(|inner| sorted_root(self.len, &inner))
+            == Some(*root)
+    }
+}
+
+impl NonInclusionProof {
+    pub fn verify(&self, root: &Digest, key: &[u8]) -> bool {
+        let lower = match &self.lower {
+            Some(lower) => match path_root(self.len, &lower.path, &lower.leaf) {
+                Some(inner) if lower.leaf.as_slice() < key => Some((lower.path.index, inner)),
+                _ => return false,
+            },
+            None => None,
+        };
+        let upper = match &self.upper {
+            Some(upper) => match path_root(self.len, &upper.path, &upper.leaf) {
+                Some(inner) if key < upper.leaf.as_slice() => Some((upper.path.index, inner)),
+                _ => return false,
+            },
+            None => None,
+        };
+
+        // The neighbours must be adjacent, or the first or last leaf when one is missing
+        let inner = match (lower, upper) {
+            (Some((i, lower)), Some((j, upper))) if j == i + 1 && lower == upper => lower,
+            (Some((i, lower)), None) if i + 1 == self.len => lower,
+            (None, Some((0, upper))) => upper,
+            (None, None) if self.len == 0 => EMPTY,
+            _ => return false,
+        };
+        sorted_root(self.len, &inner) == *root
+    }
+}
+
+impl Encode for MerklePath {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.index.encode(out);
+        self.siblings.encode(out);
+    }
+}
+
+impl Decode for MerklePath {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(MerklePath {
+            index: u64::decode(input)?,
+            siblings: Vec::decode(input)?,
+        })
+    }
+}
+
+impl Encode for MultiProof {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.depth.encode(out);
+        self.indices.encode(out);
+        self.nodes.encode(out);
+    }
+}
+
+impl Decode for MultiProof {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(MultiProof {
+            depth: u64::decode(input)?,
+            indices: Vec::decode(input)?,
+            nodes: Vec::decode(input)?,
+        })
+    }
+}
+
+impl Encode for SortedInclusionProof {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.len.encode(out);
+        self.path.encode(out);
+    }
+}
+
+impl Decode for SortedInclusionProof {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(SortedInclusionProof {
+            len: u64::decode(input)?,
+            path: MerklePath::decode(input)?,
+        })
+    }
+}
+
+impl Encode for Neighbour {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.leaf.encode(out);
+        self.path.encode(out);
+    }
+}
+
+impl Decode for Neighbour {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(Neighbour {
+            leaf: Vec::decode(input)?,
+            path: MerklePath::decode(input)?,
+        })
+    }
+}
+
+impl Encode for NonInclusionProof {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.len.encode(out);
+        self.lower.encode(out);
+        self.upper.encode(out);
+    }
+}
+
+impl Decode for NonInclusionProof {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(NonInclusionProof {
+            len: u64::decode(input)?,
+            lower: Option::decode(input)?,
+            upper: Option::decode(input)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaves(n: usize) -> Vec<Vec<u8>> {
+        (0..n as u64).map(|i| i.to_bytes()).collect()
+    }
+
+    #[test]
+    fn every_leaf_has_a_path_to_the_root() {
+        for n in [1, 2, 3, 8, 13] {
+            let leaves = leaves(n);
+            let tree = MerkleTree::new(&leaves);
+            for (i, leaf) in leaves.iter().enumerate() {
+                let path = tree.path(i).unwrap();
+                assert_eq!(
+                    path.siblings.len(),
+                    n.next_power_of_two().trailing_zeros() as usize
+                );
+                assert!(path.verify(&tree.root(), leaf));
+            }
+            assert!(tree.path(n).is_none());
+        }
+        assert_eq!(MerkleTree::new::<Vec<u8>>(&[]).root(), EMPTY);
+    }
+
+    #[test]
+    fn paths_do_not_verify_other_leaves_or_positions() {
+        let leaves = leaves(8);
+        let tree = MerkleTree::new(&leaves);
+        let path = tree.path(3).unwrap();
+        assert!(!path.verify(&tree.root(), &leaves[4]));
+
+        let mut moved = path.clone();
+        moved.index = 2;
+        assert!(!moved.verify(&tree.root(), &leaves[3]));
+        moved.index = 3 + 8;
+        assert!(!moved.verify(&tree.root(), &leaves[3]));
+
+        // An inner node is not a leaf: the pair below it does not hash to it as one
+        let pair = [hash_leaf(&leaves[0]), hash_leaf(&leaves[1])].concat();
+        let shortened = MerklePath {
+            index: 0,
+            siblings: path.siblings[1..].to_vec(),
+        };
+        assert!(!shortened.verify(&tree.root(), &pair));
+    }
+
+    #[test]
+    fn paths_round_trip_through_bytes() {
+        let tree = MerkleTree::new(&leaves(5));
+        let path = tree.path(4).unwrap();
+        let bytes = path.to_bytes();
+        assert_eq!(bytes.len(), 8 + 8 + 3 * 32);
+        assert_eq!(MerklePath::from_bytes(&bytes), Some(path));
+    }
+
+    #[test]
+    fn multiproofs_share_siblings() {
+        let leaves = leaves(16);
+        let tree = MerkleTree::new(&leaves);
+        let proof = tree.prove_many(&[5, 4, 9, 4]).unwrap();
+        assert_eq!(proof.indices, vec![4, 5, 9]);
+        // 4 and 5 need (6, 7) and (0..4), 9 needs 8, (10, 11) and (12..16), and the
+        // two halves of the tree meet at the root
+        assert_eq!(proof.nodes.len(), 5);
+        let opened = [&leaves[4], &leaves[5], &leaves[9]];
+        assert!(proof.verify(&tree.root(), &opened));
+
+        let separate: usize = [4, 5, 9]
+            .iter()
+            .map(|&i| tree.path(i).unwrap().siblings.len())
+            .sum();
+        assert!(proof.nodes.len() < separate);
+
+        assert!(!proof.verify(&tree.root(), &[&leaves[4], &leaves[5], &leaves[10]]));
+        assert!(!proof.verify(&tree.root(), &opened[..2]));
+        let mut extra = proof.clone();
+        extra.nodes.push(EMPTY);
+        assert!(!extra.verify(&tree.root(), &opened));
+        let mut unsorted = proof.clone();
+        unsorted.indices.swap(0, 1);
+        assert!(!unsorted.verify(&tree.root(), &[&leaves[5], &leaves[4], &leaves[9]]));
+
+        // Every leaf at once needs no siblings at all
+        let all = tree.prove_many(&(0..16).collect::<Vec<_>>()).unwrap();
+        assert!(all.nodes.is_empty());
+        assert!(all.verify(&tree.root(), &leaves));
+        assert!(tree.prove_many(&[16]).is_none());
+        assert!(tree.prove_many(&[]).is_none());
+
+        let bytes = proof.to_bytes();
+        assert_eq!(MultiProof::from_bytes(&bytes), Some(proof));
+    }
+
+    #[test]
+    fn sorted_trees_prove_absence() {
+        let words = ["pear", "apple", "fig", "kiwi", "plum", "fig"];
+        let tree = SortedMerkleTree::new(&words);
+        assert_eq!(tree.len(), 5);
+        let root = tree.root();
+
+        for word in &words {
+            assert!(tree.prove_non_inclusion(word.as_bytes()).is_none());
+            let proof = tree.prove_inclusion(word.as_bytes()).unwrap();
+            assert!(proof.verify(&root, word.as_bytes()));
+        }
+        for absent in ["banana", "aardvark", "zucchini", "figs"] {
+            assert!(tree.prove_inclusion(absent.as_bytes()).is_none());
+            let proof = tree.prove_non_inclusion(absent.as_bytes()).unwrap();
+            assert!(proof.verify(&root, absent.as_bytes()));
+            assert!(!proof.verify(&root, b"kiwi"));
+            let bytes = proof.to_bytes();
+            assert_eq!(NonInclusionProof::from_bytes(&bytes), Some(proof));
+        }
+
+        // The neighbours must be adjacent: apple and kiwi have fig between them
+        let banana = tree.prove_non_inclusion(b"banana").unwrap();
+        let gooseberry = tree.prove_non_inclusion(b"gooseberry").unwrap();
+        let skipping = NonInclusionProof {
+            upper: gooseberry.upper,
+            ..banana.clone()
+        };
+        assert!(!skipping.verify(&root, b"fig"));
+
+        // A proof cannot drop a neighbour, nor claim a different leaf count
+        let open_ended = NonInclusionProof {
+            upper: None,
+            ..banana.clone()
+        };
+        assert!(!open_ended.verify(&root, b"banana"));
+        let recounted = NonInclusionProof { len: 4, ..banana };
+        assert!(!recounted.verify(&root, b"banana"));
+
+        let empty = SortedMerkleTree::new::<&str>(&[]);
+        let proof = empty.prove_non_inclusion(b"anything").unwrap();
+        assert!(proof.verify(&empty.root(), b"anything"));
+        assert!(!proof.verify(&root, b"anything"));
+    }
+}
@@ -0,0 +1,168 @@
+/**
+ * Proof of knowledge of a Pedersen opening
+ * The prover knows (x, r) with C = g^x h^r. It commits to T = g^a h^b,
+ * receives a challenge c and answers (z1, z2) = (a + c x, b + c r), which is
+ * itself an opening of T C^c; the verifier checks exactly that. This is
+ * Okamoto's two-generator variant of the Schnorr protocol.
+ */
+use std::marker::PhantomData;
+
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    Extractor, Group, PedersenCommitment, PedersenGenerators, PedersenOpening, PrimeField,
+    SigmaProtocol, Simulator,
+};
+
+// The opening protocol; the statement is a commitment and the witness its opening
+pub struct Opening<G>(PhantomData<G>);
+
+impl<G: Group> SigmaProtocol for Opening<G> {
+    const DOMAIN: &'static [u8] = b"zero-knowledge-proof/opening/v1";
+
+    type Statement = PedersenCommitment<G>;
+    type Witness = PedersenOpening<G::Scalar>;
+    type Commitment = G;
+    type State = PedersenOpening<G::Scalar>;
+    type Challenge = G::Scalar;
+    type Response = PedersenOpening<G::Scalar>;
+
+    fn commit<R: RngCore + CryptoRng>(
+        _statement: &PedersenCommitment<G>,
+        _opening: &PedersenOpening<G::Scalar>,
+        rng: &mut R,
+    ) -> (G, PedersenOpening<G::Scalar>) {
+        let nonces = PedersenOpening {
+            value: G::Scalar::random(rng),
+            blinding: G::Scalar::random(rng),
+        };
+        let commitment = PedersenGenerators::<G>::default().commit(&nonces.value, &nonces.blinding);
+        (commitment.point, nonces)
+    }
+
+    fn respond(
+        _statement: &PedersenCommitment<G>,
+        opening: &PedersenOpening<G::Scalar>,
+        nonces: PedersenOpening<G::Scalar>,
+        challenge: &G::Scalar,
+    ) -> PedersenOpening<G::Scalar> {
+        PedersenOpening {
+            value: nonces.value + *challenge * opening.value,
+            blinding: nonces.blinding + *challenge * opening.blinding,
+        }
+    }
+
+    fn verify(
+        statement: &PedersenCommitment<G>,
+        commitment: &G,
+        challenge: &G::Scalar,
+        response: &PedersenOpening<G::Scalar>,
+    ) -> bool {
+        let target = PedersenCommitment {
+            point: *commitment + statement.point.scalar_mul(challenge),
+        };
+        PedersenGenerators::default().open(&target, response)
+    }
+}
+
+// Pick the response first and solve for the commitment: T = g^z1 h^z2 C^-c
+impl<G: Group> Simulator for Opening<G> {
+    fn simulate<R: RngCore + CryptoRng>(
+        statement: &PedersenCommitment<G>,
+        challenge: &G::Scalar,
+        rng: &mut R,
+    ) -> (G, PedersenOpening<G::Scalar>) {
+        let response = PedersenOpening {
+            value: G::Scalar::random(rng),
+            blinding: G::Scalar::random(rng),
+        };
+        let target = PedersenGenerators::<G>::default().commit(&response.value, &response.blinding);
+        (
+            target.point - statement.point.scalar_mul(challenge),
+            response,
+        )
+    }
+}
+
+// Two openings of T C^c and T C^c' differ by an opening of C^(c - c')
+impl<G: Group> Extractor for Opening<G> {
+    fn extract(
+        _statement: &PedersenCommitment<G>,
+        _commitment: &G,
+        first: (&G::Scalar, &PedersenOpening<G::Scalar>),
+        second: (&G::Scalar, &PedersenOpening<G::Scalar>),
+    ) -> Option<PedersenOpening<G::Scalar>> {
+        let scale = (*first.0 - *second.0).inverse()?;
+        let difference = *first.1 - *second.1;
+        Some(PedersenOpening {
+            value: difference.value * scale,
+            blinding: difference.blinding * scale,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Challenge, Commitment, Proof};
+    use curve25519_dalek::ristretto::RistrettoPoint;
+    use curve25519_dalek::scalar::Scalar;
+
+    type P = Opening<RistrettoPoint>;
+
+    fn statement() -> (PedersenCommitment<RistrettoPoint>, PedersenOpening<Scalar>) {
+        PedersenCommitment::commit(Scalar::from_u64(42), &mut rand::thread_rng())
+    }
+
+    #[test]
+    fn honest_transcripts_verify() {
+        let mut rng = rand::thread_rng();
+        let (commitment, opening) = statement();
+        let (first, nonces) = P::commit(&commitment, &opening, &mut rng);
+        let challenge = Scalar::challenge(&mut rng);
+        let response = P::respond(&commitment, &opening, nonces, &challenge);
+        assert!(P::verify(&commitment, &first, &challenge, &response));
+
+        let proof = Proof::prove::<P>(&commitment, &opening, &mut rng);
+        assert!(proof.verify::<P>(&commitment));
+    }
+
+    #[test]
+    fn wrong_opening_fails() {
+        let mut rng = rand::thread_rng();
+        let (commitment, opening) = statement();
+        let lie = PedersenOpening {
+            value: opening.value + Scalar::ONE,
+            ..opening
+        };
+        let proof = Proof::prove::<P>(&commitment, &lie, &mut rng);
+        assert!(!proof.verify::<P>(&commitment));
+    }
+
+    #[test]
+    fn simulated_transcripts_verify() {
+        let mut rng = rand::thread_rng();
+        let (commitment, _) = statement();
+        let challenge = Scalar::challenge(&mut rng);
+        let (first, response) = P::simulate(&commitment, &challenge, &mut rng);
+        assert!(P::verify(&commitment, &first, &challenge, &response));
+    }
+
+    #[test]
+    fn two_challenges_reveal_the_opening() {
+        let mut rng = rand::thread_rng();
+        let (commitment, opening) = statement();
+        let (first, nonces) = P::commit(&commitment, &opening, &mut rng);
+        let (c1, c2) = (Scalar::challenge(&mut rng), Scalar::challenge(&mut rng));
+        let r1 = P::respond(&commitment, &opening, nonces, &c1);
+        let r2 = P::respond(&commitment, &opening, nonces, &c2);
+        assert_eq!(
+            P::extract(&commitment, &first, (&c1, &r1), (&c2, &r2)),
+            Some(opening)
+        );
+        assert_eq!(
+            P::extract(&commitment, &first, (&c1, &r1), (&c1, &r1)),
+            None
+        );
+    }
+}
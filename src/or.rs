@@ -0,0 +1,417 @@
+/**
+ * OR-composition of sigma protocols
+ * The Cramer-Damgård-Schoenmakers construction proves knowledge of a witness
+ * for A or for B without revealing which. The prover picks the challenge of
+ * the branch it cannot answer in advance and runs that branch's simulator on
+ * it. The verifier's challenge c then fixes the other branch's challenge as c
+ * minus the simulated one, and the prover answers that branch honestly. Both
+ * branches verify and both challenge shares are uniform whichever branch was
+ * real. A prover who knows neither witness has to guess c.
+ *
+ * Both branches draw challenges from the same field, so the shares can be
+ * split by subtraction.
+ */
+use std::marker::PhantomData;
+
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    Challenge, Decode, Encode, Extractor, PrimeField, Response, SigmaProtocol, Simulator,
+    Transcript,
+};
+
+// A proof of knowledge of a witness for A's statement or for B's
+pub struct Or<A, B>(PhantomData<(A, B)>);
+
+// A witness for one of the two branches
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OrWitness<L, R> {
+    Left(L),
+    Right(R),
+}
+
+// The real branch's randomness, and the challenge and response simulated for the other one
+pub enum OrState<A: SigmaProtocol, B: SigmaProtocol> {
+    Left {
+        state: A::State,
+        challenge: A::Challenge,
+        simulated: B::Response,
+    },
+    Right {
+        state: B::State,
+        challenge: B::Challenge,
+        simulated: A::Response,
+    },
+}
+
+impl<A: SigmaProtocol, B: SigmaProtocol> Clone for OrState<A, B>
+where
+    A::State: Clone,
+    B::State: Clone,
+{
+    fn clone(&self) -> Self {
+        match self {
+            OrState::Left {
+                state,
+                challenge,
+                simulated,
+            } => OrState::Left {
+                state: state.clone(),
+                challenge: challenge.clone(),
+                simulated: simulated.clone(),
+            },
+            OrState::Right {
+                state,
+                challenge,
+                simulated,
+            } => OrState::Right {
+                state: state.clone(),
+                challenge: challenge.clone(),
+                simulated: simulated.clone(),
+            },
+        }
+    }
+}
+
+// Both branches' responses and the left branch's share of the challenge; the
+// right branch's share is whatever is left of the verifier's challenge
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OrResponse<F, L, R> {
+    pub left_challenge: F,
+    pub left: L,
+    pub right: R,
+}
+
+impl<F: Encode, L: Encode, R: Encode> Encode for OrResponse<F, L, R> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.left_challenge.encode(out);
+        self.left.encode(out);
+        self.right.encode(out);
+    }
+}
+
+impl<F: Decode, L: Decode, R: Decode> Decode for OrResponse<F, L, R> {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(OrResponse {
+            left_challenge: F::decode(input)?,
+            left: L::decode(input)?,
+            right: R::decode(input)?,
+        })
+    }
+}
+
+impl<F: PrimeField, L: Response, R: Response> Response for OrResponse<F, L, R> {}
+
+impl<A, B> SigmaProtocol for Or<A, B>
+where
+    A: Simulator,
+    A::Challenge: PrimeField,
+    B: Simulator<Challenge = A::Challenge>,
+{
+    const DOMAIN: &'static [u8] = b"zero-knowledge-proof/or/v1";
+
+    type Statement = (A::Statement, B::Statement);
+    type Witness = OrWitness<A::Witness, B::Witness>;
+    type Commitment = (A::Commitment, B::Commitment);
+    type State = OrState<A, B>;
+    type Challenge = A::Challenge;
+    type Response = OrResponse<A::Challenge, A::Response, B::Response>;
+
+    fn commit<R: RngCore + CryptoRng>(
+        statement: &Self::Statement,
+        witness: &Self::Witness,
+        rng: &mut R,
+    ) -> (Self::Commitment, OrState<A, B>) {
+        match witness {
+            OrWitness::Left(witness) => {
+                let challenge = <A::Challenge as Challenge>::challenge(rng);
+                let (simulated_commitment, simulated) = B::simulate(&statement.1, &challenge, rng);
+                let (commitment, state) = A::commit(&statement.0, witness, rng);
+                (
+                    (commitment, simulated_commitment),
+                    OrState::Left {
+                        state,
+                        challenge,
+                        simulated,
+                    },
+                )
+            }
+            OrWitness::Right(witness) => {
+                let challenge = <A::Challenge as Challenge>::challenge(rng);
+                let (simulated_commitment, simulated) = A::simulate(&statement.0, &challenge, rng);
+                let (commitment, state) = B::commit(&statement.1, witness, rng);
+                (
+                    (simulated_commitment, commitment),
+                    OrState::Right {
+                        state,
+                        challenge,
+                        simulated,
+                    },
+                )
+            }
+        }
+    }
+
+    fn respond(
+        statement: &Self::Statement,
+        witness: &Self::Witness,
+        state: OrState<A, B>,
+        challenge: &A::Challenge,
+    ) -> Self::Response {
+        match (witness, state) {
+            (
+                OrWitness::Left(witness),
+                OrState::Left {
+                    state,
+                    challenge: right_challenge,
+                    simulated,
+                },
+            ) => {
+                let left_challenge = *challenge - right_challenge;
+                OrResponse {
+                    left_challenge,
+                    left: A::respond(&statement.0, witness, state, &left_challenge),
+                    right: simulated,
+                }
+            }
+            (
+                OrWitness::Right(witness),
+                OrState::Right {
+                    state,
+                    challenge: left_challenge,
+                    simulated,
+                },
+            ) => {
+                let right_challenge = *challenge - left_challenge;
+                OrResponse {
+                    left_challenge,
+                    left: simulated,
+                    right: B::respond(&statement.1, witness, state, &right_challenge),
+                }
+            }
+            _ => panic!("the witness changed branch between commit and respond"),
+        }
+    }
+
+    fn verify(
+        statement: &Self::Statement,
+        commitment: &Self::Commitment,
+        challenge: &A::Challenge,
+        response: &Self::Response,
+    ) -> bool {
+        let right_challenge = *challenge - response.left_challenge;
+        A::verify(
+            &statement.0,
+            &commitment.0,
+            &response.left_challenge,
+            &response.left,
+        ) && B::verify(
+            &statement.1,
+            &commitment.1,
+            &right_challenge,
+            &response.right,
+        )
+    }
+
+    // The branches' names are hashed too, so a proof for one pair of protocols
+    // cannot be passed off as one for another pair with the same encodings
+    fn append_domain(transcript: &mut Transcript) {
+        transcript.append_message(b"protocol", Self::DOMAIN);
+        A::append_domain(transcript);
+        B::append_domain(transcript);
+    }
+}
+
+// Split the challenge at random and simulate both branches
+impl<A, B> Simulator for Or<A, B>
+where
+    A: Simulator,
+    A::Challenge: PrimeField,
+    B: Simulator<Challenge = A::Challenge>,
+{
+    fn simulate<R: RngCore + CryptoRng>(
+        statement: &Self::Statement,
+        challenge: &A::Challenge,
+        rng: &mut R,
+    ) -> (Self::Commitment, Self::Response) {
+        let left_challenge = <A::Challenge as Challenge>::challenge(rng);
+        let (left_commitment, left) = A::simulate(&statement.0, &left_challenge, rng);
+        let (right_commitment, right) =
+            B::simulate(&statement.1, &(*challenge - left_challenge), rng);
+        (
+            (left_commitment, right_commitment),
+            OrResponse {
+                left_challenge,
+                left,
+                right,
+            },
+        )
+    }
+}
+
+// Two different challenges differ in at least one share, and that branch's
+// transcripts reveal its witness
+impl<A, B> Extractor for Or<A, B>
+where
+    A: Simulator + Extractor,
+    A::Challenge: PrimeField,
+    B: Simulator<Challenge = A::Challenge> + Extractor,
+{
+    fn extract(
+        statement: &Self::Statement,
+        commitment: &Self::Commitment,
+        first: (&A::Challenge, &Self::Response),
+        second: (&A::Challenge, &Self::Response),
+    ) -> Option<Self::Witness> {
+        let (left_first, left_second) = (first.1.left_challenge, second.1.left_challenge);
+        if left_first != left_second {
+            return A::extract(
+                &statement.0,
+                &commitment.0,
+                (&left_first, &first.1.left),
+                (&left_second, &second.1.left),
+            )
+            .map(OrWitness::Left);
+        }
+        B::extract(
+            &statement.1,
+            &commitment.1,
+            (&(*first.0 - left_first), &first.1.right),
+            (&(*second.0 - left_second), &second.1.right),
+        )
+        .map(OrWitness::Right)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schnorr::{self, Dlog, DlogStatement};
+    use crate::{
+        fiat_shamir, run_protocol, HonestProver, HonestVerifier, PedersenCommitment,
+        PedersenGenerators, Schnorr, DEFAULT_SECURITY,
+    };
+    use curve25519_dalek::ristretto::RistrettoPoint;
+    use curve25519_dalek::scalar::Scalar;
+
+    type G = RistrettoPoint;
+    type Bit = Or<Dlog<G>, Dlog<G>>;
+
+    // C opens to 0 if C = h^r and to 1 if C / g = h^r
+    fn bit_statement(
+        generators: &PedersenGenerators<G>,
+        commitment: &PedersenCommitment<G>,
+    ) -> (DlogStatement<G>, DlogStatement<G>) {
+        let base = generators.h;
+        (
+            DlogStatement {
+                base,
+                point: commitment.point,
+            },
+            DlogStatement {
+                base,
+                point: commitment.point - generators.g,
+            },
+        )
+    }
+
+    #[test]
+    fn either_witness_convinces_the_verifier() {
+        type P = Or<Schnorr<G>, Schnorr<G>>;
+        let mut rng = rand::thread_rng();
+        let (left_secret, left_key) = schnorr::keypair::<G, _>(&mut rng);
+        let (right_secret, right_key) = schnorr::keypair::<G, _>(&mut rng);
+        let statement = (left_key, right_key);
+
+        for witness in [OrWitness::Left(left_secret), OrWitness::Right(right_secret)] {
+            let mut prover = HonestProver::<P>::new(statement, witness);
+            let mut verifier = HonestVerifier::<P>::new(statement);
+            assert!(run_protocol(&mut prover, &mut verifier, DEFAULT_SECURITY).is_accepted());
+        }
+
+        // A secret for neither key
+        let mut prover =
+            HonestProver::<P>::new(statement, OrWitness::Left(Scalar::random(&mut rng)));
+        let mut verifier = HonestVerifier::<P>::new(statement);
+        assert!(!run_protocol(&mut prover, &mut verifier, DEFAULT_SECURITY).is_accepted());
+    }
+
+    #[test]
+    fn commitment_opens_to_zero_or_one() {
+        let mut rng = rand::thread_rng();
+        let generators = PedersenGenerators::<G>::default();
+        for bit in [0u64, 1] {
+            let blinding = Scalar::random(&mut rng);
+            let commitment = generators.commit(&Scalar::from(bit), &blinding);
+            let statement = bit_statement(&generators, &commitment);
+            let witness = if bit == 0 {
+                OrWitness::Left(blinding)
+            } else {
+                OrWitness::Right(blinding)
+            };
+            let proof = fiat_shamir::prove::<Bit>(&statement, &witness, &mut rng);
+            assert!(fiat_shamir::verify::<Bit>(&statement, &proof));
+
+            // The same proof says nothing about a commitment to 2
+            let two = generators.commit(&Scalar::from(2u64), &blinding);
+            assert!(!fiat_shamir::verify::<Bit>(
+                &bit_statement(&generators, &two),
+                &proof
+            ));
+        }
+    }
+
+    #[test]
+    fn simulated_transcripts_verify() {
+        let mut rng = rand::thread_rng();
+        let generators = PedersenGenerators::<G>::default();
+        let commitment = generators.commit(&Scalar::from(5u64), &Scalar::random(&mut rng));
+        // The simulator needs no witness, not even for a commitment to neither bit
+        let statement = bit_statement(&generators, &commitment);
+        let challenge = Scalar::random(&mut rng);
+        let (commitment, response) = Bit::simulate(&statement, &challenge, &mut rng);
+        assert!(Bit::verify(&statement, &commitment, &challenge, &response));
+    }
+
+    #[test]
+    fn two_challenges_reveal_the_known_branch() {
+        let mut rng = rand::thread_rng();
+        let (left_secret, left_key) = schnorr::keypair::<G, _>(&mut rng);
+        let (right_secret, right_key) = schnorr::keypair::<G, _>(&mut rng);
+        let statement = (left_key, right_key);
+
+        for witness in [OrWitness::Left(left_secret), OrWitness::Right(right_secret)] {
+            type P = Or<Schnorr<G>, Schnorr<G>>;
+            let (commitment, state) = P::commit(&statement, &witness, &mut rng);
+            let (c1, c2) = (Scalar::random(&mut rng), Scalar::random(&mut rng));
+            let first = P::respond(&statement, &witness, state.clone(), &c1);
+            let second = P::respond(&statement, &witness, state, &c2);
+            let extracted = P::extract(&statement, &commitment, (&c1, &first), (&c2, &second));
+            assert_eq!(extracted, Some(witness));
+        }
+    }
+
+    #[test]
+    fn branch_protocols_are_bound_into_the_challenge() {
+        type P = Or<Schnorr<G>, Schnorr<G>>;
+        let mut rng = rand::thread_rng();
+        let (secret, key) = schnorr::keypair::<G, _>(&mut rng);
+        let statement = (key, key);
+        let (commitment, _) = P::commit(&statement, &OrWitness::Left(secret), &mut rng);
+
+        let derive = |mut transcript: Transcript| -> Scalar {
+            transcript.append(b"statement", &statement);
+            transcript.append(b"commitment", &commitment);
+            transcript.challenge(b"challenge")
+        };
+        let mut full = Transcript::new(b"test");
+        P::append_domain(&mut full);
+        let mut outer_only = Transcript::new(b"test");
+        outer_only.append_message(b"protocol", P::DOMAIN);
+
+        let challenge =
+            fiat_shamir::challenge::<P>(&mut Transcript::new(b"test"), &statement, &commitment);
+        assert_eq!(challenge, derive(full));
+        assert_ne!(challenge, derive(outer_only));
+    }
+}
@@ -0,0 +1,488 @@
+/**
+ * A zero knowledge proof that a committed integer is even
+ * The value x is held in a Pedersen commitment C = g^x h^r. Saying x = 2k is
+ * not enough on its own, since 2 is invertible modulo the group order and
+ * every scalar is "twice" something. The prover instead commits to each of
+ * bits 1..63 of x, proves each bit commitment opens to 0 or 1 with an OR
+ * proof, and the verifier checks that the bit commitments recombine to C.
+ * There is no commitment for bit 0, so x must be even.
+ *
+ * The recombined bits only pin x down if they cannot wrap around the group
+ * order q. They reach up to 2^64, so an order at or below that would let an odd
+ * x be opened as the even x + q; instantiating the protocol over a group whose
+ * scalars have 65 bits or fewer fails to compile.
+ */
+use std::marker::PhantomData;
+
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    Decode, Encode, Extractor, Group, PedersenCommitment, PedersenGenerators, PrimeField, Response,
+    SigmaProtocol, Simulator,
+};
+
+// The number of bits committed to, bits 1 through 63 of a u64
+pub const BITS: usize = 63;
+
+// Commit to a value under the default generators with a fresh blinding factor
+pub fn commit_value<G: Group, R: RngCore + CryptoRng>(
+    value: u64,
+    rng: &mut R,
+) -> (PedersenCommitment<G>, ParityWitness<G::Scalar>) {
+    let blinding = G::Scalar::random(rng);
+    let commitment = PedersenGenerators::default().commit(&G::Scalar::from_u64(value), &blinding);
+    (commitment, ParityWitness { value, blinding })
+}
+
+// The parity protocol; the statement is the Pedersen commitment to the value
+pub struct Parity<G>(PhantomData<G>);
+
+impl<G: Group> Parity<G> {
+    // Evaluated wherever the protocol is used, so a group order too close to
+    // 2^64 for the bits to be unambiguous is a compile error
+    const LARGE_ENOUGH: () = assert!(
+        G::Scalar::BITS > BITS as u32 + 2,
+        "the group order is too small for parity proofs"
+    );
+}
+
+// The opening of the statement's commitment
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParityWitness<F> {
+    pub value: u64,
+    pub blinding: F,
+}
+
+// A commitment to one bit, with the first messages of both branches of its OR proof
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BitCommitment<G> {
+    pub commitment: G,
+    pub zero_nonce: G,
+    pub one_nonce: G,
+}
+
+// The prover's first message
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParityCommitment<G> {
+    pub bits: Vec<BitCommitment<G>>,
+}
+
+// The randomness behind one bit's OR proof
+#[derive(Clone)]
+struct BitState<F> {
+    bit: bool,
+    blinding: F,
+    nonce: F,
+    fake_challenge: F,
+    fake_response: F,
+}
+
+// The randomness behind the prover's first message
+#[derive(Clone)]
+pub struct ParityState<F> {
+    bits: Vec<BitState<F>>,
+}
+
+// The answer for one bit: the challenge split and a response for each branch
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BitResponse<F> {
+    pub zero_challenge: F,
+    pub zero_response: F,
+    pub one_response: F,
+}
+
+// The prover's final message
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParityResponse<F> {
+    pub bits: Vec<BitResponse<F>>,
+}
+
+impl<G: Group> Encode for BitCommitment<G> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.commitment.encode(out);
+        self.zero_nonce.encode(out);
+        self.one_nonce.encode(out);
+    }
+}
+
+impl<G: Group> Decode for BitCommitment<G> {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(BitCommitment {
+            commitment: G::decode(input)?,
+            zero_nonce: G::decode(input)?,
+            one_nonce: G::decode(input)?,
+        })
+    }
+}
+
+impl<G: Group> Encode for ParityCommitment<G> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.bits.encode(out);
+    }
+}
+
+impl<G: Group> Decode for ParityCommitment<G> {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(ParityCommitment {
+            bits: Vec::decode(input)?,
+        })
+    }
+}
+
+impl<F: PrimeField> Encode for BitResponse<F> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.zero_challenge.encode(out);
+        self.zero_response.encode(out);
+        self.one_response.encode(out);
+    }
+}
+
+impl<F: PrimeField> Decode for BitResponse<F> {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(BitResponse {
+            zero_challenge: F::decode(input)?,
+            zero_response: F::decode(input)?,
+            one_response: F::decode(input)?,
+        })
+    }
+}
+
+impl<F: PrimeField> Encode for ParityResponse<F> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.bits.encode(out);
+    }
+}
+
+impl<F: PrimeField> Decode for ParityResponse<F> {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(ParityResponse {
+            bits: Vec::decode(input)?,
+        })
+    }
+}
+
+impl<F: PrimeField> Response for ParityResponse<F> {}
+
+impl<G: Group> SigmaProtocol for Parity<G> {
+    const DOMAIN: &'static [u8] = b"zero-knowledge-proof/parity/v1";
+
+    type Statement = PedersenCommitment<G>;
+    type Witness = ParityWitness<G::Scalar>;
+    type Commitment = ParityCommitment<G>;
+    type State = ParityState<G::Scalar>;
+    type Challenge = G::Scalar;
+    type Response = ParityResponse<G::Scalar>;
+
+    fn commit<R: RngCore + CryptoRng>(
+        _statement: &PedersenCommitment<G>,
+        witness: &ParityWitness<G::Scalar>,
+        rng: &mut R,
+    ) -> (ParityCommitment<G>, ParityState<G::Scalar>) {
+        let () = Self::LARGE_ENOUGH;
+        let PedersenGenerators { g, h } = PedersenGenerators::<G>::default();
+
+        // Split the blinding so that sum(2^i r_i) = r; the last share absorbs the rest
+        let mut blindings: Vec<G::Scalar> = (1..BITS).map(|_| G::Scalar::random(rng)).collect();
+        let partial = blindings
+            .iter()
+            .enumerate()
+            .fold(G::Scalar::zero(), |acc, (i, r)| {
+                acc + power_of_two::<G::Scalar>(i + 1) * *r
+            });
+        let last_weight = power_of_two::<G::Scalar>(BITS)
+            .inverse()
+            .expect("2 is invertible");
+        blindings.push((witness.blinding - partial) * last_weight);
+
+        let mut commitments = Vec::with_capacity(BITS);
+        let mut states = Vec::with_capacity(BITS);
+        for (i, blinding) in blindings.into_iter().enumerate() {
+            let bit = (witness.value >> (i + 1)) & 1 == 1;
+            let commitment = if bit {
+                g + h.scalar_mul(&blinding)
+            } else {
+                h.scalar_mul(&blinding)
+            };
+
+            // The real branch gets an honest nonce, the other one is simulated
+            let nonce = G::Scalar::random(rng);
+            let fake_challenge = G::Scalar::random(rng);
+            let fake_response = G::Scalar::random(rng);
+            let real_nonce = h.scalar_mul(&nonce);
+            let (zero_nonce, one_nonce) = if bit {
+                (
+                    h.scalar_mul(&fake_response) - commitment.scalar_mul(&fake_challenge),
+                    real_nonce,
+                )
+            } else {
+                (
+                    real_nonce,
+                    h.scalar_mul(&fake_response) - (commitment - g).scalar_mul(&fake_challenge),
+                )
+            };
+
+            commitments.push(BitCommitment {
+                commitment,
+                zero_nonce,
+                one_nonce,
+            });
+            states.push(BitState {
+                bit,
+                blinding,
+                nonce,
+                fake_challenge,
+                fake_response,
+            });
+        }
+
+        (
+            ParityCommitment { bits: commitments },
+            ParityState { bits: states },
+        )
+    }
+
+    fn respond(
+        _statement: &PedersenCommitment<G>,
+        _witness: &ParityWitness<G::Scalar>,
+        state: ParityState<G::Scalar>,
+        challenge: &G::Scalar,
+    ) -> ParityResponse<G::Scalar> {
+        let bits = state
+            .bits
+            .into_iter()
+            .map(|bit| {
+                let real_challenge = *challenge - bit.fake_challenge;
+                let real_response = bit.nonce + real_challenge * bit.blinding;
+                if bit.bit {
+                    BitResponse {
+                        zero_challenge: bit.fake_challenge,
+                        zero_response: bit.fake_response,
+                        one_response: real_response,
+                    }
+                } else {
+                    BitResponse {
+                        zero_challenge: real_challenge,
+                        zero_response: real_response,
+                        one_response: bit.fake_response,
+                    }
+                }
+            })
+            .collect();
+        ParityResponse { bits }
+    }
+
+    fn verify(
+        statement: &PedersenCommitment<G>,
+        commitment: &ParityCommitment<G>,
+        challenge: &G::Scalar,
+        response: &ParityResponse<G::Scalar>,
+    ) -> bool {
+        let () = Self::LARGE_ENOUGH;
+        if commitment.bits.len() != BITS || response.bits.len() != BITS {
+            return false;
+        }
+        let PedersenGenerators { g, h } = PedersenGenerators::<G>::default();
+
+        // The bits must recombine to the committed value, with no bit 0
+        let points: Vec<G> = commitment.bits.iter().map(|bit| bit.commitment).collect();
+        let weights: Vec<G::Scalar> = (1..=BITS).map(power_of_two).collect();
+        if G::multi_scalar_mul(&points, &weights) != statement.point {
+            return false;
+        }
+
+        // Each bit commitment opens to 0 (C = h^r) or to 1 (C / g = h^r)
+        commitment
+            .bits
+            .iter()
+            .zip(&response.bits)
+            .all(|(bit, answer)| {
+                let one_challenge = *challenge - answer.zero_challenge;
+                h.scalar_mul(&answer.zero_response)
+                    == bit.zero_nonce + bit.commitment.scalar_mul(&answer.zero_challenge)
+                    && h.scalar_mul(&answer.one_response)
+                        == bit.one_nonce + (bit.commitment - g).scalar_mul(&one_challenge)
+            })
+    }
+}
+
+impl<G: Group> Simulator for Parity<G> {
+    fn simulate<R: RngCore + CryptoRng>(
+        statement: &PedersenCommitment<G>,
+        challenge: &G::Scalar,
+        rng: &mut R,
+    ) -> (ParityCommitment<G>, ParityResponse<G::Scalar>) {
+        let () = Parity::<G>::LARGE_ENOUGH;
+        let PedersenGenerators { g, h } = PedersenGenerators::<G>::default();
+
+        // Hiding makes honest bit commitments uniform; draw all but the last and let the
+        // last one close the sum, just as the prover's last blinding share does
+        let mut points: Vec<G> = (1..BITS)
+            .map(|_| h.scalar_mul(&G::Scalar::random(rng)))
+            .collect();
+        let weights: Vec<G::Scalar> = (1..BITS).map(power_of_two).collect();
+        let last_weight = power_of_two::<G::Scalar>(BITS)
+            .inverse()
+            .expect("2 is invertible");
+        points.push(
+            (statement.point - G::multi_scalar_mul(&points, &weights)).scalar_mul(&last_weight),
+        );
+
+        // Both branches of every OR proof are simulated with a random challenge split
+        let (bits, answers) = points
+            .into_iter()
+            .map(|commitment| {
+                let zero_challenge = G::Scalar::random(rng);
+                let zero_response = G::Scalar::random(rng);
+                let one_response = G::Scalar::random(rng);
+                let one_challenge = *challenge - zero_challenge;
+                let bit = BitCommitment {
+                    commitment,
+                    zero_nonce: h.scalar_mul(&zero_response)
+                        - commitment.scalar_mul(&zero_challenge),
+                    one_nonce: h.scalar_mul(&one_response)
+                        - (commitment - g).scalar_mul(&one_challenge),
+                };
+                let answer = BitResponse {
+                    zero_challenge,
+                    zero_response,
+                    one_response,
+                };
+                (bit, answer)
+            })
+            .unzip();
+
+        (ParityCommitment { bits }, ParityResponse { bits: answers })
+    }
+}
+
+// Two answers to one commitment split their challenges differently for every bit.
+// The branch whose share changed was answered honestly both times, which reveals
+// the bit and that bit commitment's blinding.
+impl<G: Group> Extractor for Parity<G> {
+    fn extract(
+        _statement: &PedersenCommitment<G>,
+        commitment: &ParityCommitment<G>,
+        first: (&G::Scalar, &ParityResponse<G::Scalar>),
+        second: (&G::Scalar, &ParityResponse<G::Scalar>),
+    ) -> Option<ParityWitness<G::Scalar>> {
+        let bits = commitment.bits.len();
+        if first.0 == second.0 || first.1.bits.len() != bits || second.1.bits.len() != bits {
+            return None;
+        }
+
+        let mut value = 0u64;
+        let mut blinding = G::Scalar::zero();
+        for (i, (a, b)) in first.1.bits.iter().zip(&second.1.bits).enumerate() {
+            let (bit, share) = if a.zero_challenge != b.zero_challenge {
+                let share = (a.zero_response - b.zero_response)
+                    * (a.zero_challenge - b.zero_challenge).inverse()?;
+                (0, share)
+            } else {
+                // The one-branch challenges are c - c0 and c' - c0, which differ
+                let difference = *first.0 - *second.0;
+                (1, (a.one_response - b.one_response) * difference.inverse()?)
+            };
+            value |= bit << (i + 1);
+            blinding += power_of_two::<G::Scalar>(i + 1) * share;
+        }
+        Some(ParityWitness { value, blinding })
+    }
+}
+
+pub(crate) fn power_of_two<F: PrimeField>(exponent: usize) -> F {
+    (0..exponent).fold(F::one(), |power, _| power.double())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{run_protocol, Challenge, HonestProver, HonestVerifier, DEFAULT_SECURITY};
+    use curve25519_dalek::ristretto::RistrettoPoint;
+    use curve25519_dalek::scalar::Scalar;
+
+    type P = Parity<RistrettoPoint>;
+
+    fn transcript(
+        value: u64,
+    ) -> (
+        PedersenCommitment<RistrettoPoint>,
+        ParityCommitment<RistrettoPoint>,
+        Scalar,
+        ParityResponse<Scalar>,
+    ) {
+        let mut rng = rand::thread_rng();
+        let (statement, witness) = commit_value(value, &mut rng);
+        let (commitment, state) = P::commit(&statement, &witness, &mut rng);
+        let challenge = Scalar::challenge(&mut rng);
+        let response = P::respond(&statement, &witness, state, &challenge);
+        (statement, commitment, challenge, response)
+    }
+
+    #[test]
+    fn honest_prover_with_even_value_passes() {
+        for value in [0, 2, 437567812942, u64::MAX - 1] {
+            let (statement, commitment, challenge, response) = transcript(value);
+            assert!(P::verify(&statement, &commitment, &challenge, &response));
+        }
+    }
+
+    #[test]
+    fn prover_with_odd_value_fails() {
+        for value in [1, 59, u64::MAX] {
+            let (statement, commitment, challenge, response) = transcript(value);
+            assert!(!P::verify(&statement, &commitment, &challenge, &response));
+        }
+    }
+
+    #[test]
+    fn proof_does_not_transfer_to_another_commitment() {
+        let mut rng = rand::thread_rng();
+        let (other, _) = commit_value::<RistrettoPoint, _>(4, &mut rng);
+        let (_, commitment, challenge, response) = transcript(4);
+        assert!(!P::verify(&other, &commitment, &challenge, &response));
+    }
+
+    #[test]
+    fn tampered_response_fails() {
+        let (statement, commitment, challenge, mut response) = transcript(10);
+        response.bits[5].one_response += Scalar::ONE;
+        assert!(!P::verify(&statement, &commitment, &challenge, &response));
+    }
+
+    #[test]
+    fn simulated_transcripts_verify() {
+        let mut rng = rand::thread_rng();
+        // The simulator needs no witness, so it works even for an odd value
+        for value in [4, 5] {
+            let (statement, _) = commit_value::<RistrettoPoint, _>(value, &mut rng);
+            let challenge = Scalar::challenge(&mut rng);
+            let (commitment, response) = P::simulate(&statement, &challenge, &mut rng);
+            assert!(P::verify(&statement, &commitment, &challenge, &response));
+        }
+    }
+
+    #[test]
+    fn two_challenges_reveal_the_value() {
+        let mut rng = rand::thread_rng();
+        let (statement, witness) = commit_value::<RistrettoPoint, _>(437567812942, &mut rng);
+        let (commitment, state) = P::commit(&statement, &witness, &mut rng);
+        let (c1, c2) = (Scalar::challenge(&mut rng), Scalar::challenge(&mut rng));
+        let r1 = P::respond(&statement, &witness, state.clone(), &c1);
+        let r2 = P::respond(&statement, &witness, state, &c2);
+        assert_eq!(
+            P::extract(&statement, &commitment, (&c1, &r1), (&c2, &r2)),
+            Some(witness)
+        );
+    }
+
+    #[test]
+    fn interactive_rounds_all_accept() {
+        let mut rng = rand::thread_rng();
+        let (statement, witness) = commit_value(1234, &mut rng);
+        let mut prover = HonestProver::<P>::new(statement, witness);
+        let mut verifier = HonestVerifier::<P>::new(statement);
+        let result = run_protocol(&mut prover, &mut verifier, DEFAULT_SECURITY);
+        assert!(result.is_accepted());
+        assert!(result.soundness_bits >= DEFAULT_SECURITY);
+    }
+}
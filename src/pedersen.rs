@@ -0,0 +1,304 @@
+/**
+ * Pedersen commitments
+ * C = g^m h^r over a prime-order group. Perfectly hiding, since for every m
+ * some r gives the same C, and binding as long as log_g(h) is unknown. Both
+ * generators come out of hash-to-group on a public domain tag, so anyone can
+ * rederive them and check nobody chose them with a trapdoor.
+ *
+ * The vector form commits to n scalars in one point, C = g_1^m_1 ... g_n^m_n h^r,
+ * with every g_i hashed from the domain tag and its index.
+ */
+use std::ops::{Add, Sub};
+
+use rand::{CryptoRng, RngCore};
+
+use crate::{Commitment, Decode, Encode, Group, PrimeField, Response};
+
+// The domain tag the default generators are hashed from
+pub const DEFAULT_DOMAIN: &[u8] = b"zero-knowledge-proof/pedersen/v1";
+
+// The two generators of a Pedersen commitment
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PedersenGenerators<G: Group> {
+    pub g: G,
+    pub h: G,
+}
+
+impl<G: Group> PedersenGenerators<G> {
+    // Derive independent generators from a domain tag
+    pub fn new(domain: &[u8]) -> Self {
+        PedersenGenerators {
+            g: G::hash_to_group(domain, b"g"),
+            h: G::hash_to_group(domain, b"h"),
+        }
+    }
+
+    // g^value h^blinding
+    pub fn commit(&self, value: &G::Scalar, blinding: &G::Scalar) -> PedersenCommitment<G> {
+        PedersenCommitment {
+            point: self.g.scalar_mul(value) + self.h.scalar_mul(blinding),
+        }
+    }
+
+    // Check an opening against a commitment made with these generators
+    pub fn open(
+        &self,
+        commitment: &PedersenCommitment<G>,
+        opening: &PedersenOpening<G::Scalar>,
+    ) -> bool {
+        self.commit(&opening.value, &opening.blinding) == *commitment
+    }
+}
+
+// Two hashes to the group, which even in the 2048-bit MODP group cost about 1/60
+// of one exponentiation; protocols derive them once per call and pass them to
+// their helpers, as with bit_statement
+impl<G: Group> Default for PedersenGenerators<G> {
+    fn default() -> Self {
+        Self::new(DEFAULT_DOMAIN)
+    }
+}
+
+// Generators for committing to a vector of scalars in one point
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PedersenVectorGenerators<G: Group> {
+    pub g: Vec<G>,
+    pub h: G,
+}
+
+impl<G: Group> PedersenVectorGenerators<G> {
+    // Derive `length` value generators and the blinding generator from a domain tag;
+    // under the same tag, h is the h of PedersenGenerators
+    pub fn new(domain: &[u8], length: usize) -> Self {
+        PedersenVectorGenerators {
+            g: (0..length)
+                .map(|i| {
+                    G::hash_to_group(domain, &[b"g".as_ref(), &(i as u64).to_le_bytes()].concat())
+                })
+                .collect(),
+            h: G::hash_to_group(domain, b"h"),
+        }
+    }
+
+    // g_1^values_1 ... g_n^values_n h^blinding; a shorter vector is padded with zeros,
+    // and a longer one panics (the VectorCommitment impl checks first)
+    pub fn commit(&self, values: &[G::Scalar], blinding: &G::Scalar) -> PedersenCommitment<G> {
+        assert!(values.len() <= self.g.len(), "more values than generators");
+        PedersenCommitment {
+            point: G::multi_scalar_mul(&self.g[..values.len()], values)
+                + self.h.scalar_mul(blinding),
+        }
+    }
+}
+
+impl<G: Group> Encode for PedersenVectorGenerators<G> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.g.encode(out);
+        self.h.encode(out);
+    }
+}
+
+impl<G: Group> Decode for PedersenVectorGenerators<G> {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(PedersenVectorGenerators {
+            g: Vec::decode(input)?,
+            h: G::decode(input)?,
+        })
+    }
+}
+
+// A commitment to a scalar
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PedersenCommitment<G: Group> {
+    pub point: G,
+}
+
+// The value and blinding factor behind a commitment
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PedersenOpening<F> {
+    pub value: F,
+    pub blinding: F,
+}
+
+impl<G: Group> PedersenCommitment<G> {
+    // A commitment to k * m under blinding k * r
+    pub fn scale(&self, k: &G::Scalar) -> Self {
+        PedersenCommitment {
+            point: self.point.scalar_mul(k),
+        }
+    }
+}
+
+impl<G: Group> Encode for PedersenCommitment<G> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.point.encode(out);
+    }
+}
+
+impl<G: Group> Decode for PedersenCommitment<G> {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(PedersenCommitment {
+            point: G::decode(input)?,
+        })
+    }
+}
+
+impl<F: PrimeField> Encode for PedersenOpening<F> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.value.encode(out);
+        self.blinding.encode(out);
+    }
+}
+
+impl<F: PrimeField> Decode for PedersenOpening<F> {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(PedersenOpening {
+            value: F::decode(input)?,
+            blinding: F::decode(input)?,
+        })
+    }
+}
+
+// An opening is the response of the proof of knowledge of an opening
+impl<F: PrimeField> Response for PedersenOpening<F> {}
+
+impl<G: Group> Commitment for PedersenCommitment<G> {
+    type Value = G::Scalar;
+    type Randomness = G::Scalar;
+    type Opening = PedersenOpening<G::Scalar>;
+
+    fn commit<R: RngCore + CryptoRng>(value: G::Scalar, rng: &mut R) -> (Self, Self::Opening) {
+        let blinding = G::Scalar::random(rng);
+        (
+            Self::commit_with(&value, &blinding),
+            PedersenOpening { value, blinding },
+        )
+    }
+
+    fn commit_with(value: &G::Scalar, blinding: &G::Scalar) -> Self {
+        PedersenGenerators::default().commit(value, blinding)
+    }
+
+    fn open(&self, opening: &Self::Opening) -> Option<G::Scalar> {
+        if PedersenGenerators::default().open(self, opening) {
+            Some(opening.value)
+        } else {
+            None
+        }
+    }
+}
+
+// Adding commitments commits to the sum of the values under the sum of the blindings
+impl<G: Group> Add for PedersenCommitment<G> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        PedersenCommitment {
+            point: self.point + other.point,
+        }
+    }
+}
+
+impl<G: Group> Sub for PedersenCommitment<G> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        PedersenCommitment {
+            point: self.point - other.point,
+        }
+    }
+}
+
+impl<F: PrimeField> Add for PedersenOpening<F> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        PedersenOpening {
+            value: self.value + other.value,
+            blinding: self.blinding + other.blinding,
+        }
+    }
+}
+
+impl<F: PrimeField> Sub for PedersenOpening<F> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        PedersenOpening {
+            value: self.value - other.value,
+            blinding: self.blinding - other.blinding,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use curve25519_dalek::ristretto::RistrettoPoint;
+    use curve25519_dalek::scalar::Scalar;
+
+    type Com = PedersenCommitment<RistrettoPoint>;
+
+    #[test]
+    fn opens_to_committed_value() {
+        let mut rng = rand::thread_rng();
+        let (commitment, opening) = Com::commit(Scalar::from(42u64), &mut rng);
+        assert_eq!(commitment.open(&opening), Some(Scalar::from(42u64)));
+
+        let wrong = PedersenOpening {
+            value: Scalar::from(43u64),
+            ..opening
+        };
+        assert_eq!(commitment.open(&wrong), None);
+    }
+
+    #[test]
+    fn commitments_add_homomorphically() {
+        let mut rng = rand::thread_rng();
+        let (a, a_opening) = Com::commit(Scalar::from(5u64), &mut rng);
+        let (b, b_opening) = Com::commit(Scalar::from(7u64), &mut rng);
+
+        let sum_opening = a_opening + b_opening;
+        assert_eq!((a + b).open(&sum_opening), Some(Scalar::from(12u64)));
+        assert_eq!(
+            (b - a).open(&(b_opening - a_opening)),
+            Some(Scalar::from(2u64))
+        );
+
+        let k = Scalar::from(3u64);
+        let scaled = PedersenOpening {
+            value: a_opening.value * k,
+            blinding: a_opening.blinding * k,
+        };
+        assert_eq!(a.scale(&k).open(&scaled), Some(Scalar::from(15u64)));
+    }
+
+    #[test]
+    fn generators_are_reproducible_and_independent() {
+        let generators = PedersenGenerators::<RistrettoPoint>::default();
+        assert_eq!(generators, PedersenGenerators::new(DEFAULT_DOMAIN));
+        assert_ne!(generators.g, generators.h);
+        assert_ne!(generators, PedersenGenerators::new(b"another domain"));
+    }
+
+    #[test]
+    fn vector_commitments_bind_every_position() {
+        let mut rng = rand::thread_rng();
+        let generators = PedersenVectorGenerators::<RistrettoPoint>::new(DEFAULT_DOMAIN, 4);
+        assert_eq!(generators.h, PedersenGenerators::default().h);
+
+        let values: Vec<Scalar> = (1..=4u64).map(Scalar::from).collect();
+        let blinding = Scalar::random(&mut rng);
+        let commitment = generators.commit(&values, &blinding);
+        let mut swapped = values.clone();
+        swapped.swap(0, 1);
+        assert_ne!(generators.commit(&swapped, &blinding), commitment);
+
+        // Commitments add position by position
+        let sum: Vec<Scalar> = values.iter().map(|v| v + v).collect();
+        assert_eq!(
+            generators.commit(&sum, &(blinding + blinding)),
+            commitment + commitment
+        );
+    }
+}
@@ -0,0 +1,167 @@
+/**
+ * Non-interactive proofs
+ * A proof records the prover's commitment, the Fiat-Shamir challenge and the
+ * prover's response. Protocols that run for several rounds produce one Proof
+ * per round, all drawn from one transcript so that every challenge depends on
+ * the rounds before it.
+ *
+ * The byte format is stable: the commitment, challenge and response in their
+ * canonical encodings, back to back, and a list of rounds as a u64 count
+ * followed by the rounds.
+ */
+use rand::{CryptoRng, RngCore};
+
+use crate::fiat_shamir::{self, TRANSCRIPT_LABEL};
+use crate::{Decode, Encode, SigmaProtocol, Transcript};
+
+// A struct for holding the commitments, challenges, and responses in a zero-knowledge proof
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Proof<C, Ch, R> {
+    pub commitment: C,
+    pub challenge: Ch,
+    pub response: R,
+}
+
+impl<C, Ch, R> Proof<C, Ch, R> {
+    // Prove a statement of protocol P in a single round
+    pub fn prove<P>(
+        statement: &P::Statement,
+        witness: &P::Witness,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> Self
+    where
+        P: SigmaProtocol<Commitment = C, Challenge = Ch, Response = R>,
+    {
+        fiat_shamir::prove::<P>(statement, witness, rng)
+    }
+
+    // Check a single-round proof
+    pub fn verify<P>(&self, statement: &P::Statement) -> bool
+    where
+        P: SigmaProtocol<Commitment = C, Challenge = Ch, Response = R>,
+    {
+        fiat_shamir::verify::<P>(statement, self)
+    }
+
+    // Prove a statement over several rounds of protocol P
+    pub fn prove_rounds<P>(
+        statement: &P::Statement,
+        witness: &P::Witness,
+        rounds: usize,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> Vec<Self>
+    where
+        P: SigmaProtocol<Commitment = C, Challenge = Ch, Response = R>,
+    {
+        let mut transcript = Transcript::new(TRANSCRIPT_LABEL);
+        transcript.append_u64(b"rounds", rounds as u64);
+        (0..rounds)
+            .map(|_| {
+                fiat_shamir::prove_with_transcript::<P>(&mut transcript, statement, witness, rng)
+            })
+            .collect()
+    }
+
+    // Check every round of a multi-round proof; an empty proof proves nothing
+    pub fn verify_rounds<P>(statement: &P::Statement, proofs: &[Self]) -> bool
+    where
+        P: SigmaProtocol<Commitment = C, Challenge = Ch, Response = R>,
+    {
+        let mut transcript = Transcript::new(TRANSCRIPT_LABEL);
+        transcript.append_u64(b"rounds", proofs.len() as u64);
+        !proofs.is_empty()
+            && proofs.iter().all(|proof| {
+                fiat_shamir::verify_with_transcript::<P>(&mut transcript, statement, proof)
+            })
+    }
+}
+
+impl<C: Encode, Ch: Encode, R: Encode> Encode for Proof<C, Ch, R> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.commitment.encode(out);
+        self.challenge.encode(out);
+        self.response.encode(out);
+    }
+}
+
+impl<C: Decode, Ch: Decode, R: Decode> Decode for Proof<C, Ch, R> {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(Proof {
+            commitment: C::decode(input)?,
+            challenge: Ch::decode(input)?,
+            response: R::decode(input)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parity::{self, Parity};
+    use crate::schnorr::{self, Schnorr, SchnorrProof};
+    use crate::PrimeField;
+    use curve25519_dalek::ristretto::RistrettoPoint;
+    use curve25519_dalek::scalar::Scalar;
+
+    type S = Schnorr<RistrettoPoint>;
+    type P = Parity<RistrettoPoint>;
+
+    #[test]
+    fn schnorr_proof_has_a_fixed_layout() {
+        let mut rng = rand::thread_rng();
+        let (secret, public_key) = schnorr::keypair::<RistrettoPoint, _>(&mut rng);
+        let proof = Proof::prove::<S>(&public_key, &secret, &mut rng);
+
+        let bytes = proof.to_bytes();
+        assert_eq!(bytes.len(), 96);
+        assert_eq!(&bytes[..32], proof.commitment.to_bytes().as_slice());
+        assert_eq!(&bytes[32..64], proof.challenge.as_bytes());
+        assert_eq!(&bytes[64..], proof.response.as_bytes());
+
+        let decoded = SchnorrProof::<RistrettoPoint>::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, proof);
+        assert!(decoded.verify::<S>(&public_key));
+        assert_eq!(
+            SchnorrProof::<RistrettoPoint>::from_bytes(&bytes[..95]),
+            None
+        );
+    }
+
+    #[test]
+    fn parity_proof_round_trips() {
+        let mut rng = rand::thread_rng();
+        let (statement, witness) = parity::commit_value(64, &mut rng);
+        let proof = Proof::prove::<P>(&statement, &witness, &mut rng);
+        let decoded = Proof::from_bytes(&proof.to_bytes()).unwrap();
+        assert_eq!(decoded, proof);
+        assert!(decoded.verify::<P>(&statement));
+    }
+
+    #[test]
+    fn rounds_are_chained() {
+        let mut rng = rand::thread_rng();
+        let (secret, public_key) = schnorr::keypair::<RistrettoPoint, _>(&mut rng);
+        let proofs = Proof::prove_rounds::<S>(&public_key, &secret, 3, &mut rng);
+        assert!(Proof::verify_rounds::<S>(&public_key, &proofs));
+
+        let decoded: Vec<SchnorrProof<RistrettoPoint>> =
+            Vec::from_bytes(&proofs.to_bytes()).unwrap();
+        assert!(Proof::verify_rounds::<S>(&public_key, &decoded));
+
+        // Rounds cannot be dropped, reordered or verified on their own
+        assert!(!Proof::verify_rounds::<S>(&public_key, &proofs[..2]));
+        let swapped = vec![proofs[1].clone(), proofs[0].clone(), proofs[2].clone()];
+        assert!(!Proof::verify_rounds::<S>(&public_key, &swapped));
+        assert!(!proofs[1].verify::<S>(&public_key));
+        assert!(!Proof::verify_rounds::<S>(&public_key, &[]));
+    }
+
+    #[test]
+    fn tampered_challenge_is_rejected() {
+        let mut rng = rand::thread_rng();
+        let (secret, public_key) = schnorr::keypair::<RistrettoPoint, _>(&mut rng);
+        let mut proof = Proof::prove::<S>(&public_key, &secret, &mut rng);
+        proof.challenge += Scalar::one();
+        assert!(!proof.verify::<S>(&public_key));
+    }
+}
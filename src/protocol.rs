@@ -0,0 +1,294 @@
+/**
+ * A generic driver for interactive zero knowledge protocols
+ * The prover and verifier exchange a commitment, a challenge and a response.
+ * A single round only bounds a cheating prover's success by the protocol's
+ * soundness error, so the driver repeats the protocol until the combined error
+ * is below 2^-λ for a security parameter λ, and accepts only if every round
+ * passes. The result reports that bound rather than a ratio of accepted rounds.
+ */
+use rand::rngs::ThreadRng;
+
+use crate::{Challenge, SigmaProtocol};
+
+// The security parameter used when the caller has no particular requirement
+pub const DEFAULT_SECURITY: u32 = 128;
+
+// The prover side of an interactive protocol
+pub trait Prover {
+    type Commitment;
+    type Challenge;
+    type Response;
+
+    // Produce the commitment that opens a round
+    fn commit(&mut self) -> Self::Commitment;
+    // Answer the verifier's challenge for the current round
+    fn respond(&mut self, challenge: &Self::Challenge) -> Self::Response;
+}
+
+// The verifier side of an interactive protocol
+pub trait Verifier {
+    type Commitment;
+    type Challenge;
+    type Response;
+
+    // The per-round soundness error as a power of two, 2^-soundness_bits
+    fn soundness_bits(&self) -> u32;
+    // Pick a challenge after seeing the prover's commitment
+    fn challenge(&mut self, commitment: &Self::Commitment) -> Self::Challenge;
+    // Decide whether the round is accepted
+    fn verify(
+        &mut self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> bool;
+}
+
+// The number of rounds needed to push a per-round error of 2^-bits below 2^-security
+// A const fn, so it can size a Repeated protocol at compile time
+pub const fn rounds_for(security: u32, bits: u32) -> usize {
+    assert!(bits > 0, "a round with no soundness cannot be amplified");
+    let rounds = security.div_ceil(bits);
+    if rounds == 0 {
+        1
+    } else {
+        rounds as usize
+    }
+}
+
+// The outcome of running a protocol at a given security level
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProtocolResult {
+    // The number of rounds the security parameter requires
+    pub rounds: usize,
+    // The number of rounds that passed before the first rejection, if any
+    pub accepted: usize,
+    // The combined soundness error of all rounds as a power of two, 2^-soundness_bits
+    pub soundness_bits: u32,
+}
+
+impl ProtocolResult {
+    // Whether the verifier accepts: every required round must pass
+    pub fn is_accepted(&self) -> bool {
+        self.rounds > 0 && self.accepted == self.rounds
+    }
+
+    // The probability that a prover without a witness is accepted, at most 2^-soundness_bits.
+    // Bounds below the smallest f64 round to zero; soundness_bits is exact.
+    pub fn cheating_probability(&self) -> f64 {
+        2f64.powi(-(self.soundness_bits.min(i32::MAX as u32) as i32))
+    }
+}
+
+// Repeat commit, challenge, respond and verify until the cheating probability is at most
+// 2^-security, stopping at the first rejected round
+pub fn run_protocol<P, V>(prover: &mut P, verifier: &mut V, security: u32) -> ProtocolResult
+where
+    P: Prover,
+    V: Verifier<Commitment = P::Commitment, Challenge = P::Challenge, Response = P::Response>,
+{
+    let bits = verifier.soundness_bits();
+    let rounds = rounds_for(security, bits);
+    let mut accepted = 0;
+
+    for _ in 0..rounds {
+        // The prover creates a commitment to the value
+        let commitment = prover.commit();
+
+        // The verifier creates a challenge based on the commitment
+        let challenge = verifier.challenge(&commitment);
+
+        // The prover creates a response to the challenge
+        let response = prover.respond(&challenge);
+
+        // The verifier verifies the response using the commitment and challenge
+        if !verifier.verify(&commitment, &challenge, &response) {
+            break;
+        }
+        accepted += 1;
+    }
+
+    ProtocolResult {
+        rounds,
+        accepted,
+        soundness_bits: bits.saturating_mul(rounds as u32),
+    }
+}
+
+// A prover that follows a sigma protocol with the witness it knows
+pub struct HonestProver<P: SigmaProtocol> {
+    statement: P::Statement,
+    witness: P::Witness,
+    state: Option<P::State>,
+    rng: ThreadRng,
+}
+
+impl<P: SigmaProtocol> HonestProver<P> {
+    pub fn new(statement: P::Statement, witness: P::Witness) -> Self {
+        HonestProver {
+            statement,
+            witness,
+            state: None,
+            rng: rand::thread_rng(),
+        }
+    }
+}
+
+// Cloning a prover between its commitment and response rewinds it, so the same
+// commitment can be answered twice
+impl<P: SigmaProtocol> Clone for HonestProver<P>
+where
+    P::Statement: Clone,
+    P::Witness: Clone,
+    P::State: Clone,
+{
+    fn clone(&self) -> Self {
+        HonestProver {
+            statement: self.statement.clone(),
+            witness: self.witness.clone(),
+            state: self.state.clone(),
+            rng: self.rng.clone(),
+        }
+    }
+}
+
+impl<P: SigmaProtocol> Prover for HonestProver<P> {
+    type Commitment = P::Commitment;
+    type Challenge = P::Challenge;
+    type Response = P::Response;
+
+    fn commit(&mut self) -> P::Commitment {
+        let (commitment, state) = P::commit(&self.statement, &self.witness, &mut self.rng);
+        self.state = Some(state);
+        commitment
+    }
+
+    fn respond(&mut self, challenge: &P::Challenge) -> P::Response {
+        let state = self.state.take().expect("respond called before commit");
+        P::respond(&self.statement, &self.witness, state, challenge)
+    }
+}
+
+// A verifier that draws uniformly random challenges and checks transcripts
+pub struct HonestVerifier<P: SigmaProtocol> {
+    statement: P::Statement,
+    rng: ThreadRng,
+}
+
+impl<P: SigmaProtocol> HonestVerifier<P> {
+    pub fn new(statement: P::Statement) -> Self {
+        HonestVerifier {
+            statement,
+            rng: rand::thread_rng(),
+        }
+    }
+}
+
+impl<P: SigmaProtocol> Verifier for HonestVerifier<P> {
+    type Commitment = P::Commitment;
+    type Challenge = P::Challenge;
+    type Response = P::Response;
+
+    fn soundness_bits(&self) -> u32 {
+        P::SOUNDNESS_BITS
+    }
+
+    fn challenge(&mut self, _commitment: &P::Commitment) -> P::Challenge {
+        P::Challenge::challenge(&mut self.rng)
+    }
+
+    fn verify(
+        &mut self,
+        commitment: &P::Commitment,
+        challenge: &P::Challenge,
+        response: &P::Response,
+    ) -> bool {
+        P::verify(&self.statement, commitment, challenge, response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A toy protocol with one-bit challenges: the prover answers with the
+    // challenge it was sent if it is honest, and always with `true` if not
+    struct CoinProver {
+        honest: bool,
+    }
+
+    impl Prover for CoinProver {
+        type Commitment = ();
+        type Challenge = bool;
+        type Response = bool;
+
+        fn commit(&mut self) {}
+
+        fn respond(&mut self, challenge: &bool) -> bool {
+            if self.honest {
+                *challenge
+            } else {
+                true
+            }
+        }
+    }
+
+    struct CoinVerifier {
+        challenges: usize,
+        rng: ThreadRng,
+    }
+
+    impl Verifier for CoinVerifier {
+        type Commitment = ();
+        type Challenge = bool;
+        type Response = bool;
+
+        fn soundness_bits(&self) -> u32 {
+            1
+        }
+
+        fn challenge(&mut self, _commitment: &()) -> bool {
+            self.challenges += 1;
+            rand::Rng::gen(&mut self.rng)
+        }
+
+        fn verify(&mut self, _commitment: &(), challenge: &bool, response: &bool) -> bool {
+            challenge == response
+        }
+    }
+
+    fn coin_verifier() -> CoinVerifier {
+        CoinVerifier {
+            challenges: 0,
+            rng: rand::thread_rng(),
+        }
+    }
+
+    #[test]
+    fn rounds_cover_the_security_parameter() {
+        assert_eq!(rounds_for(128, 1), 128);
+        assert_eq!(rounds_for(128, 252), 1);
+        assert_eq!(rounds_for(128, 40), 4);
+        assert_eq!(rounds_for(0, 40), 1);
+    }
+
+    #[test]
+    fn honest_prover_passes_every_round() {
+        let mut verifier = coin_verifier();
+        let result = run_protocol(&mut CoinProver { honest: true }, &mut verifier, 40);
+        assert_eq!(result.rounds, 40);
+        assert_eq!(verifier.challenges, 40);
+        assert!(result.is_accepted());
+        assert_eq!(result.soundness_bits, 40);
+        assert_eq!(result.cheating_probability(), 2f64.powi(-40));
+    }
+
+    #[test]
+    fn cheating_prover_is_stopped_at_the_first_failure() {
+        let mut verifier = coin_verifier();
+        let result = run_protocol(&mut CoinProver { honest: false }, &mut verifier, 128);
+        // Guessing 128 fair coins in a row happens with probability 2^-128
+        assert!(!result.is_accepted());
+        assert_eq!(verifier.challenges, result.accepted + 1);
+    }
+}
@@ -0,0 +1,545 @@
+/**
+ * A zero knowledge proof that a committed integer lies in [0, 2^N)
+ * The value x is held in a Pedersen commitment C = g^x h^r. The prover commits
+ * to each bit of x as C_i = g^b_i h^r_i, with blindings chosen so that the
+ * product of the C_i^(2^i) is C, and proves every C_i opens to 0 or 1 with the
+ * OR combinator over two discrete-log proofs base h. The verifier recombines
+ * the bit commitments and checks them against C, so x is a sum of N bits.
+ *
+ * Other bounds reduce to this one by shifting the commitment: a proof for
+ * C / g^a shows x >= a, and one for g^(b - 1) / C shows x < b. Both hold as
+ * integer inequalities as long as the bounds plus 2^N stay below the group
+ * order. Both are enforced: N must be below the bit length of the order, or
+ * the prover refuses and the verifier rejects, and shifting by a u64 bound
+ * needs an order above 2^65. The proof grows linearly in N; a Bulletproofs
+ * range proof does the same in logarithmic size.
+ */
+use std::marker::PhantomData;
+
+use rand::{CryptoRng, RngCore};
+
+use crate::or::OrState;
+use crate::parity::power_of_two;
+use crate::schnorr::{Dlog, DlogStatement};
+use crate::{
+    Decode, Encode, Extractor, Group, Or, OrResponse, OrWitness, PedersenCommitment,
+    PedersenGenerators, PrimeField, Response, SigmaProtocol, Simulator, Transcript,
+};
+
+// The proof that one bit commitment opens to 0 (left) or 1 (right)
+pub type BitProof<G> = Or<Dlog<G>, Dlog<G>>;
+
+// Commit to a value under the default generators with a fresh blinding factor
+pub fn commit_value<G: Group, R: RngCore + CryptoRng>(
+    value: u64,
+    rng: &mut R,
+) -> (PedersenCommitment<G>, RangeWitness<G::Scalar>) {
+    let blinding = G::Scalar::random(rng);
+    let commitment = PedersenGenerators::default().commit(&G::Scalar::from_u64(value), &blinding);
+    (commitment, RangeWitness { value, blinding })
+}
+
+// Whether N-bit proofs over G are sound: 2^N values cannot wrap past the group order
+pub fn supported<G: Group, const N: usize>() -> bool {
+    N > 0 && N <= 64 && (N as u32) < G::Scalar::BITS
+}
+
+// A u64 bound plus up to 2^64 stays below 2^65, so the order needs more than 65 bits
+fn shifts_supported<G: Group>() -> bool {
+    G::Scalar::BITS > 65
+}
+
+// The commitment to x - bound under the generators it was made with, whose range
+// proof shows x >= bound; None if the group is too small for the shift to hold
+// as an integer inequality
+pub fn at_least<G: Group>(
+    generators: &PedersenGenerators<G>,
+    commitment: &PedersenCommitment<G>,
+    bound: u64,
+) -> Option<PedersenCommitment<G>> {
+    if !shifts_supported::<G>() {
+        return None;
+    }
+    Some(PedersenCommitment {
+        point: commitment.point - generators.g.scalar_mul(&G::Scalar::from_u64(bound)),
+    })
+}
+
+// The commitment to bound - 1 - x, whose range proof shows x < bound; None under
+// the same condition as at_least, or for a bound of 0, where bound - 1 would wrap
+// to q - 1 and a commitment to -1 would pass
+pub fn below<G: Group>(
+    generators: &PedersenGenerators<G>,
+    commitment: &PedersenCommitment<G>,
+    bound: u64,
+) -> Option<PedersenCommitment<G>> {
+    if bound == 0 || !shifts_supported::<G>() {
+        return None;
+    }
+    let limit = G::Scalar::from_u64(bound) - G::Scalar::one();
+    Some(PedersenCommitment {
+        point: generators.g.scalar_mul(&limit) - commitment.point,
+    })
+}
+
+// The statements of a bit commitment's two branches: C = h^r, or C / g = h^r
+pub fn bit_statement<G: Group>(
+    generators: &PedersenGenerators<G>,
+    commitment: G,
+) -> (DlogStatement<G>, DlogStatement<G>) {
+    (
+        DlogStatement {
+            base: generators.h,
+            point: commitment,
+        },
+        DlogStatement {
+            base: generators.h,
+            point: commitment - generators.g,
+        },
+    )
+}
+
+// The range protocol for N-bit values; the statement is the Pedersen commitment to the value
+pub struct Range<G, const N: usize>(PhantomData<G>);
+
+// The opening of the statement's commitment
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RangeWitness<F> {
+    pub value: u64,
+    pub blinding: F,
+}
+
+impl<F: PrimeField> RangeWitness<F> {
+    // The opening of at_least(C, bound), if the value reaches the bound
+    pub fn at_least(&self, bound: u64) -> Option<Self> {
+        Some(RangeWitness {
+            value: self.value.checked_sub(bound)?,
+            blinding: self.blinding,
+        })
+    }
+
+    // The opening of below(C, bound), if the value is under the bound
+    pub fn below(&self, bound: u64) -> Option<Self> {
+        Some(RangeWitness {
+            value: bound.checked_sub(1)?.checked_sub(self.value)?,
+            blinding: -self.blinding,
+        })
+    }
+}
+
+// The prover's first message: the bit commitments and their OR proofs' commitments
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RangeCommitment<G> {
+    pub bits: Vec<G>,
+    pub proofs: Vec<(G, G)>,
+}
+
+// The randomness behind one bit's OR proof
+#[derive(Clone)]
+struct BitState<G: Group> {
+    commitment: G,
+    witness: OrWitness<G::Scalar, G::Scalar>,
+    state: OrState<Dlog<G>, Dlog<G>>,
+}
+
+// The randomness behind the prover's first message
+#[derive(Clone)]
+pub struct RangeState<G: Group> {
+    bits: Vec<BitState<G>>,
+}
+
+// The prover's final message, one OR response per bit
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RangeResponse<F> {
+    pub bits: Vec<OrResponse<F, F, F>>,
+}
+
+impl<G: Group> Encode for RangeCommitment<G> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.bits.encode(out);
+        self.proofs.encode(out);
+    }
+}
+
+impl<G: Group> Decode for RangeCommitment<G> {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(RangeCommitment {
+            bits: Vec::decode(input)?,
+            proofs: Vec::decode(input)?,
+        })
+    }
+}
+
+impl<F: PrimeField> Encode for RangeResponse<F> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.bits.encode(out);
+    }
+}
+
+impl<F: PrimeField> Decode for RangeResponse<F> {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(RangeResponse {
+            bits: Vec::decode(input)?,
+        })
+    }
+}
+
+impl<F: PrimeField> Response for RangeResponse<F> {}
+
+impl<G: Group, const N: usize> SigmaProtocol for Range<G, N> {
+    const DOMAIN: &'static [u8] = b"zero-knowledge-proof/range/v1";
+
+    type Statement = PedersenCommitment<G>;
+    type Witness = RangeWitness<G::Scalar>;
+    type Commitment = RangeCommitment<G>;
+    type State = RangeState<G>;
+    type Challenge = G::Scalar;
+    type Response = RangeResponse<G::Scalar>;
+
+    fn commit<R: RngCore + CryptoRng>(
+        _statement: &PedersenCommitment<G>,
+        witness: &RangeWitness<G::Scalar>,
+        rng: &mut R,
+    ) -> (RangeCommitment<G>, RangeState<G>) {
+        assert!(N > 0 && N <= 64, "a u64 witness has between 1 and 64 bits");
+        assert!(
+            supported::<G, N>(),
+            "the group order is too small for N-bit range proofs"
+        );
+        let generators = PedersenGenerators::<G>::default();
+
+        // Split the blinding so that sum(2^i r_i) = r; bit 0 absorbs the rest
+        let mut blindings: Vec<G::Scalar> = (0..N).map(|_| G::Scalar::random(rng)).collect();
+        let rest = blindings
+            .iter()
+            .enumerate()
+            .skip(1)
+            .fold(witness.blinding, |acc, (i, r)| {
+                acc - power_of_two::<G::Scalar>(i) * *r
+            });
+        blindings[0] = rest;
+
+        let mut bits = Vec::with_capacity(N);
+        let mut proofs = Vec::with_capacity(N);
+        let mut states = Vec::with_capacity(N);
+        for (i, blinding) in blindings.into_iter().enumerate() {
+            let one = (witness.value >> i) & 1 == 1;
+            let (commitment, witness) = if one {
+                (
+                    generators.g + generators.h.scalar_mul(&blinding),
+                    OrWitness::Right(blinding),
+                )
+            } else {
+                (
+                    generators.h.scalar_mul(&blinding),
+                    OrWitness::Left(blinding),
+                )
+            };
+            let statement = bit_statement(&generators, commitment);
+            let (proof, state) = BitProof::<G>::commit(&statement, &witness, rng);
+            bits.push(commitment);
+            proofs.push(proof);
+            states.push(BitState {
+                commitment,
+                witness,
+                state,
+            });
+        }
+
+        (
+            RangeCommitment { bits, proofs },
+            RangeState { bits: states },
+        )
+    }
+
+    fn respond(
+        _statement: &PedersenCommitment<G>,
+        _witness: &RangeWitness<G::Scalar>,
+        state: RangeState<G>,
+        challenge: &G::Scalar,
+    ) -> RangeResponse<G::Scalar> {
+        let generators = PedersenGenerators::<G>::default();
+        let bits = state
+            .bits
+            .into_iter()
+            .map(|bit| {
+                let statement = bit_statement(&generators, bit.commitment);
+                BitProof::<G>::respond(&statement, &bit.witness, bit.state, challenge)
+            })
+            .collect();
+        RangeResponse { bits }
+    }
+
+    fn verify(
+        statement: &PedersenCommitment<G>,
+        commitment: &RangeCommitment<G>,
+        challenge: &G::Scalar,
+        response: &RangeResponse<G::Scalar>,
+    ) -> bool {
+        if !supported::<G, N>()
+            || commitment.bits.len() != N
+            || commitment.proofs.len() != N
+            || response.bits.len() != N
+        {
+            return false;
+        }
+        let generators = PedersenGenerators::<G>::default();
+
+        // The bits must recombine to the committed value
+        let weights: Vec<G::Scalar> = (0..N).map(power_of_two).collect();
+        if G::multi_scalar_mul(&commitment.bits, &weights) != statement.point {
+            return false;
+        }
+
+        commitment
+            .bits
+            .iter()
+            .zip(&commitment.proofs)
+            .zip(&response.bits)
+            .all(|((bit, proof), answer)| {
+                BitProof::<G>::verify(&bit_statement(&generators, *bit), proof, challenge, answer)
+            })
+    }
+
+    fn append_domain(transcript: &mut Transcript) {
+        transcript.append_message(b"protocol", Self::DOMAIN);
+        transcript.append_u64(b"bits", N as u64);
+    }
+}
+
+impl<G: Group, const N: usize> Simulator for Range<G, N> {
+    fn simulate<R: RngCore + CryptoRng>(
+        statement: &PedersenCommitment<G>,
+        challenge: &G::Scalar,
+        rng: &mut R,
+    ) -> (RangeCommitment<G>, RangeResponse<G::Scalar>) {
+        let generators = PedersenGenerators::<G>::default();
+
+        // Honest bit commitments are uniform; draw all but bit 0 and let it close the sum
+        let mut bits: Vec<G> = (0..N)
+            .map(|_| generators.h.scalar_mul(&G::Scalar::random(rng)))
+            .collect();
+        let weights: Vec<G::Scalar> = (1..N).map(power_of_two).collect();
+        if let Some((first, rest)) = bits.split_first_mut() {
+            *first = statement.point - G::multi_scalar_mul(rest, &weights);
+        }
+
+        let (proofs, answers) = bits
+            .iter()
+            .map(|bit| BitProof::<G>::simulate(&bit_statement(&generators, *bit), challenge, rng))
+            .unzip();
+        (
+            RangeCommitment { bits, proofs },
+            RangeResponse { bits: answers },
+        )
+    }
+}
+
+// Every bit's OR proof gives up the branch it knows and that bit commitment's
+// blinding, which add up to the value and the statement's blinding
+impl<G: Group, const N: usize> Extractor for Range<G, N> {
+    fn extract(
+        _statement: &PedersenCommitment<G>,
+        commitment: &RangeCommitment<G>,
+        first: (&G::Scalar, &RangeResponse<G::Scalar>),
+        second: (&G::Scalar, &RangeResponse<G::Scalar>),
+    ) -> Option<RangeWitness<G::Scalar>> {
+        if N > 64
+            || commitment.bits.len() != N
+            || commitment.proofs.len() != N
+            || first.1.bits.len() != N
+            || second.1.bits.len() != N
+        {
+            return None;
+        }
+        let generators = PedersenGenerators::<G>::default();
+
+        let mut value = 0u64;
+        let mut blinding = G::Scalar::zero();
+        for i in 0..N {
+            let statement = bit_statement(&generators, commitment.bits[i]);
+            let (bit, share) = match BitProof::<G>::extract(
+                &statement,
+                &commitment.proofs[i],
+                (first.0, &first.1.bits[i]),
+                (second.0, &second.1.bits[i]),
+            )? {
+                OrWitness::Left(share) => (0, share),
+                OrWitness::Right(share) => (1, share),
+            };
+            value |= bit << i;
+            blinding += power_of_two::<G::Scalar>(i) * share;
+        }
+        Some(RangeWitness { value, blinding })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{run_protocol, Challenge, HonestProver, HonestVerifier, Proof, DEFAULT_SECURITY};
+    use curve25519_dalek::ristretto::RistrettoPoint;
+    use curve25519_dalek::scalar::Scalar;
+
+    type G = RistrettoPoint;
+    type U8 = Range<G, 8>;
+
+    fn transcript<const N: usize>(
+        statement: &PedersenCommitment<G>,
+        witness: &RangeWitness<Scalar>,
+    ) -> (RangeCommitment<G>, Scalar, RangeResponse<Scalar>) {
+        let mut rng = rand::thread_rng();
+        let (commitment, state) = Range::<G, N>::commit(statement, witness, &mut rng);
+        let challenge = Scalar::challenge(&mut rng);
+        let response = Range::<G, N>::respond(statement, witness, state, &challenge);
+        (commitment, challenge, response)
+    }
+
+    fn accepts<const N: usize>(value: u64) -> bool {
+        let (statement, witness) = commit_value::<G, _>(value, &mut rand::thread_rng());
+        let (commitment, challenge, response) = transcript::<N>(&statement, &witness);
+        Range::<G, N>::verify(&statement, &commitment, &challenge, &response)
+    }
+
+    #[test]
+    fn values_in_range_pass() {
+        for value in [0, 1, 37, 255] {
+            assert!(accepts::<8>(value), "{}", value);
+        }
+        assert!(accepts::<32>(u64::from(u32::MAX)));
+        assert!(accepts::<64>(u64::MAX));
+    }
+
+    #[test]
+    fn values_out_of_range_fail() {
+        for value in [256, 1000, u64::MAX] {
+            assert!(!accepts::<8>(value), "{}", value);
+        }
+        assert!(!accepts::<32>(1 << 32));
+    }
+
+    #[test]
+    fn bounds_shift_the_commitment() {
+        type P = Range<G, 7>;
+        let mut rng = rand::thread_rng();
+        let generators = PedersenGenerators::<G>::default();
+        // An age of at least 18 and under 100, without saying which
+        let (age, opening) = commit_value::<G, _>(30, &mut rng);
+        let adult = at_least(&generators, &age, 18).unwrap();
+        let proof = Proof::prove::<P>(&adult, &opening.at_least(18).unwrap(), &mut rng);
+        assert!(proof.verify::<P>(&adult));
+        let young = below(&generators, &age, 100).unwrap();
+        let proof = Proof::prove::<P>(&young, &opening.below(100).unwrap(), &mut rng);
+        assert!(proof.verify::<P>(&young));
+        assert_eq!(opening.below(30), None);
+
+        // Nothing is below 0, not even -1 = q - 1, whose shifted commitment
+        // would otherwise be to q - 1 - (q - 1) = 0
+        let minus_one = generators.commit(&-Scalar::ONE, &opening.blinding);
+        assert_eq!(below(&generators, &minus_one, 0), None);
+        assert_eq!(opening.below(0), None);
+
+        // A minor has nothing better than the wrapped-around difference, which has too many bits
+        let (minor, opening) = commit_value::<G, _>(17, &mut rng);
+        assert_eq!(opening.at_least(18), None);
+        let wrapped = RangeWitness {
+            value: 17u64.wrapping_sub(18),
+            ..opening
+        };
+        let shifted = at_least(&generators, &minor, 18).unwrap();
+        let proof = Proof::prove::<P>(&shifted, &wrapped, &mut rng);
+        assert!(!proof.verify::<P>(&shifted));
+    }
+
+    #[test]
+    fn groups_too_small_for_the_bits_are_refused() {
+        use crate::group::modp::{ModpGroup, Toy64};
+        type S = ModpGroup<Toy64>;
+        let mut rng = rand::thread_rng();
+
+        // The order is just under 2^63, so 62 bits are fine and 63 could wrap
+        assert!(supported::<S, 62>());
+        assert!(!supported::<S, 63>());
+        assert!(supported::<G, 64>());
+
+        let (statement, witness) = commit_value::<S, _>(1 << 40, &mut rng);
+        let proof = Proof::prove::<Range<S, 62>>(&statement, &witness, &mut rng);
+        assert!(proof.verify::<Range<S, 62>>(&statement));
+        // Simulated transcripts satisfy every equation, so only the size check rejects them
+        let challenge = PrimeField::random(&mut rng);
+        let (commitment, response) = Range::<S, 64>::simulate(&statement, &challenge, &mut rng);
+        assert!(!Range::<S, 64>::verify(
+            &statement,
+            &commitment,
+            &challenge,
+            &response
+        ));
+
+        let generators = PedersenGenerators::<S>::default();
+        assert_eq!(at_least(&generators, &statement, 18), None);
+        assert_eq!(below(&generators, &statement, 100), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "too small for N-bit range proofs")]
+    fn provers_refuse_groups_too_small_for_the_bits() {
+        use crate::group::modp::{ModpGroup, Toy64};
+        type S = ModpGroup<Toy64>;
+        let mut rng = rand::thread_rng();
+        let (statement, witness) = commit_value::<S, _>(12345, &mut rng);
+        Proof::prove::<Range<S, 64>>(&statement, &witness, &mut rng);
+    }
+
+    #[test]
+    fn proof_does_not_transfer_to_another_commitment() {
+        let mut rng = rand::thread_rng();
+        let (statement, witness) = commit_value::<G, _>(42, &mut rng);
+        let (other, _) = commit_value::<G, _>(42, &mut rng);
+        let (commitment, challenge, response) = transcript::<8>(&statement, &witness);
+        assert!(U8::verify(&statement, &commitment, &challenge, &response));
+        assert!(!U8::verify(&other, &commitment, &challenge, &response));
+        // Nor to a proof for a different number of bits
+        assert!(!Range::<G, 9>::verify(
+            &statement,
+            &commitment,
+            &challenge,
+            &response
+        ));
+    }
+
+    #[test]
+    fn simulated_transcripts_verify() {
+        let mut rng = rand::thread_rng();
+        // The simulator needs no witness, so it works even for a value out of range
+        for value in [4, 1 << 20] {
+            let (statement, _) = commit_value::<G, _>(value, &mut rng);
+            let challenge = Scalar::challenge(&mut rng);
+            let (commitment, response) = U8::simulate(&statement, &challenge, &mut rng);
+            assert!(U8::verify(&statement, &commitment, &challenge, &response));
+        }
+    }
+
+    #[test]
+    fn two_challenges_reveal_the_value() {
+        let mut rng = rand::thread_rng();
+        let (statement, witness) = commit_value::<G, _>(201, &mut rng);
+        let (commitment, state) = U8::commit(&statement, &witness, &mut rng);
+        let (c1, c2) = (Scalar::challenge(&mut rng), Scalar::challenge(&mut rng));
+        let r1 = U8::respond(&statement, &witness, state.clone(), &c1);
+        let r2 = U8::respond(&statement, &witness, state, &c2);
+        assert_eq!(
+            U8::extract(&statement, &commitment, (&c1, &r1), (&c2, &r2)),
+            Some(witness)
+        );
+    }
+
+    #[test]
+    fn interactive_rounds_all_accept() {
+        let mut rng = rand::thread_rng();
+        let (statement, witness) = commit_value(99, &mut rng);
+        let mut prover = HonestProver::<U8>::new(statement, witness);
+        let mut verifier = HonestVerifier::<U8>::new(statement);
+        let result = run_protocol(&mut prover, &mut verifier, DEFAULT_SECURITY);
+        assert!(result.is_accepted());
+    }
+}
@@ -0,0 +1,214 @@
+/**
+ * Parallel repetition of a sigma protocol
+ * Runs K copies of a protocol side by side on one statement and witness: K
+ * commitments, then K independent challenges sent together, then K responses.
+ * The result is again a sigma protocol, whose soundness error is the K-th
+ * power of the original's, so a protocol with small challenges reaches a
+ * security level in one round and one aggregated proof rather than a loop of
+ * separate proofs. Pick K with rounds_for, which is a const fn.
+ */
+use std::marker::PhantomData;
+
+use rand::{CryptoRng, RngCore};
+
+use crate::{Extractor, SigmaProtocol, Simulator, Transcript};
+
+// K parallel copies of protocol P
+pub struct Repeated<P, const K: usize>(PhantomData<P>);
+
+// Gather exactly K items into an array
+fn collect<T, const K: usize>(items: impl IntoIterator<Item = T>) -> [T; K] {
+    let mut items = items.into_iter();
+    std::array::from_fn(|_| items.next().expect("one item per repetition"))
+}
+
+impl<P: SigmaProtocol, const K: usize> SigmaProtocol for Repeated<P, K> {
+    const DOMAIN: &'static [u8] = b"zero-knowledge-proof/repeated/v1";
+    const SOUNDNESS_BITS: u32 = P::SOUNDNESS_BITS.saturating_mul(K as u32);
+
+    type Statement = P::Statement;
+    type Witness = P::Witness;
+    type Commitment = [P::Commitment; K];
+    type State = Vec<P::State>;
+    type Challenge = [P::Challenge; K];
+    type Response = [P::Response; K];
+
+    fn commit<R: RngCore + CryptoRng>(
+        statement: &P::Statement,
+        witness: &P::Witness,
+        rng: &mut R,
+    ) -> (Self::Commitment, Vec<P::State>) {
+        let (commitments, states): (Vec<_>, Vec<_>) =
+            (0..K).map(|_| P::commit(statement, witness, rng)).unzip();
+        (collect(commitments), states)
+    }
+
+    fn respond(
+        statement: &P::Statement,
+        witness: &P::Witness,
+        states: Vec<P::State>,
+        challenges: &Self::Challenge,
+    ) -> Self::Response {
+        collect(
+            states
+                .into_iter()
+                .zip(challenges)
+                .map(|(state, challenge)| P::respond(statement, witness, state, challenge)),
+        )
+    }
+
+    // Zero copies would prove nothing, so they are never accepted
+    fn verify(
+        statement: &P::Statement,
+        commitments: &Self::Commitment,
+        challenges: &Self::Challenge,
+        responses: &Self::Response,
+    ) -> bool {
+        K > 0
+            && commitments.iter().zip(challenges).zip(responses).all(
+                |((commitment, challenge), response)| {
+                    P::verify(statement, commitment, challenge, response)
+                },
+            )
+    }
+
+    fn append_domain(transcript: &mut Transcript) {
+        transcript.append_message(b"protocol", Self::DOMAIN);
+        transcript.append_u64(b"repetitions", K as u64);
+        P::append_domain(transcript);
+    }
+}
+
+impl<P: Simulator, const K: usize> Simulator for Repeated<P, K> {
+    fn simulate<R: RngCore + CryptoRng>(
+        statement: &P::Statement,
+        challenges: &Self::Challenge,
+        rng: &mut R,
+    ) -> (Self::Commitment, Self::Response) {
+        let (commitments, responses): (Vec<_>, Vec<_>) = challenges
+            .iter()
+            .map(|challenge| P::simulate(statement, challenge, rng))
+            .unzip();
+        (collect(commitments), collect(responses))
+    }
+}
+
+// Two different challenge vectors differ in some copy, and that copy's two
+// transcripts reveal the witness
+impl<P: Extractor, const K: usize> Extractor for Repeated<P, K> {
+    fn extract(
+        statement: &P::Statement,
+        commitments: &Self::Commitment,
+        first: (&Self::Challenge, &Self::Response),
+        second: (&Self::Challenge, &Self::Response),
+    ) -> Option<P::Witness> {
+        let i = (0..K).find(|&i| first.0[i] != second.0[i])?;
+        P::extract(
+            statement,
+            &commitments[i],
+            (&first.0[i], &first.1[i]),
+            (&second.0[i], &second.1[i]),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schnorr::{self, Schnorr};
+    use crate::{
+        rounds_for, run_protocol, Challenge, Encode, Group, HonestProver, HonestVerifier, Proof,
+        DEFAULT_SECURITY,
+    };
+    use curve25519_dalek::ristretto::RistrettoPoint;
+    use curve25519_dalek::scalar::Scalar;
+
+    type G = RistrettoPoint;
+    type S = Schnorr<G>;
+
+    #[test]
+    fn soundness_adds_up_over_copies() {
+        assert_eq!(Repeated::<S, 3>::SOUNDNESS_BITS, 3 * S::SOUNDNESS_BITS);
+        assert_eq!(<[Scalar; 3] as Challenge>::BITS, 3 * 252);
+
+        // A protocol with one-bit challenges needs as many copies as the security parameter
+        const ROUNDS: usize = rounds_for(DEFAULT_SECURITY, 1);
+        assert_eq!(ROUNDS, DEFAULT_SECURITY as usize);
+    }
+
+    #[test]
+    fn one_round_of_copies_replaces_the_loop() {
+        type P = Repeated<S, 2>;
+        let (secret, public_key) = schnorr::keypair::<G, _>(&mut rand::thread_rng());
+        let mut prover = HonestProver::<P>::new(public_key, secret);
+        let mut verifier = HonestVerifier::<P>::new(public_key);
+        // Two 252-bit copies reach 300 bits where a single Schnorr run needs two rounds
+        let result = run_protocol(&mut prover, &mut verifier, 300);
+        assert_eq!(result.rounds, 1);
+        assert!(result.is_accepted());
+        assert_eq!(result.soundness_bits, 504);
+    }
+
+    #[test]
+    fn aggregated_proof_verifies_in_one_call() {
+        type P = Repeated<S, 3>;
+        let mut rng = rand::thread_rng();
+        let (secret, public_key) = schnorr::keypair::<G, _>(&mut rng);
+        let proof = Proof::prove::<P>(&public_key, &secret, &mut rng);
+        assert_eq!(proof.to_bytes().len(), 3 * 96);
+        assert!(proof.verify::<P>(&public_key));
+
+        // Every copy is checked, and copies cannot be reordered
+        let mut tampered = proof.clone();
+        tampered.response[2] += Scalar::ONE;
+        assert!(!tampered.verify::<P>(&public_key));
+        let mut swapped = proof.clone();
+        swapped.commitment.swap(0, 1);
+        swapped.response.swap(0, 1);
+        assert!(!swapped.verify::<P>(&public_key));
+
+        // Nor does the proof pass for a different number of copies
+        let single = Proof {
+            commitment: proof.commitment[0],
+            challenge: proof.challenge[0],
+            response: proof.response[0],
+        };
+        assert!(!single.verify::<S>(&public_key));
+    }
+
+    #[test]
+    fn wrong_secret_fails_every_copy() {
+        type P = Repeated<S, 4>;
+        let mut rng = rand::thread_rng();
+        let (_, public_key) = schnorr::keypair::<G, _>(&mut rng);
+        let mut prover = HonestProver::<P>::new(public_key, Scalar::random(&mut rng));
+        let mut verifier = HonestVerifier::<P>::new(public_key);
+        assert!(!run_protocol(&mut prover, &mut verifier, DEFAULT_SECURITY).is_accepted());
+    }
+
+    #[test]
+    fn zero_copies_prove_nothing() {
+        type P = Repeated<S, 0>;
+        let public_key = G::generator();
+        assert!(!P::verify(&public_key, &[], &[], &[]));
+    }
+
+    #[test]
+    fn a_differing_copy_reveals_the_secret() {
+        type P = Repeated<S, 3>;
+        let mut rng = rand::thread_rng();
+        let (secret, public_key) = schnorr::keypair::<G, _>(&mut rng);
+        let (commitments, states) = P::commit(&public_key, &secret, &mut rng);
+        let first = <[Scalar; 3] as Challenge>::challenge(&mut rng);
+        let mut second = first;
+        second[1] = Scalar::random(&mut rng);
+        let r1 = P::respond(&public_key, &secret, states.clone(), &first);
+        let r2 = P::respond(&public_key, &secret, states, &second);
+        let extracted = P::extract(&public_key, &commitments, (&first, &r1), (&second, &r2));
+        assert_eq!(extracted, Some(secret));
+        assert_eq!(
+            P::extract(&public_key, &commitments, (&first, &r1), (&first, &r1)),
+            None
+        );
+    }
+}
@@ -0,0 +1,271 @@
+/**
+ * Schnorr proof of knowledge of a discrete logarithm
+ * The prover knows x with y = g^x. It commits to t = g^r, receives a
+ * challenge c and answers s = r + c x; the verifier checks g^s = t y^c.
+ * Run interactively through the protocol driver, or non-interactively through
+ * the Fiat-Shamir transform, where the challenge is a hash of the statement
+ * and commitment. Dlog is the same proof over a base named in the statement,
+ * such as the blinding generator h of a Pedersen commitment.
+ */
+use std::marker::PhantomData;
+
+use rand::{CryptoRng, RngCore};
+
+use crate::{Decode, Encode, Extractor, Group, PrimeField, Proof, SigmaProtocol, Simulator};
+
+// Generate a secret exponent and the matching public key g^x
+pub fn keypair<G: Group, R: RngCore + CryptoRng>(rng: &mut R) -> (G::Scalar, G) {
+    let secret = G::Scalar::random(rng);
+    (secret, G::generator().scalar_mul(&secret))
+}
+
+// The Schnorr protocol; the statement is the public key y and the witness is x
+pub struct Schnorr<G>(PhantomData<G>);
+
+impl<G: Group> SigmaProtocol for Schnorr<G> {
+    const DOMAIN: &'static [u8] = b"zero-knowledge-proof/schnorr/v1";
+
+    type Statement = G;
+    type Witness = G::Scalar;
+    type Commitment = G;
+    type State = G::Scalar;
+    type Challenge = G::Scalar;
+    type Response = G::Scalar;
+
+    fn commit<R: RngCore + CryptoRng>(
+        _public_key: &G,
+        _secret: &G::Scalar,
+        rng: &mut R,
+    ) -> (G, G::Scalar) {
+        let nonce = G::Scalar::random(rng);
+        (G::generator().scalar_mul(&nonce), nonce)
+    }
+
+    fn respond(
+        _public_key: &G,
+        secret: &G::Scalar,
+        nonce: G::Scalar,
+        challenge: &G::Scalar,
+    ) -> G::Scalar {
+        nonce + *challenge * *secret
+    }
+
+    fn verify(public_key: &G, commitment: &G, challenge: &G::Scalar, response: &G::Scalar) -> bool {
+        G::generator().scalar_mul(response) == *commitment + public_key.scalar_mul(challenge)
+    }
+}
+
+// Pick the response first and solve for the commitment: t = g^s y^-c
+impl<G: Group> Simulator for Schnorr<G> {
+    fn simulate<R: RngCore + CryptoRng>(
+        public_key: &G,
+        challenge: &G::Scalar,
+        rng: &mut R,
+    ) -> (G, G::Scalar) {
+        let response = G::Scalar::random(rng);
+        let commitment = G::generator().scalar_mul(&response) - public_key.scalar_mul(challenge);
+        (commitment, response)
+    }
+}
+
+// s - s' = (c - c') x, so x = (s - s') / (c - c')
+impl<G: Group> Extractor for Schnorr<G> {
+    fn extract(
+        _public_key: &G,
+        _commitment: &G,
+        first: (&G::Scalar, &G::Scalar),
+        second: (&G::Scalar, &G::Scalar),
+    ) -> Option<G::Scalar> {
+        Some((*first.1 - *second.1) * (*first.0 - *second.0).inverse()?)
+    }
+}
+
+// The claim point = base^x
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DlogStatement<G> {
+    pub base: G,
+    pub point: G,
+}
+
+impl<G: Group> Encode for DlogStatement<G> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.base.encode(out);
+        self.point.encode(out);
+    }
+}
+
+impl<G: Group> Decode for DlogStatement<G> {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(DlogStatement {
+            base: G::decode(input)?,
+            point: G::decode(input)?,
+        })
+    }
+}
+
+// The Schnorr protocol over the statement's base instead of the generator
+pub struct Dlog<G>(PhantomData<G>);
+
+impl<G: Group> SigmaProtocol for Dlog<G> {
+    const DOMAIN: &'static [u8] = b"zero-knowledge-proof/dlog/v1";
+
+    type Statement = DlogStatement<G>;
+    type Witness = G::Scalar;
+    type Commitment = G;
+    type State = G::Scalar;
+    type Challenge = G::Scalar;
+    type Response = G::Scalar;
+
+    fn commit<R: RngCore + CryptoRng>(
+        statement: &DlogStatement<G>,
+        _secret: &G::Scalar,
+        rng: &mut R,
+    ) -> (G, G::Scalar) {
+        let nonce = G::Scalar::random(rng);
+        (statement.base.scalar_mul(&nonce), nonce)
+    }
+
+    fn respond(
+        _statement: &DlogStatement<G>,
+        secret: &G::Scalar,
+        nonce: G::Scalar,
+        challenge: &G::Scalar,
+    ) -> G::Scalar {
+        nonce + *challenge * *secret
+    }
+
+    fn verify(
+        statement: &DlogStatement<G>,
+        commitment: &G,
+        challenge: &G::Scalar,
+        response: &G::Scalar,
+    ) -> bool {
+        statement.base.scalar_mul(response) == *commitment + statement.point.scalar_mul(challenge)
+    }
+}
+
+impl<G: Group> Simulator for Dlog<G> {
+    fn simulate<R: RngCore + CryptoRng>(
+        statement: &DlogStatement<G>,
+        challenge: &G::Scalar,
+        rng: &mut R,
+    ) -> (G, G::Scalar) {
+        let response = G::Scalar::random(rng);
+        let commitment =
+            statement.base.scalar_mul(&response) - statement.point.scalar_mul(challenge);
+        (commitment, response)
+    }
+}
+
+impl<G: Group> Extractor for Dlog<G> {
+    fn extract(
+        _statement: &DlogStatement<G>,
+        _commitment: &G,
+        first: (&G::Scalar, &G::Scalar),
+        second: (&G::Scalar, &G::Scalar),
+    ) -> Option<G::Scalar> {
+        Some((*first.1 - *second.1) * (*first.0 - *second.0).inverse()?)
+    }
+}
+
+// A non-interactive Schnorr proof
+pub type SchnorrProof<G> = Proof<G, <G as Group>::Scalar, <G as Group>::Scalar>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{run_protocol, HonestProver, HonestVerifier, DEFAULT_SECURITY};
+    use curve25519_dalek::ristretto::RistrettoPoint;
+    use curve25519_dalek::scalar::Scalar;
+
+    #[test]
+    fn interactive_rounds_all_accept() {
+        let (secret, public_key) = keypair::<RistrettoPoint, _>(&mut rand::thread_rng());
+        let mut prover = HonestProver::<Schnorr<RistrettoPoint>>::new(public_key, secret);
+        let mut verifier = HonestVerifier::<Schnorr<RistrettoPoint>>::new(public_key);
+        // A 252-bit challenge space needs two rounds to reach 300 bits of security
+        let result = run_protocol(&mut prover, &mut verifier, 300);
+        assert_eq!(result.rounds, 2);
+        assert!(result.is_accepted());
+        assert_eq!(result.soundness_bits, 504);
+    }
+
+    #[test]
+    fn wrong_secret_is_rejected_interactively() {
+        let mut rng = rand::thread_rng();
+        let (_, public_key) = keypair::<RistrettoPoint, _>(&mut rng);
+        let mut prover =
+            HonestProver::<Schnorr<RistrettoPoint>>::new(public_key, Scalar::random(&mut rng));
+        let mut verifier = HonestVerifier::<Schnorr<RistrettoPoint>>::new(public_key);
+        let result = run_protocol(&mut prover, &mut verifier, DEFAULT_SECURITY);
+        assert!(!result.is_accepted());
+        assert_eq!(result.accepted, 0);
+    }
+
+    #[test]
+    fn simulated_transcripts_verify() {
+        let mut rng = rand::thread_rng();
+        let (_, public_key) = keypair::<RistrettoPoint, _>(&mut rng);
+        let challenge = Scalar::random(&mut rng);
+        let (commitment, response) =
+            Schnorr::<RistrettoPoint>::simulate(&public_key, &challenge, &mut rng);
+        assert!(Schnorr::verify(
+            &public_key,
+            &commitment,
+            &challenge,
+            &response
+        ));
+    }
+
+    #[test]
+    fn two_challenges_reveal_the_secret() {
+        let mut rng = rand::thread_rng();
+        let (secret, public_key) = keypair::<RistrettoPoint, _>(&mut rng);
+        let (commitment, nonce) = Schnorr::commit(&public_key, &secret, &mut rng);
+        let (c1, c2) = (Scalar::random(&mut rng), Scalar::random(&mut rng));
+        let s1 = Schnorr::<RistrettoPoint>::respond(&public_key, &secret, nonce, &c1);
+        let s2 = Schnorr::<RistrettoPoint>::respond(&public_key, &secret, nonce, &c2);
+        let extracted =
+            Schnorr::<RistrettoPoint>::extract(&public_key, &commitment, (&c1, &s1), (&c2, &s2));
+        assert_eq!(extracted, Some(secret));
+    }
+
+    #[test]
+    fn non_interactive_proof_verifies_offline() {
+        type S = Schnorr<RistrettoPoint>;
+        let mut rng = rand::thread_rng();
+        let (secret, public_key) = keypair::<RistrettoPoint, _>(&mut rng);
+        let proof: SchnorrProof<RistrettoPoint> = Proof::prove::<S>(&public_key, &secret, &mut rng);
+        assert!(proof.verify::<S>(&public_key));
+
+        let (_, other_key) = keypair::<RistrettoPoint, _>(&mut rng);
+        assert!(!proof.verify::<S>(&other_key));
+
+        let forged = SchnorrProof {
+            response: proof.response + Scalar::ONE,
+            ..proof
+        };
+        assert!(!forged.verify::<S>(&public_key));
+    }
+
+    #[test]
+    fn dlog_proves_against_another_base() {
+        type D = Dlog<RistrettoPoint>;
+        let mut rng = rand::thread_rng();
+        let base = RistrettoPoint::hash_to_group(b"zk", b"base");
+        let secret = Scalar::random(&mut rng);
+        let statement = DlogStatement {
+            base,
+            point: base.scalar_mul(&secret),
+        };
+        let proof = Proof::prove::<D>(&statement, &secret, &mut rng);
+        assert!(proof.verify::<D>(&statement));
+
+        // The same exponent over the generator is a different statement
+        let moved = DlogStatement {
+            base: RistrettoPoint::generator(),
+            ..statement
+        };
+        assert!(!proof.verify::<D>(&moved));
+    }
+}
@@ -0,0 +1,61 @@
+/**
+ * Sigma protocols
+ * A three-move protocol: the prover commits, the verifier sends a random
+ * challenge, and the prover answers with a response the verifier can check
+ * against the public statement
+ */
+use rand::{CryptoRng, RngCore};
+
+use crate::{Challenge, Decode, Encode, Response, Transcript};
+
+// A trait for three-move public-coin proofs of knowledge
+pub trait SigmaProtocol {
+    // A name for the protocol, hashed into non-interactive challenges
+    const DOMAIN: &'static [u8];
+    // The per-round soundness error as a power of two: a prover without a witness
+    // convinces the verifier of one round with probability at most 2^-SOUNDNESS_BITS.
+    // For a special-sound protocol this is the size of the challenge space.
+    const SOUNDNESS_BITS: u32 = <Self::Challenge as Challenge>::BITS;
+
+    // The public claim being proven
+    type Statement: Encode;
+    // The secret that makes the statement true
+    type Witness;
+    // The prover's first message
+    type Commitment: Clone + Encode + Decode;
+    // The prover's secret randomness behind the first message
+    type State;
+    // The verifier's challenge
+    type Challenge: Challenge;
+    // The prover's final message
+    type Response: Response;
+
+    // Produce the first message and remember the randomness behind it
+    fn commit<R: RngCore + CryptoRng>(
+        statement: &Self::Statement,
+        witness: &Self::Witness,
+        rng: &mut R,
+    ) -> (Self::Commitment, Self::State);
+
+    // Answer a challenge; the state is consumed so it is never reused
+    fn respond(
+        statement: &Self::Statement,
+        witness: &Self::Witness,
+        state: Self::State,
+        challenge: &Self::Challenge,
+    ) -> Self::Response;
+
+    // Check a transcript against the statement
+    fn verify(
+        statement: &Self::Statement,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> bool;
+
+    // Absorb the protocol's name into a non-interactive transcript; a composed
+    // protocol also absorbs the names of its parts
+    fn append_domain(transcript: &mut Transcript) {
+        transcript.append_message(b"protocol", Self::DOMAIN);
+    }
+}
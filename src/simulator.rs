@@ -0,0 +1,288 @@
+/**
+ * Honest-verifier zero knowledge
+ * A sigma protocol is zero knowledge if, for a random challenge, a simulator
+ * that does not know the witness can produce accepting transcripts distributed
+ * exactly like those of an honest prover. A verifier then learns nothing from
+ * a transcript it could not have produced itself.
+ *
+ * The harness here compares real and simulated transcripts statistically. It
+ * looks at every bit of the encoded transcripts and reports the largest
+ * two-proportion z-score between the two samples. That cannot prove zero
+ * knowledge, since it only sees one bit at a time, but it does catch a prover
+ * that sends something of the witness in the clear, such as a value's parity.
+ */
+use rand::{CryptoRng, RngCore};
+
+use crate::{Challenge, Encode, Proof, SigmaProtocol};
+
+// The chance that the harness flags a protocol whose transcripts are identically distributed
+pub const FALSE_POSITIVE_RATE: f64 = 1e-9;
+
+// A sigma protocol whose transcripts can be produced without the witness
+pub trait Simulator: SigmaProtocol {
+    // Produce a commitment and response that verify with the given challenge,
+    // distributed like an honest prover's when the challenge is uniform
+    fn simulate<R: RngCore + CryptoRng>(
+        statement: &Self::Statement,
+        challenge: &Self::Challenge,
+        rng: &mut R,
+    ) -> (Self::Commitment, Self::Response);
+}
+
+// A transcript of an honest prover answering a uniformly random challenge
+pub fn real_transcript<P: SigmaProtocol>(
+    statement: &P::Statement,
+    witness: &P::Witness,
+    rng: &mut (impl RngCore + CryptoRng),
+) -> Proof<P::Commitment, P::Challenge, P::Response> {
+    let (commitment, state) = P::commit(statement, witness, rng);
+    let challenge = P::Challenge::challenge(rng);
+    let response = P::respond(statement, witness, state, &challenge);
+    Proof {
+        commitment,
+        challenge,
+        response,
+    }
+}
+
+// A transcript produced by the simulator for a uniformly random challenge
+pub fn simulated_transcript<P: Simulator>(
+    statement: &P::Statement,
+    rng: &mut (impl RngCore + CryptoRng),
+) -> Proof<P::Commitment, P::Challenge, P::Response> {
+    let challenge = P::Challenge::challenge(rng);
+    let (commitment, response) = P::simulate(statement, &challenge, rng);
+    Proof {
+        commitment,
+        challenge,
+        response,
+    }
+}
+
+// The outcome of comparing real and simulated transcripts
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Indistinguishability {
+    // The number of transcripts drawn from each side
+    pub samples: usize,
+    // Whether every simulated transcript was accepted by the verifier
+    pub simulated_accepted: bool,
+    // The largest z-score over all bit positions; infinite if the encodings differ in length
+    pub max_z: f64,
+    // The bit position, counted from the start of the encoding, where max_z was found
+    pub position: usize,
+    // The z-score above which the two samples are declared different
+    pub threshold: f64,
+}
+
+impl Indistinguishability {
+    // Whether the simulator passes: its transcripts verify and look like the real ones
+    pub fn is_indistinguishable(&self) -> bool {
+        self.simulated_accepted && self.max_z <= self.threshold
+    }
+}
+
+// Draw `samples` real and simulated transcripts and compare them bit by bit
+pub fn compare<P: Simulator>(
+    statement: &P::Statement,
+    witness: &P::Witness,
+    samples: usize,
+    rng: &mut (impl RngCore + CryptoRng),
+) -> Indistinguishability {
+    assert!(samples > 0, "cannot compare empty samples");
+    let real: Vec<Vec<u8>> = (0..samples)
+        .map(|_| real_transcript::<P>(statement, witness, rng).to_bytes())
+        .collect();
+
+    let mut simulated_accepted = true;
+    let simulated: Vec<Vec<u8>> = (0..samples)
+        .map(|_| {
+            let transcript = simulated_transcript::<P>(statement, rng);
+            simulated_accepted &= P::verify(
+                statement,
+                &transcript.commitment,
+                &transcript.challenge,
+                &transcript.response,
+            );
+            transcript.to_bytes()
+        })
+        .collect();
+
+    let len = real[0].len();
+    let positions = 8 * len;
+    let threshold = threshold(positions.max(1));
+
+    // Transcripts of different lengths are told apart without looking at their contents
+    if let Some(odd) = real
+        .iter()
+        .chain(&simulated)
+        .find(|bytes| bytes.len() != len)
+    {
+        return Indistinguishability {
+            samples,
+            simulated_accepted,
+            max_z: f64::INFINITY,
+            position: 8 * odd.len().min(len),
+            threshold,
+        };
+    }
+
+    let real_counts = bit_counts(&real, len);
+    let simulated_counts = bit_counts(&simulated, len);
+    let (position, max_z) = (0..positions)
+        .map(|i| (i, z_score(real_counts[i], simulated_counts[i], samples)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap_or((0, 0.0));
+
+    Indistinguishability {
+        samples,
+        simulated_accepted,
+        max_z,
+        position,
+        threshold,
+    }
+}
+
+// How often each bit is set across a sample of equal-length encodings
+fn bit_counts(transcripts: &[Vec<u8>], len: usize) -> Vec<usize> {
+    let mut counts = vec![0; 8 * len];
+    for bytes in transcripts {
+        for (i, byte) in bytes.iter().enumerate() {
+            for bit in 0..8 {
+                counts[8 * i + bit] += usize::from(byte >> bit & 1);
+            }
+        }
+    }
+    counts
+}
+
+// The absolute two-proportion z-score of two counts out of `samples` each
+fn z_score(a: usize, b: usize, samples: usize) -> f64 {
+    let n = samples as f64;
+    let pooled = (a + b) as f64 / (2.0 * n);
+    let variance = pooled * (1.0 - pooled) * 2.0 / n;
+    if variance == 0.0 {
+        // Both samples agree on a constant bit
+        return 0.0;
+    }
+    (a as f64 - b as f64).abs() / n / variance.sqrt()
+}
+
+// The z-score that any of `positions` tests exceeds by chance with probability at most
+// FALSE_POSITIVE_RATE, from the Gaussian tail bound P(|Z| > z) <= 2 exp(-z^2 / 2)
+fn threshold(positions: usize) -> f64 {
+    (2.0 * (2.0 * positions as f64 / FALSE_POSITIVE_RATE).ln()).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Decode, Group, Response};
+    use curve25519_dalek::ristretto::RistrettoPoint;
+    use curve25519_dalek::scalar::Scalar;
+
+    type G = RistrettoPoint;
+
+    // A Schnorr proof whose response carries the low byte of the secret as an
+    // unchecked hint; it verifies, it can be simulated, and it leaks
+    struct LeakySchnorr;
+
+    #[derive(Clone, Debug)]
+    struct LeakyResponse {
+        response: Scalar,
+        hint: u64,
+    }
+
+    impl Encode for LeakyResponse {
+        fn encode(&self, out: &mut Vec<u8>) {
+            self.response.encode(out);
+            self.hint.encode(out);
+        }
+    }
+
+    impl Decode for LeakyResponse {
+        fn decode(input: &mut &[u8]) -> Option<Self> {
+            Some(LeakyResponse {
+                response: Scalar::decode(input)?,
+                hint: u64::decode(input)?,
+            })
+        }
+    }
+
+    impl Response for LeakyResponse {}
+
+    impl SigmaProtocol for LeakySchnorr {
+        const DOMAIN: &'static [u8] = b"zero-knowledge-proof/test/leaky-schnorr";
+
+        type Statement = G;
+        type Witness = Scalar;
+        type Commitment = G;
+        type State = Scalar;
+        type Challenge = Scalar;
+        type Response = LeakyResponse;
+
+        fn commit<R: RngCore + CryptoRng>(
+            _public_key: &G,
+            _secret: &Scalar,
+            rng: &mut R,
+        ) -> (G, Scalar) {
+            let nonce = Scalar::random(rng);
+            (G::generator().scalar_mul(&nonce), nonce)
+        }
+
+        fn respond(
+            _public_key: &G,
+            secret: &Scalar,
+            nonce: Scalar,
+            challenge: &Scalar,
+        ) -> LeakyResponse {
+            LeakyResponse {
+                response: nonce + challenge * secret,
+                hint: u64::from(secret.as_bytes()[0]),
+            }
+        }
+
+        fn verify(
+            public_key: &G,
+            commitment: &G,
+            challenge: &Scalar,
+            answer: &LeakyResponse,
+        ) -> bool {
+            G::generator().scalar_mul(&answer.response)
+                == *commitment + public_key.scalar_mul(challenge)
+        }
+    }
+
+    impl Simulator for LeakySchnorr {
+        fn simulate<R: RngCore + CryptoRng>(
+            public_key: &G,
+            challenge: &Scalar,
+            rng: &mut R,
+        ) -> (G, LeakyResponse) {
+            let response = Scalar::random(rng);
+            let commitment =
+                G::generator().scalar_mul(&response) - public_key.scalar_mul(challenge);
+            // The simulator has no secret, so the best it can do is a random hint
+            let hint = u64::from(rng.next_u32() as u8);
+            (commitment, LeakyResponse { response, hint })
+        }
+    }
+
+    #[test]
+    fn leaky_protocol_is_detected() {
+        let mut rng = rand::thread_rng();
+        let (secret, public_key) = crate::schnorr::keypair::<G, _>(&mut rng);
+        let result = compare::<LeakySchnorr>(&public_key, &secret, 256, &mut rng);
+        assert!(result.simulated_accepted);
+        assert!(!result.is_indistinguishable());
+        // The leak sits in the hint, after the 32-byte commitment, challenge and response
+        assert!((96 * 8..97 * 8).contains(&result.position));
+    }
+
+    #[test]
+    fn threshold_grows_slowly_with_positions() {
+        assert!(threshold(1) > 6.0);
+        assert!(threshold(1 << 20) < 9.0);
+        assert_eq!(z_score(10, 10, 20), 0.0);
+        assert_eq!(z_score(0, 0, 20), 0.0);
+    }
+}
@@ -0,0 +1,150 @@
+/**
+ * A transcript for non-interactive proofs, in the style of Merlin
+ * Protocols append labeled messages and squeeze challenges out of a running
+ * SHA-256 state. Every operation is framed with an operation code and the
+ * lengths of its label and data, so two different sequences of operations
+ * never feed the hash the same bytes and messages from different protocols
+ * or sub-proofs cannot be confused with one another.
+ */
+use sha2::{Digest, Sha256};
+
+use crate::{Challenge, Encode};
+
+// Operation codes that frame each absorbed record
+const APPEND: u8 = 1;
+const CHALLENGE: u8 = 2;
+const FORK: u8 = 3;
+const RATCHET: u8 = 4;
+
+// The label that opens every transcript
+const DOMAIN_SEPARATOR: &[u8] = b"zero-knowledge-proof/transcript/v1";
+
+#[derive(Clone)]
+pub struct Transcript {
+    state: Sha256,
+}
+
+impl Transcript {
+    // Start a transcript for the protocol named by `label`
+    pub fn new(label: &[u8]) -> Self {
+        let mut transcript = Transcript {
+            state: Sha256::new(),
+        };
+        transcript.absorb(APPEND, DOMAIN_SEPARATOR, label);
+        transcript
+    }
+
+    // Append a labeled message
+    pub fn append_message(&mut self, label: &[u8], message: &[u8]) {
+        self.absorb(APPEND, label, message);
+    }
+
+    // Append a labeled value in its canonical encoding
+    pub fn append<T: Encode + ?Sized>(&mut self, label: &[u8], value: &T) {
+        self.append_message(label, &value.to_bytes());
+    }
+
+    pub fn append_u64(&mut self, label: &[u8], value: u64) {
+        self.append_message(label, &value.to_le_bytes());
+    }
+
+    // Fill `dest` with challenge bytes bound to everything appended so far
+    pub fn challenge_bytes(&mut self, label: &[u8], dest: &mut [u8]) {
+        self.absorb(CHALLENGE, label, &(dest.len() as u64).to_le_bytes());
+        let seed: [u8; 32] = self.state.clone().finalize().into();
+
+        // Counter-mode expansion of the seed
+        for (counter, chunk) in dest.chunks_mut(32).enumerate() {
+            let mut block = Sha256::new();
+            block.update(seed);
+            block.update((counter as u64).to_le_bytes());
+            chunk.copy_from_slice(&block.finalize()[..chunk.len()]);
+        }
+
+        // Later challenges depend on this one
+        self.absorb(RATCHET, b"", &seed);
+    }
+
+    // Squeeze a challenge out of the transcript
+    pub fn challenge<C: Challenge>(&mut self, label: &[u8]) -> C {
+        let mut wide = [0u8; 64];
+        self.challenge_bytes(label, &mut wide);
+        C::from_uniform_bytes(&wide)
+    }
+
+    // A transcript for a sub-protocol, bound to this one's state but independent of it from here on
+    pub fn fork(&self, label: &[u8]) -> Transcript {
+        let mut forked = self.clone();
+        forked.absorb(FORK, label, b"");
+        forked
+    }
+
+    fn absorb(&mut self, operation: u8, label: &[u8], data: &[u8]) {
+        self.state.update([operation]);
+        self.state.update((label.len() as u64).to_le_bytes());
+        self.state.update(label);
+        self.state.update((data.len() as u64).to_le_bytes());
+        self.state.update(data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    fn squeeze(transcript: &mut Transcript, label: &[u8], length: usize) -> String {
+        let mut out = vec![0u8; length];
+        transcript.challenge_bytes(label, &mut out);
+        hex(&out)
+    }
+
+    // Expected values were computed with an independent Python implementation of the framing
+    #[test]
+    fn known_answers() {
+        let mut transcript = Transcript::new(b"test protocol");
+        transcript.append_message(b"some label", b"some data");
+        assert_eq!(
+            squeeze(&mut transcript, b"challenge", 32),
+            "c711b2775010d76c3bfe1c7b2cf070292ecbf28c20d8bf8b9d2fcac96df430bc"
+        );
+        assert_eq!(
+            squeeze(&mut transcript, b"challenge", 40),
+            "83c1f863e7341502676038bf40a80a56c6e59a44537fe19c3b31961d129e28039ddea69dd54275e2"
+        );
+
+        let mut forked = transcript.fork(b"sub-proof");
+        forked.append_u64(b"index", 7);
+        assert_eq!(
+            squeeze(&mut forked, b"challenge", 16),
+            "6894d6702eb55aca02bf242a212c1bb1"
+        );
+    }
+
+    #[test]
+    fn framing_separates_labels_and_messages() {
+        let mut first = Transcript::new(b"test");
+        first.append_message(b"ab", b"c");
+        let mut second = Transcript::new(b"test");
+        second.append_message(b"a", b"bc");
+        assert_ne!(
+            squeeze(&mut first, b"c", 32),
+            squeeze(&mut second, b"c", 32)
+        );
+    }
+
+    #[test]
+    fn forks_are_independent_of_the_parent() {
+        let parent = Transcript::new(b"test");
+        let mut one = parent.fork(b"one");
+        let mut two = parent.fork(b"two");
+        let mut same = parent.fork(b"one");
+        let one_challenge = squeeze(&mut one, b"c", 32);
+        assert_ne!(one_challenge, squeeze(&mut two, b"c", 32));
+        assert_eq!(one_challenge, squeeze(&mut same, b"c", 32));
+        assert_ne!(one_challenge, squeeze(&mut parent.clone(), b"c", 32));
+    }
+}
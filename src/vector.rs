@@ -0,0 +1,422 @@
+/**
+ * Pedersen vector commitments with batch openings
+ * A vector x_1..x_n is committed as C = g_1^x_1 ... g_n^x_n h^r, one point
+ * whatever n is. To open the positions in a set S, the committer reveals
+ * their values and proves it knows an opening of the rest:
+ *
+ *   C / prod_{i in S} g_i^x_i = prod_{i not in S} g_i^x_i h^r
+ *
+ * a proof of knowledge of a representation over the unopened generators and
+ * h, in the style of the single-value opening proof. A wrong value at any
+ * opened position would give a second representation of C, which breaks the
+ * discrete-log relations between the generators. The unopened positions stay
+ * hidden, and any subset is opened with one proof of n - |S| + 1 scalars.
+ *
+ * Positions past the end of the committed vector hold zero, so the vector can
+ * be shorter than the key.
+ */
+use std::marker::PhantomData;
+
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    Decode, Encode, Extractor, Group, PedersenCommitment, PedersenVectorGenerators, PrimeField,
+    Proof, SigmaProtocol, Simulator, VectorCommitment,
+};
+
+// The proof of knowledge of an opening of every position outside the statement's
+pub struct VectorOpening<G>(PhantomData<G>);
+
+// The claim that the commitment holds the given values at the given positions
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VectorOpeningStatement<G: Group> {
+    pub generators: PedersenVectorGenerators<G>,
+    pub commitment: PedersenCommitment<G>,
+    // Strictly increasing positions and their values
+    pub indices: Vec<u64>,
+    pub values: Vec<G::Scalar>,
+}
+
+// The whole vector and the blinding factor behind a commitment
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PedersenVectorOpening<F> {
+    pub values: Vec<F>,
+    pub blinding: F,
+}
+
+// A non-interactive batch opening
+pub type VectorOpeningProof<G> = Proof<G, <G as Group>::Scalar, Vec<<G as Group>::Scalar>>;
+
+impl<G: Group> VectorOpeningStatement<G> {
+    // The statement for positions in any order, or None if one repeats, lies outside
+    // the generators, or has no value
+    pub fn new(
+        generators: &PedersenVectorGenerators<G>,
+        commitment: &PedersenCommitment<G>,
+        indices: &[usize],
+        values: &[G::Scalar],
+    ) -> Option<Self> {
+        if indices.len() != values.len() {
+            return None;
+        }
+        let mut opened: Vec<(u64, G::Scalar)> = indices
+            .iter()
+            .map(|i| *i as u64)
+            .zip(values.iter().copied())
+            .collect();
+        opened.sort_unstable_by_key(|(i, _)| *i);
+        let statement = VectorOpeningStatement {
+            generators: generators.clone(),
+            commitment: *commitment,
+            indices: opened.iter().map(|(i, _)| *i).collect(),
+            values: opened.iter().map(|(_, v)| *v).collect(),
+        };
+        if statement.is_well_formed() {
+            Some(statement)
+        } else {
+            None
+        }
+    }
+
+    fn is_well_formed(&self) -> bool {
+        self.indices.len() == self.values.len()
+            && self.indices.windows(2).all(|pair| pair[0] < pair[1])
+            && self
+                .indices
+                .iter()
+                .all(|i| *i < self.generators.g.len() as u64)
+    }
+
+    // The generators of the positions left closed
+    fn hidden(&self) -> Vec<G> {
+        self.hidden_indices()
+            .map(|i| self.generators.g[i])
+            .collect()
+    }
+
+    fn hidden_indices(&self) -> impl Iterator<Item = usize> + '_ {
+        let mut opened = self.indices.iter().peekable();
+        (0..self.generators.g.len()).filter(move |i| {
+            if opened.peek() == Some(&&(*i as u64)) {
+                opened.next();
+                false
+            } else {
+                true
+            }
+        })
+    }
+
+    // C with the opened positions taken out: a commitment to the hidden ones
+    fn remainder(&self) -> G {
+        let opened: Vec<G> = self
+            .indices
+            .iter()
+            .map(|i| self.generators.g[*i as usize])
+            .collect();
+        self.commitment.point - G::multi_scalar_mul(&opened, &self.values)
+    }
+
+    // The point a response opens: the hidden generators and h raised to its scalars
+    fn represent(&self, scalars: &[G::Scalar]) -> G {
+        let mut bases = self.hidden();
+        bases.push(self.generators.h);
+        G::multi_scalar_mul(&bases, scalars)
+    }
+}
+
+impl<G: Group> Encode for VectorOpeningStatement<G> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.generators.encode(out);
+        self.commitment.encode(out);
+        self.indices.encode(out);
+        self.values.encode(out);
+    }
+}
+
+impl<G: Group> Decode for VectorOpeningStatement<G> {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(VectorOpeningStatement {
+            generators: PedersenVectorGenerators::decode(input)?,
+            commitment: PedersenCommitment::decode(input)?,
+            indices: Vec::decode(input)?,
+            values: Vec::decode(input)?,
+        })
+    }
+}
+
+impl<F: PrimeField> Encode for PedersenVectorOpening<F> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.values.encode(out);
+        self.blinding.encode(out);
+    }
+}
+
+impl<F: PrimeField> Decode for PedersenVectorOpening<F> {
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(PedersenVectorOpening {
+            values: Vec::decode(input)?,
+            blinding: F::decode(input)?,
+        })
+    }
+}
+
+impl<G: Group> SigmaProtocol for VectorOpening<G> {
+    const DOMAIN: &'static [u8] = b"zero-knowledge-proof/vector-opening/v1";
+
+    type Statement = VectorOpeningStatement<G>;
+    type Witness = PedersenVectorOpening<G::Scalar>;
+    type Commitment = G;
+    type State = Vec<G::Scalar>;
+    type Challenge = G::Scalar;
+    type Response = Vec<G::Scalar>;
+
+    // One nonce per hidden position and one for the blinding
+    fn commit<R: RngCore + CryptoRng>(
+        statement: &VectorOpeningStatement<G>,
+        _opening: &PedersenVectorOpening<G::Scalar>,
+        rng: &mut R,
+    ) -> (G, Vec<G::Scalar>) {
+        let nonces: Vec<G::Scalar> = (0..statement.hidden_indices().count() + 1)
+            .map(|_| G::Scalar::random(rng))
+            .collect();
+        (statement.represent(&nonces), nonces)
+    }
+
+    fn respond(
+        statement: &VectorOpeningStatement<G>,
+        opening: &PedersenVectorOpening<G::Scalar>,
+        nonces: Vec<G::Scalar>,
+        challenge: &G::Scalar,
+    ) -> Vec<G::Scalar> {
+        let secrets = statement
+            .hidden_indices()
+            .map(|i| {
+                opening
+                    .values
+                    .get(i)
+                    .copied()
+                    .unwrap_or_else(G::Scalar::zero)
+            })
+            .chain([opening.blinding]);
+        nonces
+            .iter()
+            .zip(secrets)
+            .map(|(nonce, secret)| *nonce + *challenge * secret)
+            .collect()
+    }
+
+    fn verify(
+        statement: &VectorOpeningStatement<G>,
+        commitment: &G,
+        challenge: &G::Scalar,
+        response: &Vec<G::Scalar>,
+    ) -> bool {
+        statement.is_well_formed()
+            && response.len() == statement.hidden_indices().count() + 1
+            && statement.represent(response)
+                == *commitment + statement.remainder().scalar_mul(challenge)
+    }
+}
+
+// Pick the responses first and solve for the commitment
+impl<G: Group> Simulator for VectorOpening<G> {
+    fn simulate<R: RngCore + CryptoRng>(
+        statement: &VectorOpeningStatement<G>,
+        challenge: &G::Scalar,
+        rng: &mut R,
+    ) -> (G, Vec<G::Scalar>) {
+        let response: Vec<G::Scalar> = (0..statement.hidden_indices().count() + 1)
+            .map(|_| G::Scalar::random(rng))
+            .collect();
+        let commitment =
+            statement.represent(&response) - statement.remainder().scalar_mul(challenge);
+        (commitment, response)
+    }
+}
+
+// Two responses differ by c - c' times the hidden values and blinding, which
+// together with the opened values make up the whole vector
+impl<G: Group> Extractor for VectorOpening<G> {
+    fn extract(
+        statement: &VectorOpeningStatement<G>,
+        _commitment: &G,
+        first: (&G::Scalar, &Vec<G::Scalar>),
+        second: (&G::Scalar, &Vec<G::Scalar>),
+    ) -> Option<PedersenVectorOpening<G::Scalar>> {
+        let hidden: Vec<usize> = statement.hidden_indices().collect();
+        if !statement.is_well_formed()
+            || first.1.len() != hidden.len() + 1
+            || second.1.len() != hidden.len() + 1
+        {
+            return None;
+        }
+        let scale = (*first.0 - *second.0).inverse()?;
+        let mut secrets = first.1.iter().zip(second.1).map(|(a, b)| (*a - *b) * scale);
+
+        let mut values = vec![G::Scalar::zero(); statement.generators.g.len()];
+        for (i, value) in hidden.into_iter().zip(&mut secrets) {
+            values[i] = value;
+        }
+        for (i, value) in statement.indices.iter().zip(&statement.values) {
+            values[*i as usize] = *value;
+        }
+        Some(PedersenVectorOpening {
+            values,
+            blinding: secrets.next()?,
+        })
+    }
+}
+
+impl<G: Group> VectorCommitment for PedersenCommitment<G> {
+    type Key = PedersenVectorGenerators<G>;
+    type Value = G::Scalar;
+    type Opening = PedersenVectorOpening<G::Scalar>;
+    type Proof = VectorOpeningProof<G>;
+
+    fn commit_vector<R: RngCore + CryptoRng>(
+        generators: &PedersenVectorGenerators<G>,
+        values: &[G::Scalar],
+        rng: &mut R,
+    ) -> Option<(Self, PedersenVectorOpening<G::Scalar>)> {
+        if values.len() > generators.g.len() {
+            return None;
+        }
+        let blinding = G::Scalar::random(rng);
+        Some((
+            generators.commit(values, &blinding),
+            PedersenVectorOpening {
+                values: values.to_vec(),
+                blinding,
+            },
+        ))
+    }
+
+    fn open_at<R: RngCore + CryptoRng>(
+        &self,
+        generators: &PedersenVectorGenerators<G>,
+        opening: &PedersenVectorOpening<G::Scalar>,
+        indices: &[usize],
+        rng: &mut R,
+    ) -> Option<VectorOpeningProof<G>> {
+        let values = indices
+            .iter()
+            .map(|i| opening.values.get(*i).copied())
+            .collect::<Option<Vec<_>>>()?;
+        let statement = VectorOpeningStatement::new(generators, self, indices, &values)?;
+        Some(Proof::prove::<VectorOpening<G>>(&statement, opening, rng))
+    }
+
+    fn verify_at(
+        &self,
+        generators: &PedersenVectorGenerators<G>,
+        indices: &[usize],
+        values: &[G::Scalar],
+        proof: &VectorOpeningProof<G>,
+    ) -> bool {
+        match VectorOpeningStatement::new(generators, self, indices, values) {
+            Some(statement) => proof.verify::<VectorOpening<G>>(&statement),
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pedersen::DEFAULT_DOMAIN;
+    use crate::{run_protocol, HonestProver, HonestVerifier, DEFAULT_SECURITY};
+    use curve25519_dalek::ristretto::RistrettoPoint;
+    use curve25519_dalek::scalar::Scalar;
+
+    type G = RistrettoPoint;
+    type C = PedersenCommitment<G>;
+
+    fn setup(n: usize) -> (PedersenVectorGenerators<G>, Vec<Scalar>) {
+        let mut rng = rand::thread_rng();
+        (
+            PedersenVectorGenerators::new(DEFAULT_DOMAIN, n),
+            (0..n).map(|_| Scalar::random(&mut rng)).collect(),
+        )
+    }
+
+    #[test]
+    fn any_subset_opens_with_one_proof() {
+        let mut rng = rand::thread_rng();
+        let (generators, values) = setup(8);
+        let (commitment, opening) = C::commit_vector(&generators, &values, &mut rng).unwrap();
+        for indices in [vec![3], vec![6, 0, 2], (0..8).collect(), vec![]] {
+            let proof = commitment
+                .open_at(&generators, &opening, &indices, &mut rng)
+                .unwrap();
+            let claimed: Vec<Scalar> = indices.iter().map(|i| values[*i]).collect();
+            assert!(commitment.verify_at(&generators, &indices, &claimed, &proof));
+            assert_eq!(proof.response.len(), 8 - indices.len() + 1);
+        }
+    }
+
+    #[test]
+    fn wrong_values_and_positions_are_rejected() {
+        let mut rng = rand::thread_rng();
+        let (generators, values) = setup(6);
+        let (commitment, opening) = C::commit_vector(&generators, &values, &mut rng).unwrap();
+        let proof = commitment
+            .open_at(&generators, &opening, &[1, 4], &mut rng)
+            .unwrap();
+        assert!(commitment.verify_at(&generators, &[1, 4], &[values[1], values[4]], &proof));
+        assert!(commitment.verify_at(&generators, &[4, 1], &[values[4], values[1]], &proof));
+
+        assert!(!commitment.verify_at(&generators, &[1, 4], &[values[4], values[1]], &proof));
+        assert!(!commitment.verify_at(&generators, &[1, 4], &[values[1], Scalar::ONE], &proof));
+        assert!(!commitment.verify_at(&generators, &[1, 5], &[values[1], values[5]], &proof));
+        assert!(!commitment.verify_at(&generators, &[1], &[values[1]], &proof));
+        assert!(!commitment.verify_at(&generators, &[1, 1], &[values[1], values[1]], &proof));
+        assert!(commitment
+            .open_at(&generators, &opening, &[6], &mut rng)
+            .is_none());
+
+        let other = C::commit_vector(&generators, &values, &mut rng).unwrap().0;
+        assert!(!other.verify_at(&generators, &[1, 4], &[values[1], values[4]], &proof));
+    }
+
+    #[test]
+    fn short_vectors_are_padded_with_zeros() {
+        let mut rng = rand::thread_rng();
+        let (generators, values) = setup(4);
+        let (commitment, opening) = C::commit_vector(&generators, &values[..2], &mut rng).unwrap();
+        let proof = commitment
+            .open_at(&generators, &opening, &[1], &mut rng)
+            .unwrap();
+        assert!(commitment.verify_at(&generators, &[1], &[values[1]], &proof));
+        // The padding is not part of the opening, so it cannot be opened
+        assert!(commitment
+            .open_at(&generators, &opening, &[3], &mut rng)
+            .is_none());
+        // and a vector longer than the generators cannot be committed at all
+        let (_, longer) = setup(5);
+        assert!(C::commit_vector(&generators, &longer, &mut rng).is_none());
+    }
+
+    #[test]
+    fn interactive_simulated_and_extracted() {
+        let mut rng = rand::thread_rng();
+        let (generators, values) = setup(5);
+        let (commitment, opening) = C::commit_vector(&generators, &values, &mut rng).unwrap();
+        let statement =
+            VectorOpeningStatement::new(&generators, &commitment, &[2], &[values[2]]).unwrap();
+
+        let mut prover = HonestProver::<VectorOpening<G>>::new(statement.clone(), opening.clone());
+        let mut verifier = HonestVerifier::<VectorOpening<G>>::new(statement.clone());
+        assert!(run_protocol(&mut prover, &mut verifier, DEFAULT_SECURITY).is_accepted());
+
+        let challenge = Scalar::random(&mut rng);
+        let (t, response) = VectorOpening::simulate(&statement, &challenge, &mut rng);
+        assert!(VectorOpening::verify(&statement, &t, &challenge, &response));
+
+        let (t, nonces) = VectorOpening::commit(&statement, &opening, &mut rng);
+        let (c1, c2) = (Scalar::random(&mut rng), Scalar::random(&mut rng));
+        let r1 = VectorOpening::respond(&statement, &opening, nonces.clone(), &c1);
+        let r2 = VectorOpening::respond(&statement, &opening, nonces, &c2);
+        let extracted = VectorOpening::extract(&statement, &t, (&c1, &r1), (&c2, &r2));
+        assert_eq!(extracted, Some(opening));
+    }
+}
@@ -0,0 +1,128 @@
+/**
+ * Every cheating prover against every protocol's verifier: with challenges
+ * of over 250 bits none of them should ever get through
+ */
+use curve25519_dalek::ristretto::RistrettoPoint;
+use curve25519_dalek::scalar::Scalar;
+use zero_knowledge_proof::adversary::{
+    measure, AttackReport, ChallengeGuesser, MalformedCommitment, Replayer, WrongWitness,
+};
+use zero_knowledge_proof::membership::MembershipStatement;
+use zero_knowledge_proof::parity::{self, Parity, ParityWitness};
+use zero_knowledge_proof::range::{self, Range, RangeWitness};
+use zero_knowledge_proof::schnorr::{self, Schnorr};
+use zero_knowledge_proof::{
+    ChaumPedersen, Commitment, DleqStatement, Group, HonestVerifier, Membership, Opening, Or,
+    OrWitness, PedersenCommitment, PedersenOpening, PrimeField, Simulator, DEFAULT_SECURITY,
+};
+
+type G = RistrettoPoint;
+
+const TRIALS: usize = 20;
+
+fn assert_rejected(name: &str, report: AttackReport) {
+    assert_eq!(report.accepted, 0, "{} got through: {:?}", name, report);
+    assert!(report.soundness_bits >= DEFAULT_SECURITY);
+    assert!(report.is_within_bound());
+}
+
+// Run each adversary against a fresh verifier of protocol P
+fn attack<P>(statement: P::Statement, witness: P::Witness, wrong: P::Witness)
+where
+    P: Simulator,
+    P::Statement: Clone,
+    P::Witness: Clone,
+{
+    let mut rng = rand::thread_rng();
+    let mut verifier = HonestVerifier::<P>::new(statement.clone());
+
+    let mut guesser = ChallengeGuesser::<P>::new(statement.clone());
+    let report = measure(&mut guesser, &mut verifier, DEFAULT_SECURITY, TRIALS);
+    assert_rejected("guesser", report);
+
+    let mut replayer = Replayer::<P>::record(&statement, &witness, 4, &mut rng);
+    let report = measure(&mut replayer, &mut verifier, DEFAULT_SECURITY, TRIALS);
+    assert_rejected("replayer", report);
+
+    let mut malformed = MalformedCommitment::<P>::new(statement.clone(), witness);
+    let report = measure(&mut malformed, &mut verifier, DEFAULT_SECURITY, TRIALS);
+    assert_rejected("malformed commitment", report);
+
+    let mut wrong = WrongWitness::<P>::new(statement, wrong);
+    let report = measure(&mut wrong, &mut verifier, DEFAULT_SECURITY, TRIALS);
+    assert_rejected("wrong witness", report);
+}
+
+#[test]
+fn schnorr() {
+    let mut rng = rand::thread_rng();
+    let (secret, public_key) = schnorr::keypair::<G, _>(&mut rng);
+    attack::<Schnorr<G>>(public_key, secret, Scalar::random(&mut rng));
+}
+
+#[test]
+fn opening() {
+    let mut rng = rand::thread_rng();
+    let (commitment, opening) = PedersenCommitment::<G>::commit(Scalar::from_u64(9), &mut rng);
+    let wrong = PedersenOpening {
+        value: Scalar::from_u64(10),
+        ..opening
+    };
+    attack::<Opening<G>>(commitment, opening, wrong);
+}
+
+#[test]
+fn chaum_pedersen() {
+    let mut rng = rand::thread_rng();
+    let x = Scalar::random(&mut rng);
+    let h = G::hash_to_group(b"adversaries", b"h");
+    let statement = DleqStatement::new(G::generator(), h, &x);
+    attack::<ChaumPedersen<G>>(statement, x, x + Scalar::one());
+}
+
+#[test]
+fn parity() {
+    let mut rng = rand::thread_rng();
+    let (statement, witness) = parity::commit_value::<G, _>(1 << 20, &mut rng);
+    // Another even value, so the bit commitments recombine to the wrong point
+    let wrong = ParityWitness {
+        value: witness.value + 2,
+        ..witness
+    };
+    attack::<Parity<G>>(statement, witness, wrong);
+}
+
+#[test]
+fn or() {
+    let mut rng = rand::thread_rng();
+    let (secret, left_key) = schnorr::keypair::<G, _>(&mut rng);
+    let (_, right_key) = schnorr::keypair::<G, _>(&mut rng);
+    // A secret for neither key, whichever branch it claims
+    let wrong = OrWitness::Right(Scalar::random(&mut rng));
+    attack::<Or<Schnorr<G>, Schnorr<G>>>((left_key, right_key), OrWitness::Left(secret), wrong);
+}
+
+#[test]
+fn range() {
+    let mut rng = rand::thread_rng();
+    let (statement, witness) = range::commit_value::<G, _>(100, &mut rng);
+    // Another value in range does not open the commitment
+    let wrong = RangeWitness {
+        value: 101,
+        ..witness
+    };
+    attack::<Range<G, 8>>(statement, witness, wrong);
+}
+
+#[test]
+fn membership() {
+    let mut rng = rand::thread_rng();
+    let set: Vec<Scalar> = (0..6u64).map(Scalar::from_u64).collect();
+    let (commitment, opening) = PedersenCommitment::<G>::commit(set[3], &mut rng);
+    // Another member of the set does not open the commitment
+    let wrong = PedersenOpening {
+        value: set[4],
+        ..opening
+    };
+    attack::<Membership<G>>(MembershipStatement { commitment, set }, opening, wrong);
+}
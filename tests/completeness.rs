@@ -0,0 +1,267 @@
+/**
+ * Completeness: a prover who knows the witness is always accepted, at every
+ * security level, interactively and non-interactively
+ */
+use curve25519_dalek::ristretto::RistrettoPoint;
+use curve25519_dalek::scalar::Scalar;
+use rand::Rng;
+use zero_knowledge_proof::membership::MerkleMembershipStatement;
+use zero_knowledge_proof::parity::{self, Parity};
+use zero_knowledge_proof::range::{self, Range};
+use zero_knowledge_proof::schnorr::{self, Schnorr};
+use zero_knowledge_proof::{
+    run_protocol, And, BulletproofGenerators, Commitment, Fr, HashCommitment, HonestProver,
+    HonestVerifier, KzgSetup, KzgVectorCommitment, Membership, MembershipStatement,
+    MerkleMembership, MerkleSet, Opening, PedersenCommitment, PedersenGenerators,
+    PedersenVectorGenerators, Polynomial, PrimeField, Proof, RangeProof, Repeated,
+    VectorCommitment, DEFAULT_SECURITY,
+};
+
+type G = RistrettoPoint;
+
+// Security levels that need one, two and several rounds of a 252-bit challenge
+const LEVELS: [u32; 4] = [1, DEFAULT_SECURITY, 256, 1000];
+
+#[test]
+fn schnorr_interactive() {
+    for security in LEVELS {
+        let (secret, public_key) = schnorr::keypair::<G, _>(&mut rand::thread_rng());
+        let mut prover = HonestProver::<Schnorr<G>>::new(public_key, secret);
+        let mut verifier = HonestVerifier::<Schnorr<G>>::new(public_key);
+        let result = run_protocol(&mut prover, &mut verifier, security);
+        assert!(result.is_accepted());
+        assert!(result.soundness_bits >= security);
+    }
+}
+
+#[test]
+fn schnorr_non_interactive() {
+    let mut rng = rand::thread_rng();
+    let (secret, public_key) = schnorr::keypair::<G, _>(&mut rng);
+    let proof = Proof::prove::<Schnorr<G>>(&public_key, &secret, &mut rng);
+    assert!(proof.verify::<Schnorr<G>>(&public_key));
+
+    let proofs = Proof::prove_rounds::<Schnorr<G>>(&public_key, &secret, 3, &mut rng);
+    assert!(Proof::verify_rounds::<Schnorr<G>>(&public_key, &proofs));
+}
+
+#[test]
+fn parity_interactive() {
+    let mut rng = rand::thread_rng();
+    for value in [0, 2, u64::MAX - 1, rng.gen::<u64>() & !1] {
+        let (statement, witness) = parity::commit_value::<G, _>(value, &mut rng);
+        let mut prover = HonestProver::<Parity<G>>::new(statement, witness);
+        let mut verifier = HonestVerifier::<Parity<G>>::new(statement);
+        let result = run_protocol(&mut prover, &mut verifier, DEFAULT_SECURITY);
+        assert!(result.is_accepted(), "even value {} was rejected", value);
+    }
+}
+
+#[test]
+fn parity_non_interactive() {
+    let mut rng = rand::thread_rng();
+    let (statement, witness) = parity::commit_value::<G, _>(rng.gen::<u64>() & !1, &mut rng);
+    let proof = Proof::prove::<Parity<G>>(&statement, &witness, &mut rng);
+    assert!(proof.verify::<Parity<G>>(&statement));
+
+    let proofs = Proof::prove_rounds::<Parity<G>>(&statement, &witness, 2, &mut rng);
+    assert!(Proof::verify_rounds::<Parity<G>>(&statement, &proofs));
+}
+
+#[test]
+fn opening_interactive_and_non_interactive() {
+    let mut rng = rand::thread_rng();
+    let (commitment, opening) = PedersenCommitment::<G>::commit(Scalar::random(&mut rng), &mut rng);
+    let mut prover = HonestProver::<Opening<G>>::new(commitment, opening);
+    let mut verifier = HonestVerifier::<Opening<G>>::new(commitment);
+    assert!(run_protocol(&mut prover, &mut verifier, DEFAULT_SECURITY).is_accepted());
+
+    let proof = Proof::prove::<Opening<G>>(&commitment, &opening, &mut rng);
+    assert!(proof.verify::<Opening<G>>(&commitment));
+}
+
+#[test]
+fn commitments_open_to_their_values() {
+    let mut rng = rand::thread_rng();
+    let value = rng.gen::<u64>();
+
+    let (commitment, opening) = HashCommitment::commit(value, &mut rng);
+    assert_eq!(commitment.open(&opening), Some(value));
+
+    let scalar = Scalar::from_u64(value);
+    let (commitment, opening) = PedersenCommitment::<G>::commit(scalar, &mut rng);
+    assert_eq!(commitment.open(&opening), Some(scalar));
+}
+
+#[test]
+fn every_protocol_runs_over_secp256k1() {
+    type K = k256::ProjectivePoint;
+    let mut rng = rand::thread_rng();
+
+    let (secret, public_key) = schnorr::keypair::<K, _>(&mut rng);
+    let mut prover = HonestProver::<Schnorr<K>>::new(public_key, secret);
+    let mut verifier = HonestVerifier::<Schnorr<K>>::new(public_key);
+    assert!(run_protocol(&mut prover, &mut verifier, DEFAULT_SECURITY).is_accepted());
+
+    let value = k256::Scalar::random(&mut rng);
+    let (commitment, opening) = PedersenCommitment::<K>::commit(value, &mut rng);
+    let proof = Proof::prove::<Opening<K>>(&commitment, &opening, &mut rng);
+    assert!(proof.verify::<Opening<K>>(&commitment));
+
+    let (statement, witness) = parity::commit_value::<K, _>(rng.gen::<u64>() & !1, &mut rng);
+    let proof = Proof::prove::<Parity<K>>(&statement, &witness, &mut rng);
+    assert!(proof.verify::<Parity<K>>(&statement));
+}
+
+#[test]
+fn every_protocol_runs_over_a_safe_prime_group() {
+    use zero_knowledge_proof::group::modp::{Rfc3526Group14, Toy23, Toy64};
+    use zero_knowledge_proof::ModpGroup;
+    let mut rng = rand::thread_rng();
+
+    // Challenges mod 11 carry 3 bits each, so 128-bit security takes 43 rounds
+    type T = ModpGroup<Toy23>;
+    let (secret, public_key) = schnorr::keypair::<T, _>(&mut rng);
+    let mut prover = HonestProver::<Schnorr<T>>::new(public_key, secret);
+    let mut verifier = HonestVerifier::<Schnorr<T>>::new(public_key);
+    let result = run_protocol(&mut prover, &mut verifier, DEFAULT_SECURITY);
+    assert!(result.is_accepted());
+    assert_eq!(result.rounds, 43);
+
+    type M = ModpGroup<Rfc3526Group14>;
+    let (secret, public_key) = schnorr::keypair::<M, _>(&mut rng);
+    let proof = Proof::prove::<Schnorr<M>>(&public_key, &secret, &mut rng);
+    assert!(proof.verify::<Schnorr<M>>(&public_key));
+
+    // Parity needs an order well above 2^64, which only the full-size group has
+    let (statement, witness) = parity::commit_value::<M, _>(rng.gen::<u64>() & !1, &mut rng);
+    let proof = Proof::prove::<Parity<M>>(&statement, &witness, &mut rng);
+    assert!(proof.verify::<Parity<M>>(&statement));
+
+    type S = ModpGroup<Toy64>;
+    let (commitment, opening) =
+        PedersenCommitment::<S>::commit(PrimeField::random(&mut rng), &mut rng);
+    let mut prover = HonestProver::<Opening<S>>::new(commitment, opening);
+    let mut verifier = HonestVerifier::<Opening<S>>::new(commitment);
+    assert!(run_protocol(&mut prover, &mut verifier, DEFAULT_SECURITY).is_accepted());
+}
+
+#[test]
+fn composed_protocols_at_every_level() {
+    type P = Repeated<And<Schnorr<G>, Parity<G>>, 2>;
+    let mut rng = rand::thread_rng();
+    let (secret, public_key) = schnorr::keypair::<G, _>(&mut rng);
+    let (commitment, witness) = parity::commit_value::<G, _>(rng.gen::<u64>() & !1, &mut rng);
+    let statement = (public_key, commitment);
+    for &security in &LEVELS {
+        let mut prover = HonestProver::<P>::new(statement, (secret, witness));
+        let mut verifier = HonestVerifier::<P>::new(statement);
+        assert!(run_protocol(&mut prover, &mut verifier, security).is_accepted());
+    }
+    let proof = Proof::prove::<P>(&statement, &(secret, witness), &mut rng);
+    assert!(proof.verify::<P>(&statement));
+}
+
+#[test]
+fn range_proofs_for_common_bounds() {
+    let mut rng = rand::thread_rng();
+    let (statement, witness) = range::commit_value::<G, _>(u64::from(rng.gen::<u32>()), &mut rng);
+    let mut prover = HonestProver::<Range<G, 32>>::new(statement, witness);
+    let mut verifier = HonestVerifier::<Range<G, 32>>::new(statement);
+    assert!(run_protocol(&mut prover, &mut verifier, DEFAULT_SECURITY).is_accepted());
+
+    // age >= 18, for any age up to 18 + 2^8 - 1
+    let (age, opening) = range::commit_value::<G, _>(18 + rng.gen::<u8>() as u64, &mut rng);
+    let adult = range::at_least(&PedersenGenerators::default(), &age, 18).unwrap();
+    let proof = Proof::prove::<Range<G, 8>>(&adult, &opening.at_least(18).unwrap(), &mut rng);
+    assert!(proof.verify::<Range<G, 8>>(&adult));
+}
+
+#[test]
+fn bulletproofs_cover_the_same_commitments_as_bit_range_proofs() {
+    let mut rng = rand::thread_rng();
+    let generators = BulletproofGenerators::<G>::new(4 * 32);
+    let openings: Vec<_> = (0..4)
+        .map(|_| range::commit_value::<G, _>(u64::from(rng.gen::<u32>()), &mut rng))
+        .collect();
+    let values: Vec<u64> = openings.iter().map(|(_, opening)| opening.value).collect();
+    let blindings: Vec<Scalar> = openings
+        .iter()
+        .map(|(_, opening)| opening.blinding)
+        .collect();
+    let (proof, commitments) =
+        RangeProof::prove(&generators, &values, &blindings, 32, &mut rng).unwrap();
+    assert!(openings
+        .iter()
+        .zip(&commitments)
+        .all(|((statement, _), commitment)| statement == commitment));
+    assert!(proof.verify(&generators, &commitments, 32));
+}
+
+#[test]
+fn committed_values_are_members_of_small_and_large_sets() {
+    let mut rng = rand::thread_rng();
+    let set: Vec<Scalar> = (0..300u64).map(|i| Scalar::from(i * i)).collect();
+
+    let (commitment, opening) = PedersenCommitment::<G>::commit(set[7], &mut rng);
+    let statement = MembershipStatement {
+        commitment,
+        set: set[..10].to_vec(),
+    };
+    let mut prover = HonestProver::<Membership<G>>::new(statement.clone(), opening);
+    let mut verifier = HonestVerifier::<Membership<G>>::new(statement);
+    assert!(run_protocol(&mut prover, &mut verifier, DEFAULT_SECURITY).is_accepted());
+
+    let tree = MerkleSet::new(&set, 8);
+    let (commitment, opening) =
+        PedersenCommitment::<G>::commit(set[rng.gen_range(0..300)], &mut rng);
+    let statement = MerkleMembershipStatement {
+        commitment,
+        root: tree.root(),
+    };
+    let witness = tree.witness(&opening).unwrap();
+    let proof = Proof::prove::<MerkleMembership<G>>(&statement, &witness, &mut rng);
+    assert!(proof.verify::<MerkleMembership<G>>(&statement));
+}
+
+#[test]
+fn vector_commitments_open_any_subset() {
+    let mut rng = rand::thread_rng();
+    let generators = PedersenVectorGenerators::<G>::new(b"completeness", 10);
+    let values: Vec<Scalar> = (0..10).map(|_| Scalar::random(&mut rng)).collect();
+    let (commitment, opening) =
+        PedersenCommitment::commit_vector(&generators, &values, &mut rng).unwrap();
+    for _ in 0..5 {
+        let indices: Vec<usize> = (0..10).filter(|_| rng.gen()).collect();
+        let claimed: Vec<Scalar> = indices.iter().map(|i| values[*i]).collect();
+        let proof = commitment
+            .open_at(&generators, &opening, &indices, &mut rng)
+            .unwrap();
+        assert!(commitment.verify_at(&generators, &indices, &claimed, &proof));
+    }
+}
+
+#[test]
+fn kzg_opens_polynomials_and_vectors() {
+    let mut rng = rand::thread_rng();
+    let setup = KzgSetup::insecure_from_seed(b"completeness", 16, 4);
+    let polynomial = Polynomial::new((0..=16).map(|_| Fr::random(&mut rng)).collect());
+    let commitment = setup.commit(&polynomial).unwrap();
+    let point = Fr::random(&mut rng);
+    let (value, proof) = setup.open(&polynomial, &point).unwrap();
+    assert!(setup.verify(&commitment, &point, &value, &proof));
+    let points: Vec<Fr> = (0..4).map(|_| Fr::random(&mut rng)).collect();
+    let (values, proof) = setup.open_many(&polynomial, &points).unwrap();
+    assert!(setup.verify_many(&commitment, &points, &values, &proof));
+
+    let values: Vec<Fr> = (0..10).map(|_| Fr::random(&mut rng)).collect();
+    let (commitment, opening) =
+        KzgVectorCommitment::commit_vector(&setup, &values, &mut rng).unwrap();
+    for indices in [vec![0], vec![9], vec![1, 4, 7], vec![0, 3, 6, 9]] {
+        let claimed: Vec<Fr> = indices.iter().map(|i| values[*i]).collect();
+        let proof = commitment
+            .open_at(&setup, &opening, &indices, &mut rng)
+            .unwrap();
+        assert!(commitment.verify_at(&setup, &indices, &claimed, &proof));
+    }
+}
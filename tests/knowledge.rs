@@ -0,0 +1,153 @@
+/**
+ * Proof of knowledge: rewinding an honest prover and answering two challenges
+ * yields its witness, while rewinding a prover without one yields nothing
+ */
+use curve25519_dalek::ristretto::RistrettoPoint;
+use curve25519_dalek::scalar::Scalar;
+use rand::rngs::ThreadRng;
+use rand::Rng;
+use zero_knowledge_proof::extractor;
+use zero_knowledge_proof::membership::MerkleMembershipStatement;
+use zero_knowledge_proof::parity::{self, Parity};
+use zero_knowledge_proof::range::{self, Range};
+use zero_knowledge_proof::schnorr::{self, Schnorr};
+use zero_knowledge_proof::{
+    ChaumPedersen, Commitment, DleqStatement, Group, HonestProver, MerkleMembership, MerkleSet,
+    Opening, Or, OrWitness, PedersenCommitment, PedersenVectorGenerators, PrimeField, Prover,
+    Simulator, VectorCommitment, VectorOpening, VectorOpeningStatement,
+};
+
+type G = RistrettoPoint;
+
+// A Schnorr prover without the secret that prepares a transcript for one guessed challenge
+#[derive(Clone)]
+struct GuessingProver {
+    public_key: G,
+    response: Scalar,
+    rng: ThreadRng,
+}
+
+impl Prover for GuessingProver {
+    type Commitment = G;
+    type Challenge = Scalar;
+    type Response = Scalar;
+
+    fn commit(&mut self) -> G {
+        let guess = Scalar::random(&mut self.rng);
+        let (commitment, response) =
+            Schnorr::<G>::simulate(&self.public_key, &guess, &mut self.rng);
+        self.response = response;
+        commitment
+    }
+
+    fn respond(&mut self, _challenge: &Scalar) -> Scalar {
+        self.response
+    }
+}
+
+#[test]
+fn schnorr_secret_is_extracted() {
+    let mut rng = rand::thread_rng();
+    let (secret, public_key) = schnorr::keypair::<G, _>(&mut rng);
+    let mut prover = HonestProver::<Schnorr<G>>::new(public_key, secret);
+    let extracted = extractor::rewind::<Schnorr<G>, _>(&public_key, &mut prover, &mut rng);
+    assert_eq!(extracted, Some(secret));
+    assert_eq!(G::generator().scalar_mul(&secret), public_key);
+}
+
+#[test]
+fn pedersen_opening_is_extracted() {
+    let mut rng = rand::thread_rng();
+    let (commitment, opening) = PedersenCommitment::<G>::commit(Scalar::random(&mut rng), &mut rng);
+    let mut prover = HonestProver::<Opening<G>>::new(commitment, opening);
+    let extracted = extractor::rewind::<Opening<G>, _>(&commitment, &mut prover, &mut rng);
+    assert_eq!(extracted, Some(opening));
+}
+
+#[test]
+fn dleq_exponent_is_extracted() {
+    let mut rng = rand::thread_rng();
+    let x = Scalar::random(&mut rng);
+    let statement = DleqStatement::new(G::generator(), G::hash_to_group(b"pok", b"h"), &x);
+    let mut prover = HonestProver::<ChaumPedersen<G>>::new(statement, x);
+    let extracted = extractor::rewind::<ChaumPedersen<G>, _>(&statement, &mut prover, &mut rng);
+    assert_eq!(extracted, Some(x));
+}
+
+#[test]
+fn parity_value_is_extracted() {
+    let mut rng = rand::thread_rng();
+    let (statement, witness) = parity::commit_value::<G, _>(rng.gen::<u64>() & !1, &mut rng);
+    let mut prover = HonestProver::<Parity<G>>::new(statement, witness);
+    let extracted = extractor::rewind::<Parity<G>, _>(&statement, &mut prover, &mut rng);
+    assert_eq!(extracted, Some(witness));
+}
+
+#[test]
+fn or_witness_is_extracted_from_the_known_branch() {
+    type P = Or<Schnorr<G>, Schnorr<G>>;
+    let mut rng = rand::thread_rng();
+    let (_, left_key) = schnorr::keypair::<G, _>(&mut rng);
+    let (secret, right_key) = schnorr::keypair::<G, _>(&mut rng);
+    let statement = (left_key, right_key);
+    let mut prover = HonestProver::<P>::new(statement, OrWitness::Right(secret));
+    let extracted = extractor::rewind::<P, _>(&statement, &mut prover, &mut rng);
+    assert_eq!(extracted, Some(OrWitness::Right(secret)));
+}
+
+#[test]
+fn range_value_is_extracted() {
+    let mut rng = rand::thread_rng();
+    let (statement, witness) = range::commit_value::<G, _>(u64::from(rng.gen::<u16>()), &mut rng);
+    let mut prover = HonestProver::<Range<G, 16>>::new(statement, witness);
+    let extracted = extractor::rewind::<Range<G, 16>, _>(&statement, &mut prover, &mut rng);
+    assert_eq!(extracted, Some(witness));
+}
+
+#[test]
+fn nothing_is_extracted_from_a_cheater() {
+    let mut rng = rand::thread_rng();
+    let (_, public_key) = schnorr::keypair::<G, _>(&mut rng);
+    let mut prover = GuessingProver {
+        public_key,
+        response: Scalar::zero(),
+        rng: rand::thread_rng(),
+    };
+    for _ in 0..10 {
+        assert_eq!(
+            extractor::rewind::<Schnorr<G>, _>(&public_key, &mut prover, &mut rng),
+            None
+        );
+    }
+}
+
+#[test]
+fn member_opening_is_extracted() {
+    let mut rng = rand::thread_rng();
+    let set: Vec<Scalar> = (0..100).map(|_| Scalar::random(&mut rng)).collect();
+    let tree = MerkleSet::new(&set, 10);
+    let (commitment, opening) =
+        PedersenCommitment::<G>::commit(set[rng.gen_range(0..100)], &mut rng);
+    let statement = MerkleMembershipStatement {
+        commitment,
+        root: tree.root(),
+    };
+    let witness = tree.witness(&opening).unwrap();
+    let mut prover = HonestProver::<MerkleMembership<G>>::new(statement, witness.clone());
+    let extracted = extractor::rewind::<MerkleMembership<G>, _>(&statement, &mut prover, &mut rng);
+    assert_eq!(extracted, Some(witness));
+}
+
+#[test]
+fn hidden_vector_positions_are_extracted() {
+    let mut rng = rand::thread_rng();
+    let generators = PedersenVectorGenerators::<G>::new(b"knowledge", 6);
+    let values: Vec<Scalar> = (0..6).map(|_| Scalar::random(&mut rng)).collect();
+    let (commitment, opening) =
+        PedersenCommitment::commit_vector(&generators, &values, &mut rng).unwrap();
+    let statement =
+        VectorOpeningStatement::new(&generators, &commitment, &[4], &[values[4]]).unwrap();
+    let mut prover = HonestProver::<VectorOpening<G>>::new(statement.clone(), opening.clone());
+    let extracted = extractor::rewind::<VectorOpening<G>, _>(&statement, &mut prover, &mut rng);
+    assert_eq!(extracted, Some(opening));
+}
@@ -0,0 +1,258 @@
+/**
+ * Serialization: every proof, commitment and response survives a round trip
+ * through its byte encoding, still verifies afterwards, and malformed input
+ * is rejected rather than misread
+ */
+use curve25519_dalek::ristretto::RistrettoPoint;
+use rand::Rng;
+use zero_knowledge_proof::membership::MerkleMembershipStatement;
+use zero_knowledge_proof::merkle::{MultiProof, NonInclusionProof};
+use zero_knowledge_proof::parity::{self, Parity, ParityCommitment, ParityResponse};
+use zero_knowledge_proof::range::{self, Range};
+use zero_knowledge_proof::schnorr::{self, Schnorr};
+use zero_knowledge_proof::{
+    BulletproofGenerators, ChaumPedersen, Commitment, Decode, DleqStatement, Encode, Fr, Group,
+    KzgCommitment, KzgProof, KzgSetup, KzgVectorCommitment, Membership, MembershipStatement,
+    MerkleMembership, MerkleSet, MerkleTree, Opening, Or, OrWitness, PedersenCommitment,
+    PedersenVectorGenerators, Polynomial, PrimeField, Proof, RangeProof, SchnorrProof,
+    SortedMerkleTree, VectorCommitment, VectorOpeningStatement,
+};
+
+type G = RistrettoPoint;
+type Scalar = <G as Group>::Scalar;
+type ParityProof = Proof<ParityCommitment<G>, Scalar, ParityResponse<Scalar>>;
+
+// Encode, decode, and check that nothing was lost and truncation is caught
+fn round_trip<T: Encode + Decode + PartialEq + std::fmt::Debug>(value: &T) -> T {
+    let bytes = value.to_bytes();
+    let decoded = T::from_bytes(&bytes).expect("round trip");
+    assert_eq!(&decoded, value);
+    assert_eq!(decoded.to_bytes(), bytes);
+
+    assert!(T::from_bytes(&bytes[..bytes.len() - 1]).is_none());
+    let mut extended = bytes;
+    extended.push(0);
+    assert!(T::from_bytes(&extended).is_none());
+    decoded
+}
+
+#[test]
+fn schnorr_proof() {
+    let mut rng = rand::thread_rng();
+    let (secret, public_key) = schnorr::keypair::<G, _>(&mut rng);
+    let proof: SchnorrProof<G> = Proof::prove::<Schnorr<G>>(&public_key, &secret, &mut rng);
+    assert_eq!(proof.to_bytes().len(), 96);
+    assert!(round_trip(&proof).verify::<Schnorr<G>>(&public_key));
+
+    let proofs = Proof::prove_rounds::<Schnorr<G>>(&public_key, &secret, 3, &mut rng);
+    assert!(Proof::verify_rounds::<Schnorr<G>>(
+        &public_key,
+        &round_trip(&proofs)
+    ));
+}
+
+#[test]
+fn secp256k1_schnorr_proof() {
+    type K = k256::ProjectivePoint;
+    let mut rng = rand::thread_rng();
+    let (secret, public_key) = schnorr::keypair::<K, _>(&mut rng);
+    let proof: SchnorrProof<K> = Proof::prove::<Schnorr<K>>(&public_key, &secret, &mut rng);
+    // A 33-byte compressed point and two 32-byte scalars
+    assert_eq!(proof.to_bytes().len(), 97);
+    assert!(round_trip(&proof).verify::<Schnorr<K>>(&public_key));
+}
+
+#[test]
+fn parity_proof() {
+    let mut rng = rand::thread_rng();
+    let (statement, witness) = parity::commit_value::<G, _>(rng.gen::<u64>() & !1, &mut rng);
+    let proof: ParityProof = Proof::prove::<Parity<G>>(&statement, &witness, &mut rng);
+    assert!(round_trip(&proof).verify::<Parity<G>>(&statement));
+    round_trip(&proof.commitment);
+    round_trip(&proof.response);
+
+    let proofs = Proof::prove_rounds::<Parity<G>>(&statement, &witness, 2, &mut rng);
+    assert!(Proof::verify_rounds::<Parity<G>>(
+        &statement,
+        &round_trip(&proofs)
+    ));
+}
+
+#[test]
+fn opening_proof() {
+    let mut rng = rand::thread_rng();
+    let (commitment, opening) = PedersenCommitment::<G>::commit(Scalar::random(&mut rng), &mut rng);
+    let proof = Proof::prove::<Opening<G>>(&commitment, &opening, &mut rng);
+    assert_eq!(proof.to_bytes().len(), 128);
+    assert!(round_trip(&proof).verify::<Opening<G>>(&commitment));
+    round_trip(&opening);
+}
+
+#[test]
+fn chaum_pedersen_proof() {
+    let mut rng = rand::thread_rng();
+    let x = Scalar::random(&mut rng);
+    let statement = DleqStatement::new(G::generator(), G::hash_to_group(b"wire", b"h"), &x);
+    let proof = Proof::prove::<ChaumPedersen<G>>(&statement, &x, &mut rng);
+    // Two points and two scalars
+    assert_eq!(proof.to_bytes().len(), 128);
+    assert!(round_trip(&proof).verify::<ChaumPedersen<G>>(&round_trip(&statement)));
+}
+
+#[test]
+fn or_proof() {
+    type P = Or<Schnorr<G>, Schnorr<G>>;
+    let mut rng = rand::thread_rng();
+    let (secret, left_key) = schnorr::keypair::<G, _>(&mut rng);
+    let (_, right_key) = schnorr::keypair::<G, _>(&mut rng);
+    let statement = (left_key, right_key);
+    let proof = Proof::prove::<P>(&statement, &OrWitness::Left(secret), &mut rng);
+    // Two commitments, the challenge, the left share and two responses
+    assert_eq!(proof.to_bytes().len(), 192);
+    assert!(round_trip(&proof).verify::<P>(&round_trip(&statement)));
+}
+
+#[test]
+fn range_proof() {
+    let mut rng = rand::thread_rng();
+    let (statement, witness) = range::commit_value::<G, _>(200, &mut rng);
+    let proof = Proof::prove::<Range<G, 8>>(&statement, &witness, &mut rng);
+    // Per bit: a bit commitment, two OR commitments, a challenge share and two responses
+    assert_eq!(
+        proof.to_bytes().len(),
+        8 + 8 * 32 + 8 + 8 * 64 + 32 + 8 + 8 * 96
+    );
+    assert!(round_trip(&proof).verify::<Range<G, 8>>(&statement));
+}
+
+#[test]
+fn bulletproof() {
+    let mut rng = rand::thread_rng();
+    let generators = BulletproofGenerators::<G>::new(2 * 32);
+    let blindings = [Scalar::random(&mut rng), Scalar::random(&mut rng)];
+    let (proof, commitments) =
+        RangeProof::prove(&generators, &[200, 1 << 31], &blindings, 32, &mut rng).unwrap();
+    // Four points, three scalars, then log2(64) = 6 rounds of L and R and the two final scalars
+    assert_eq!(
+        proof.to_bytes().len(),
+        4 * 32 + 3 * 32 + 2 * (8 + 6 * 32) + 2 * 32
+    );
+    assert!(round_trip(&proof).verify(&generators, &commitments, 32));
+}
+
+#[test]
+fn membership_proofs() {
+    let mut rng = rand::thread_rng();
+    let set: Vec<Scalar> = (0..40u64).map(Scalar::from_u64).collect();
+    let (commitment, opening) = PedersenCommitment::<G>::commit(set[25], &mut rng);
+
+    let statement = MembershipStatement {
+        commitment,
+        set: set[20..30].to_vec(),
+    };
+    let proof = Proof::prove::<Membership<G>>(&statement, &opening, &mut rng);
+    // Ten branch commitments, the challenge, nine shares and ten responses
+    assert_eq!(
+        proof.to_bytes().len(),
+        8 + 10 * 32 + 32 + 8 + 9 * 32 + 8 + 10 * 32
+    );
+    assert!(round_trip(&proof).verify::<Membership<G>>(&round_trip(&statement)));
+
+    let tree = MerkleSet::new(&set, 8);
+    let statement = MerkleMembershipStatement {
+        commitment,
+        root: tree.root(),
+    };
+    let witness = tree.witness(&opening).unwrap();
+    let proof = Proof::prove::<MerkleMembership<G>>(&statement, &witness, &mut rng);
+    assert!(round_trip(&proof).verify::<MerkleMembership<G>>(&round_trip(&statement)));
+}
+
+#[test]
+fn merkle_openings() {
+    let records: Vec<Vec<u8>> = (0..100u64).map(|i| (i, i * i).to_bytes()).collect();
+    let tree = MerkleTree::new(&records);
+    let proof = tree.prove_many(&[3, 50, 51, 99]).unwrap();
+    let opened = [&records[3], &records[50], &records[51], &records[99]];
+    assert!(round_trip(&proof).verify(&tree.root(), &opened));
+    assert!(MultiProof::from_bytes(&[0xff; 24]).is_none());
+
+    let sorted = SortedMerkleTree::new(&records);
+    let absent = (7u64, 50u64).to_bytes();
+    let proof = sorted.prove_non_inclusion(&absent).unwrap();
+    assert!(round_trip(&proof).verify(&sorted.root(), &absent));
+    let mut bytes = proof.to_bytes();
+    // The lower neighbour's tag must be 0 or 1
+    bytes[8] = 2;
+    assert!(NonInclusionProof::from_bytes(&bytes).is_none());
+}
+
+#[test]
+fn vector_opening_proof() {
+    let mut rng = rand::thread_rng();
+    let generators = PedersenVectorGenerators::<G>::new(b"serialization", 6);
+    let values: Vec<Scalar> = (0..6).map(|_| Scalar::random(&mut rng)).collect();
+    let (commitment, opening) =
+        PedersenCommitment::commit_vector(&generators, &values, &mut rng).unwrap();
+    let proof = commitment
+        .open_at(&generators, &opening, &[0, 5], &mut rng)
+        .unwrap();
+    // The commitment, the challenge, and four hidden values plus the blinding
+    assert_eq!(proof.to_bytes().len(), 32 + 32 + 8 + 5 * 32);
+    let decoded = round_trip(&proof);
+    assert!(commitment.verify_at(&generators, &[0, 5], &[values[0], values[5]], &decoded));
+    round_trip(&opening);
+    round_trip(&VectorOpeningStatement::new(&generators, &commitment, &[5], &[values[5]]).unwrap());
+}
+
+#[test]
+fn kzg_commitments_and_proofs() {
+    let mut rng = rand::thread_rng();
+    let setup = KzgSetup::insecure_from_seed(b"serialization", 8, 3);
+    let polynomial = Polynomial::new((0..=8).map(|_| Fr::random(&mut rng)).collect());
+    let commitment = setup.commit(&polynomial).unwrap();
+    let points = [Fr::from_u64(1), Fr::from_u64(2), Fr::from_u64(3)];
+    let (values, proof) = setup.open_many(&polynomial, &points).unwrap();
+    // One compressed G1 point each, however many points are opened
+    assert_eq!(commitment.to_bytes().len(), 48);
+    assert_eq!(proof.to_bytes().len(), 48);
+    let decoded = round_trip(&proof);
+    assert!(setup.verify_many(&round_trip(&commitment), &points, &values, &decoded));
+    round_trip(&polynomial);
+    let (vector, _) =
+        KzgVectorCommitment::commit_vector(&setup, &polynomial.coefficients, &mut rng).unwrap();
+    // The point and the vector's length
+    assert_eq!(vector.to_bytes().len(), 48 + 8);
+    round_trip(&vector);
+
+    // Bytes that are not the compressed form of a point are rejected
+    assert!(KzgCommitment::from_bytes(&[0xff; 48]).is_none());
+    assert!(KzgProof::from_bytes(&[0; 48]).is_none());
+    // A trailing zero coefficient is not the canonical form of a polynomial
+    let mut padded = polynomial.coefficients.clone();
+    padded.push(Fr::zero());
+    assert!(Polynomial::from_bytes(&padded.to_bytes()).is_none());
+}
+
+#[test]
+fn statements_and_scalars() {
+    let mut rng = rand::thread_rng();
+    let (commitment, _) = PedersenCommitment::<G>::commit(PrimeField::random(&mut rng), &mut rng);
+    round_trip(&commitment);
+    round_trip(&Fr::random(&mut rng));
+    round_trip(&rng.gen::<u64>());
+}
+
+#[test]
+fn non_canonical_encodings_are_rejected() {
+    // The group order does not fit in a canonical scalar, and 0xff.. is no point
+    assert!(Fr::from_bytes(&[0xff; 32]).is_none());
+    assert!(PedersenCommitment::<G>::from_bytes(&[0xff; 32]).is_none());
+
+    // A proof whose commitment is not a point fails to decode at all
+    let mut rng = rand::thread_rng();
+    let (secret, public_key) = schnorr::keypair::<G, _>(&mut rng);
+    let mut bytes = Proof::prove::<Schnorr<G>>(&public_key, &secret, &mut rng).to_bytes();
+    bytes[..32].copy_from_slice(&[0xff; 32]);
+    assert!(SchnorrProof::<G>::from_bytes(&bytes).is_none());
+}
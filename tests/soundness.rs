@@ -0,0 +1,297 @@
+/**
+ * Soundness: a prover without a witness is rejected, and the driver's reported
+ * cheating probability is at most 2^-λ for the security parameter it was given
+ */
+use curve25519_dalek::ristretto::RistrettoPoint;
+use curve25519_dalek::scalar::Scalar;
+use rand::rngs::ThreadRng;
+use zero_knowledge_proof::membership::MerkleMembershipStatement;
+use zero_knowledge_proof::parity::{self, Parity};
+use zero_knowledge_proof::range::{self, Range, RangeWitness};
+use zero_knowledge_proof::schnorr::{self, Schnorr};
+use zero_knowledge_proof::{
+    run_protocol, BulletproofGenerators, Commitment, Fr, Group, HashCommitment, HashOpening,
+    HonestProver, HonestVerifier, KzgSetup, KzgVectorCommitment, Membership, MembershipStatement,
+    MerkleMembership, MerkleSet, Opening, PedersenCommitment, PedersenGenerators, PedersenOpening,
+    PedersenVectorGenerators, PrimeField, Proof, Prover, RangeProof, VectorCommitment,
+    VectorOpening, VectorOpeningStatement, DEFAULT_SECURITY,
+};
+
+type G = RistrettoPoint;
+
+// A Schnorr prover without the secret: it guesses the challenge c in advance and
+// commits to g^s y^-c, which only verifies if the verifier picks exactly c
+struct GuessingProver {
+    public_key: G,
+    guess: Scalar,
+    response: Scalar,
+    rng: ThreadRng,
+}
+
+impl Prover for GuessingProver {
+    type Commitment = G;
+    type Challenge = Scalar;
+    type Response = Scalar;
+
+    fn commit(&mut self) -> G {
+        self.guess = Scalar::random(&mut self.rng);
+        self.response = Scalar::random(&mut self.rng);
+        G::generator().scalar_mul(&self.response) - self.public_key.scalar_mul(&self.guess)
+    }
+
+    fn respond(&mut self, _challenge: &Scalar) -> Scalar {
+        self.response
+    }
+}
+
+#[test]
+fn schnorr_rejects_a_wrong_secret() {
+    let mut rng = rand::thread_rng();
+    let (_, public_key) = schnorr::keypair::<G, _>(&mut rng);
+    let mut prover = HonestProver::<Schnorr<G>>::new(public_key, Scalar::random(&mut rng));
+    let mut verifier = HonestVerifier::<Schnorr<G>>::new(public_key);
+    let result = run_protocol(&mut prover, &mut verifier, DEFAULT_SECURITY);
+    assert!(!result.is_accepted());
+    assert!(result.soundness_bits >= DEFAULT_SECURITY);
+}
+
+#[test]
+fn schnorr_rejects_a_challenge_guesser() {
+    let (_, public_key) = schnorr::keypair::<G, _>(&mut rand::thread_rng());
+    let mut prover = GuessingProver {
+        public_key,
+        guess: Scalar::zero(),
+        response: Scalar::zero(),
+        rng: rand::thread_rng(),
+    };
+    let mut verifier = HonestVerifier::<Schnorr<G>>::new(public_key);
+    for _ in 0..20 {
+        let result = run_protocol(&mut prover, &mut verifier, DEFAULT_SECURITY);
+        assert!(!result.is_accepted());
+        assert!(result.cheating_probability() <= 2f64.powi(-(DEFAULT_SECURITY as i32)));
+    }
+}
+
+#[test]
+fn schnorr_proof_does_not_transfer() {
+    let mut rng = rand::thread_rng();
+    let (secret, public_key) = schnorr::keypair::<G, _>(&mut rng);
+    let (_, other_key) = schnorr::keypair::<G, _>(&mut rng);
+    let proof = Proof::prove::<Schnorr<G>>(&public_key, &secret, &mut rng);
+    assert!(!proof.verify::<Schnorr<G>>(&other_key));
+
+    // A valid transcript with a challenge the hash did not produce is rejected
+    let mut forged = proof.clone();
+    forged.challenge += Scalar::one();
+    forged.response += secret;
+    assert!(!forged.verify::<Schnorr<G>>(&public_key));
+
+    // Rounds cannot be reordered or dropped
+    let mut proofs = Proof::prove_rounds::<Schnorr<G>>(&public_key, &secret, 3, &mut rng);
+    proofs.swap(0, 1);
+    assert!(!Proof::verify_rounds::<Schnorr<G>>(&public_key, &proofs));
+    proofs.swap(0, 1);
+    proofs.pop();
+    assert!(!Proof::verify_rounds::<Schnorr<G>>(&public_key, &proofs));
+    assert!(!Proof::verify_rounds::<Schnorr<G>>(&public_key, &[]));
+}
+
+#[test]
+fn parity_rejects_an_odd_value() {
+    let mut rng = rand::thread_rng();
+    for value in [1, 3, u64::MAX] {
+        let (statement, witness) = parity::commit_value::<G, _>(value, &mut rng);
+        let mut prover = HonestProver::<Parity<G>>::new(statement, witness);
+        let mut verifier = HonestVerifier::<Parity<G>>::new(statement);
+        let result = run_protocol(&mut prover, &mut verifier, DEFAULT_SECURITY);
+        assert!(!result.is_accepted(), "odd value {} was accepted", value);
+
+        let proof = Proof::prove::<Parity<G>>(&statement, &witness, &mut rng);
+        assert!(!proof.verify::<Parity<G>>(&statement));
+    }
+}
+
+#[test]
+fn parity_rejects_a_witness_for_another_commitment() {
+    let mut rng = rand::thread_rng();
+    let (statement, _) = parity::commit_value::<G, _>(1, &mut rng);
+    let (_, witness) = parity::commit_value::<G, _>(2, &mut rng);
+    let mut prover = HonestProver::<Parity<G>>::new(statement, witness);
+    let mut verifier = HonestVerifier::<Parity<G>>::new(statement);
+    assert!(!run_protocol(&mut prover, &mut verifier, DEFAULT_SECURITY).is_accepted());
+}
+
+#[test]
+fn opening_rejects_a_wrong_opening() {
+    let mut rng = rand::thread_rng();
+    let (commitment, opening) = PedersenCommitment::<G>::commit(Scalar::from_u64(7), &mut rng);
+    let lie = PedersenOpening {
+        blinding: opening.blinding + Scalar::one(),
+        ..opening
+    };
+    let mut prover = HonestProver::<Opening<G>>::new(commitment, lie);
+    let mut verifier = HonestVerifier::<Opening<G>>::new(commitment);
+    assert!(!run_protocol(&mut prover, &mut verifier, DEFAULT_SECURITY).is_accepted());
+    assert!(
+        !Proof::prove::<Opening<G>>(&commitment, &lie, &mut rng).verify::<Opening<G>>(&commitment)
+    );
+}
+
+#[test]
+fn commitments_are_binding() {
+    let mut rng = rand::thread_rng();
+
+    let (commitment, opening) = HashCommitment::commit(7, &mut rng);
+    let lie = HashOpening {
+        value: 8,
+        ..opening
+    };
+    assert_eq!(commitment.open(&lie), None);
+
+    let (commitment, opening) = PedersenCommitment::<G>::commit(Scalar::from_u64(7), &mut rng);
+    let lie = PedersenOpening {
+        value: Scalar::from_u64(8),
+        ..opening
+    };
+    assert_eq!(commitment.open(&lie), None);
+}
+
+#[test]
+fn range_rejects_a_value_out_of_range() {
+    let mut rng = rand::thread_rng();
+    for value in [256, 1 << 40, u64::MAX] {
+        let (statement, witness) = range::commit_value::<G, _>(value, &mut rng);
+        let mut prover = HonestProver::<Range<G, 8>>::new(statement, witness);
+        let mut verifier = HonestVerifier::<Range<G, 8>>::new(statement);
+        assert!(!run_protocol(&mut prover, &mut verifier, DEFAULT_SECURITY).is_accepted());
+    }
+
+    // Under 18: the shifted value wraps around to a huge number
+    let (age, opening) = range::commit_value::<G, _>(17, &mut rng);
+    let adult = range::at_least(&PedersenGenerators::default(), &age, 18).unwrap();
+    let wrapped = RangeWitness {
+        value: 17u64.wrapping_sub(18),
+        ..opening
+    };
+    let proof = Proof::prove::<Range<G, 8>>(&adult, &wrapped, &mut rng);
+    assert!(!proof.verify::<Range<G, 8>>(&adult));
+}
+
+#[test]
+fn bulletproofs_do_not_carry_over_to_other_commitments() {
+    let mut rng = rand::thread_rng();
+    let generators = BulletproofGenerators::<G>::new(2 * 8);
+    let blindings = [Scalar::random(&mut rng), Scalar::random(&mut rng)];
+    assert!(RangeProof::prove(&generators, &[255, 256], &blindings, 8, &mut rng).is_none());
+
+    // A proof for 255 and 0 does not cover 256 = 255 + 1 under the same blinding
+    let (proof, commitments) =
+        RangeProof::prove(&generators, &[255, 0], &blindings, 8, &mut rng).unwrap();
+    let shifted = PedersenCommitment {
+        point: commitments[0].point + generators.pedersen.g,
+    };
+    assert!(!proof.verify(&generators, &[shifted, commitments[1]], 8));
+    // Nor is it a proof about 16-bit values or about either commitment alone
+    assert!(!proof.verify(&generators, &commitments, 16));
+    assert!(!proof.verify(&generators, &commitments[..1], 8));
+}
+
+#[test]
+fn bulletproofs_with_too_many_rounds_are_rejected() {
+    let mut rng = rand::thread_rng();
+    let generators = BulletproofGenerators::<G>::new(8);
+    let (mut proof, commitments) =
+        RangeProof::prove(&generators, &[42], &[Scalar::random(&mut rng)], 8, &mut rng).unwrap();
+    // 2^64 does not fit a usize, so the round count must not be shifted into one
+    for rounds in [64, 65, 200] {
+        proof.inner_product.left = vec![G::generator(); rounds];
+        proof.inner_product.right = vec![G::generator(); rounds];
+        assert!(!proof.verify(&generators, &commitments, 8));
+    }
+}
+
+#[test]
+fn membership_rejects_a_value_outside_the_set() {
+    let mut rng = rand::thread_rng();
+    let set: Vec<Scalar> = (1..=50u64).map(|i| Scalar::from(2 * i)).collect();
+    let (commitment, opening) = PedersenCommitment::<G>::commit(Scalar::from(51u64), &mut rng);
+    let statement = MembershipStatement {
+        commitment,
+        set: set.clone(),
+    };
+    let mut prover = HonestProver::<Membership<G>>::new(statement.clone(), opening);
+    let mut verifier = HonestVerifier::<Membership<G>>::new(statement);
+    assert!(!run_protocol(&mut prover, &mut verifier, DEFAULT_SECURITY).is_accepted());
+
+    // The outsider cannot borrow a member's block and path either
+    let tree = MerkleSet::new(&set, 4);
+    let (member, member_opening) = PedersenCommitment::<G>::commit(set[20], &mut rng);
+    let mut witness = tree.witness(&member_opening).unwrap();
+    witness.opening = opening;
+    let statement = MerkleMembershipStatement {
+        commitment,
+        root: tree.root(),
+    };
+    let proof = Proof::prove::<MerkleMembership<G>>(&statement, &witness, &mut rng);
+    assert!(!proof.verify::<MerkleMembership<G>>(&statement));
+    assert!(
+        !proof.verify::<MerkleMembership<G>>(&MerkleMembershipStatement {
+            commitment: member,
+            ..statement
+        })
+    );
+}
+
+#[test]
+fn vector_openings_cannot_change_a_value() {
+    let mut rng = rand::thread_rng();
+    let generators = PedersenVectorGenerators::<G>::new(b"soundness", 4);
+    let values: Vec<Scalar> = (1..=4u64).map(Scalar::from).collect();
+    let (commitment, mut opening) =
+        PedersenCommitment::commit_vector(&generators, &values, &mut rng).unwrap();
+
+    // A prover who claims 5 at position 2 still only knows the opening with 3 there
+    let statement =
+        VectorOpeningStatement::new(&generators, &commitment, &[2], &[Scalar::from(5u64)]).unwrap();
+    let mut prover = HonestProver::<VectorOpening<G>>::new(statement.clone(), opening.clone());
+    let mut verifier = HonestVerifier::<VectorOpening<G>>::new(statement.clone());
+    assert!(!run_protocol(&mut prover, &mut verifier, DEFAULT_SECURITY).is_accepted());
+    opening.values[2] = Scalar::from(5u64);
+    let proof = Proof::prove::<VectorOpening<G>>(&statement, &opening, &mut rng);
+    assert!(!proof.verify::<VectorOpening<G>>(&statement));
+    assert!(!commitment.verify_at(&generators, &[2], &[Scalar::from(5u64)], &proof));
+}
+
+#[test]
+fn kzg_openings_cannot_change_a_value() {
+    let mut rng = rand::thread_rng();
+    let setup = KzgSetup::insecure_from_seed(b"soundness", 8, 2);
+    let values: Vec<Fr> = (1..=4u64).map(Fr::from_u64).collect();
+    let (commitment, mut opening) =
+        KzgVectorCommitment::commit_vector(&setup, &values, &mut rng).unwrap();
+
+    // An honest proof for the committed vector says nothing about another one
+    let proof = commitment
+        .open_at(&setup, &opening, &[1, 2], &mut rng)
+        .unwrap();
+    let forged = [Fr::from_u64(2), Fr::from_u64(5)];
+    assert!(!commitment.verify_at(&setup, &[1, 2], &forged, &proof));
+    assert!(!commitment.verify_at(&setup, &[2, 1], &[values[1], values[2]], &proof));
+
+    // Nor does a proof built from a different polynomial
+    opening.polynomial = opening.polynomial.scale(&Fr::from_u64(2));
+    let proof = commitment
+        .open_at(&setup, &opening, &[1, 2], &mut rng)
+        .unwrap();
+    assert!(!commitment.verify_at(&setup, &[1, 2], &[Fr::from_u64(4), Fr::from_u64(6)], &proof));
+
+    // A proof made under another setup does not carry over. The vector above
+    // lies on a line, whose quotient is a constant under every τ, so use cubes.
+    let values: Vec<Fr> = (1..=4u64).map(|i| Fr::from_u64(i * i * i)).collect();
+    let (commitment, _) = KzgVectorCommitment::commit_vector(&setup, &values, &mut rng).unwrap();
+    let other = KzgSetup::insecure_from_seed(b"other", 8, 2);
+    let (moved, opening) = KzgVectorCommitment::commit_vector(&other, &values, &mut rng).unwrap();
+    let proof = moved.open_at(&other, &opening, &[0], &mut rng).unwrap();
+    assert!(moved.verify_at(&other, &[0], &values[..1], &proof));
+    assert!(!commitment.verify_at(&setup, &[0], &values[..1], &proof));
+}
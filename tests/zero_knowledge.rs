@@ -0,0 +1,109 @@
+/**
+ * Zero knowledge: for every protocol, transcripts from the simulator, which
+ * never sees the witness, verify and cannot be told apart from real ones
+ */
+use curve25519_dalek::ristretto::RistrettoPoint;
+use rand::Rng;
+use zero_knowledge_proof::membership::MembershipStatement;
+use zero_knowledge_proof::parity::{self, Parity};
+use zero_knowledge_proof::range::{self, Range};
+use zero_knowledge_proof::schnorr::{self, Schnorr};
+use zero_knowledge_proof::{
+    simulator, And, ChaumPedersen, Commitment, DleqStatement, Group, Membership, Opening, Or,
+    OrWitness, PedersenCommitment, PedersenVectorGenerators, PrimeField, Repeated,
+    VectorCommitment, VectorOpening, VectorOpeningStatement,
+};
+
+type G = RistrettoPoint;
+
+#[test]
+fn schnorr_transcripts_are_simulatable() {
+    let mut rng = rand::thread_rng();
+    let (secret, public_key) = schnorr::keypair::<G, _>(&mut rng);
+    let result = simulator::compare::<Schnorr<G>>(&public_key, &secret, 500, &mut rng);
+    assert!(result.is_indistinguishable(), "{:?}", result);
+}
+
+#[test]
+fn opening_transcripts_are_simulatable() {
+    let mut rng = rand::thread_rng();
+    let (commitment, opening) =
+        PedersenCommitment::<G>::commit(PrimeField::random(&mut rng), &mut rng);
+    let result = simulator::compare::<Opening<G>>(&commitment, &opening, 500, &mut rng);
+    assert!(result.is_indistinguishable(), "{:?}", result);
+}
+
+#[test]
+fn chaum_pedersen_transcripts_are_simulatable() {
+    let mut rng = rand::thread_rng();
+    let x = PrimeField::random(&mut rng);
+    let statement = DleqStatement::new(G::generator(), G::hash_to_group(b"zk", b"h"), &x);
+    let result = simulator::compare::<ChaumPedersen<G>>(&statement, &x, 500, &mut rng);
+    assert!(result.is_indistinguishable(), "{:?}", result);
+}
+
+#[test]
+fn parity_transcripts_are_simulatable() {
+    let mut rng = rand::thread_rng();
+    // A fixed witness is the worst case for a leak: every real transcript shares it
+    let (statement, witness) = parity::commit_value::<G, _>(rng.gen::<u64>() & !1, &mut rng);
+    let result = simulator::compare::<Parity<G>>(&statement, &witness, 100, &mut rng);
+    assert!(result.is_indistinguishable(), "{:?}", result);
+}
+
+#[test]
+fn or_transcripts_hide_the_known_branch() {
+    type P = Or<Schnorr<G>, Schnorr<G>>;
+    let mut rng = rand::thread_rng();
+    let (left_secret, left_key) = schnorr::keypair::<G, _>(&mut rng);
+    let (right_secret, right_key) = schnorr::keypair::<G, _>(&mut rng);
+    // Real transcripts from either branch look like simulated ones, and so like each other
+    for witness in [OrWitness::Left(left_secret), OrWitness::Right(right_secret)] {
+        let result = simulator::compare::<P>(&(left_key, right_key), &witness, 500, &mut rng);
+        assert!(result.is_indistinguishable(), "{:?}", result);
+    }
+}
+
+#[test]
+fn composed_transcripts_are_simulatable() {
+    type P = Repeated<And<Schnorr<G>, Opening<G>>, 2>;
+    let mut rng = rand::thread_rng();
+    let (secret, public_key) = schnorr::keypair::<G, _>(&mut rng);
+    let (commitment, opening) =
+        PedersenCommitment::<G>::commit(PrimeField::random(&mut rng), &mut rng);
+    let statement = (public_key, commitment);
+    let result = simulator::compare::<P>(&statement, &(secret, opening), 300, &mut rng);
+    assert!(result.is_indistinguishable(), "{:?}", result);
+}
+
+#[test]
+fn range_transcripts_are_simulatable() {
+    let mut rng = rand::thread_rng();
+    let (statement, witness) = range::commit_value::<G, _>(u64::from(rng.gen::<u8>()), &mut rng);
+    let result = simulator::compare::<Range<G, 8>>(&statement, &witness, 200, &mut rng);
+    assert!(result.is_indistinguishable(), "{:?}", result);
+}
+
+#[test]
+fn membership_transcripts_are_simulatable() {
+    let mut rng = rand::thread_rng();
+    let set: Vec<_> = (0..8).map(|_| PrimeField::random(&mut rng)).collect();
+    let (commitment, opening) = PedersenCommitment::<G>::commit(set[rng.gen_range(0..8)], &mut rng);
+    let statement = MembershipStatement { commitment, set };
+    let result = simulator::compare::<Membership<G>>(&statement, &opening, 200, &mut rng);
+    assert!(result.is_indistinguishable(), "{:?}", result);
+}
+
+#[test]
+fn vector_opening_transcripts_are_simulatable() {
+    let mut rng = rand::thread_rng();
+    let generators = PedersenVectorGenerators::<G>::new(b"zero knowledge", 5);
+    let values: Vec<_> = (0..5).map(|_| PrimeField::random(&mut rng)).collect();
+    let (commitment, opening) =
+        PedersenCommitment::commit_vector(&generators, &values, &mut rng).unwrap();
+    let statement =
+        VectorOpeningStatement::new(&generators, &commitment, &[1, 3], &[values[1], values[3]])
+            .unwrap();
+    let result = simulator::compare::<VectorOpening<G>>(&statement, &opening, 200, &mut rng);
+    assert!(result.is_indistinguishable(), "{:?}", result);
+}